@@ -3,6 +3,8 @@
 
 use std::io;
 
+use chaste_types::{ErrorKind, Locate, Located, Position};
+
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum Error {
@@ -35,6 +37,57 @@ pub enum Error {
 
     #[error("Chaste error: {0:?}")]
     SSRIError(#[from] chaste_types::ssri::Error),
+
+    #[error(transparent)]
+    At(#[from] Located<Box<Error>>),
+}
+
+impl Error {
+    /// What went wrong, as an [`ErrorKind`] shared with the other implementations.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::UnknownLockfileVersion(_) => ErrorKind::UnsupportedVersion,
+            Error::InvalidKey(_)
+            | Error::InvalidDescriptor(_)
+            | Error::InvalidVariant(_)
+            | Error::VariantMarkerMismatch(_)
+            | Error::JSONCError(_) => ErrorKind::MalformedSyntax,
+            Error::DependencyNotFound(_) => ErrorKind::DanglingReference,
+            Error::IOError(_) => ErrorKind::Io,
+            Error::ChasteError(e) => e.kind(),
+            Error::SSRIError(_) => ErrorKind::InvalidChecksum,
+            Error::At(located) => located.source.kind(),
+        }
+    }
+
+    /// Where the syntax error is, if the lockfile could not be deserialized.
+    pub fn position(&self) -> Option<Position> {
+        match self.inner() {
+            Error::JSONCError(json5::Error::Message {
+                location: Some(l), ..
+            }) => Some(Position {
+                line: l.line,
+                column: l.column,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Locate for Error {
+    fn located(&self) -> Option<&Located<Box<Error>>> {
+        match self {
+            Error::At(located) => Some(located),
+            _ => None,
+        }
+    }
+
+    fn into_located(self) -> Result<Located<Box<Error>>, Error> {
+        match self {
+            Error::At(located) => Ok(located),
+            e => Err(e),
+        }
+    }
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
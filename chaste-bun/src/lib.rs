@@ -2,14 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
 
 use std::collections::{HashMap, HashSet};
-use std::fs;
 use std::path::Path;
 
 use chaste_types::{
     package_name_str, Chastefile, ChastefileBuilder, Checksums, DependencyBuilder, DependencyKind,
-    InstallationBuilder, Integrity, LockfileVersion, ModulePath, PackageBuilder, PackageDerivation,
+    FileSource, InstallationBuilder, Integrity, Locate as _, LockfileVersion, ModulePath,
+    OverrideBuilder, OverrideSelector, PackageBuilder, PackageDerivation,
     PackageDerivationMetaBuilder, PackageID, PackageName, PackagePatchBuilder, PackageSource,
-    ProviderMeta, SourceVersionSpecifier, SourceVersionSpecifierKind,
+    ProviderMeta, RealFileSource, SourceVersionSpecifier, SourceVersionSpecifierKind, Warnings,
 };
 use nom::{
     bytes::complete::tag,
@@ -20,6 +20,7 @@ use nom::{
 };
 
 pub use crate::error::{Error, Result};
+pub use crate::prune::write_pruned;
 use crate::types::LockPackageElement;
 
 #[cfg(feature = "fuzzing")]
@@ -29,6 +30,7 @@ pub use crate::types::BunLock;
 use crate::types::BunLock;
 
 mod error;
+mod prune;
 #[cfg(test)]
 mod tests;
 mod types;
@@ -83,17 +85,40 @@ pub fn parse<P>(root_dir: P) -> Result<Chastefile<Meta>>
 where
     P: AsRef<Path>,
 {
-    let bun_lock_contents = fs::read_to_string(root_dir.as_ref().join(LOCKFILE_NAME))?;
-    let bun_lock: BunLock = json5::from_str(&bun_lock_contents)?;
-    parse_contents(bun_lock)
+    parse_with_source(root_dir, &RealFileSource)
+}
+
+/// Like [`parse`], but with the lockfile read from `file_source` instead of the file system.
+pub fn parse_with_source<P, S>(root_dir: P, file_source: &S) -> Result<Chastefile<Meta>>
+where
+    P: AsRef<Path>,
+    S: FileSource + ?Sized,
+{
+    let bun_lock_contents = file_source.read_to_string(&root_dir.as_ref().join(LOCKFILE_NAME))?;
+    parse_str(&bun_lock_contents)
+}
+
+/// Parse the contents of a bun.lock. No other files are needed.
+pub fn parse_str(lockfile_contents: &str) -> Result<Chastefile<Meta>> {
+    let bun_lock: BunLock = json5::from_str(lockfile_contents)?;
+    parse_contents(bun_lock, &mut Warnings::strict())
+}
+
+/// Like [`parse_str`], but problems with single packages or dependencies are skipped
+/// and returned alongside, instead of failing the whole parse.
+pub fn parse_str_lenient(lockfile_contents: &str) -> Result<(Chastefile<Meta>, Vec<Error>)> {
+    let bun_lock: BunLock = json5::from_str(lockfile_contents)?;
+    let mut warnings = Warnings::lenient();
+    let chastefile = parse_contents(bun_lock, &mut warnings)?;
+    Ok((chastefile, warnings.into_vec()))
 }
 
 #[cfg(feature = "fuzzing")]
 pub fn parse_lock(bun_lock: BunLock) -> Result<Chastefile<Meta>> {
-    parse_contents(bun_lock)
+    parse_contents(bun_lock, &mut Warnings::strict())
 }
 
-fn parse_contents(bun_lock: BunLock) -> Result<Chastefile<Meta>> {
+fn parse_contents(bun_lock: BunLock, warnings: &mut Warnings<Error>) -> Result<Chastefile<Meta>> {
     if !matches!(bun_lock.lockfile_version, (0..=1)) {
         return Err(Error::UnknownLockfileVersion(bun_lock.lockfile_version));
     }
@@ -114,7 +139,11 @@ fn parse_contents(bun_lock: BunLock) -> Result<Chastefile<Meta>> {
                 .transpose()?,
             ws_member.version.as_ref().map(|n| n.to_string()),
         );
-        let pid = chastefile.add_package(pkg_builder.build()?)?;
+        let pid =
+            chastefile
+                .add_package(warnings.build_package(pkg_builder, |e| {
+                    e.at(["workspaces", ws_location.as_ref()])
+                })?)?;
         chastefile.add_package_installation(InstallationBuilder::new(pid, ws_path).build()?);
         if ws_location.is_empty() {
             chastefile.set_root_package_id(pid)?;
@@ -129,10 +158,20 @@ fn parse_contents(bun_lock: BunLock) -> Result<Chastefile<Meta>> {
     let mut presolved_unhoistable: HashMap<(&str, &str), PackageID> = HashMap::new();
     let mut aliased_pids: HashSet<PackageID> = HashSet::new();
     for (lock_key, lock_pkg) in &bun_lock.packages {
-        let (source, installation_package_name) = parse_package_key(lock_key)?;
+        let (source, installation_package_name) = match parse_package_key(lock_key) {
+            Ok(k) => k,
+            Err(e) => {
+                warnings.skip(e.at(["packages", lock_key.as_ref()]))?;
+                continue;
+            }
+        };
         let descriptor = match &lock_pkg[..] {
             [LockPackageElement::String(d), ..] => d.as_ref(),
-            _ => return Err(Error::InvalidVariant(lock_key.to_string())),
+            _ => {
+                return Err(
+                    Error::InvalidVariant(lock_key.to_string()).at(["packages", lock_key.as_ref()])
+                )
+            }
         };
         // Packages repeat, so we dedup them by the descriptor.
         // But we still want to reverse search them by key.
@@ -141,7 +180,13 @@ fn parse_contents(bun_lock: BunLock) -> Result<Chastefile<Meta>> {
                 presolved_unhoistable.insert((source_key, installation_package_name), *pid);
             }
         } else {
-            let (package_name, sv_marker) = parse_descriptor(descriptor)?;
+            let (package_name, sv_marker) = match parse_descriptor(descriptor) {
+                Ok(d) => d,
+                Err(e) => {
+                    warnings.skip(e.at(["packages", lock_key.as_ref()]))?;
+                    continue;
+                }
+            };
             let pid = if let Some(pid) = sv_marker
                 .strip_prefix("workspace:")
                 .and_then(|l| ws_location_to_pid.get(l))
@@ -184,10 +229,18 @@ fn parse_contents(bun_lock: BunLock) -> Result<Chastefile<Meta>> {
                             });
                         }
                     }
-                    (_, _) => return Err(Error::VariantMarkerMismatch(lock_key.to_string())),
+                    (_, _) => {
+                        warnings.skip(
+                            Error::VariantMarkerMismatch(lock_key.to_string())
+                                .at(["packages", lock_key.as_ref()]),
+                        )?;
+                        continue;
+                    }
                 }
+                let pkg = warnings
+                    .build_package(pkg_builder, |e| e.at(["packages", lock_key.as_ref()]))?;
                 let p = if let Some(mut patched) = patched_pkg_builder {
-                    let og_p = chastefile.add_package(pkg_builder.build()?)?;
+                    let og_p = chastefile.add_package(pkg)?;
                     let patch =
                         PackagePatchBuilder::new(patch_path.unwrap().to_string()).build()?;
                     patched.derived(
@@ -196,7 +249,7 @@ fn parse_contents(bun_lock: BunLock) -> Result<Chastefile<Meta>> {
                     );
                     chastefile.add_package(patched.build()?)?
                 } else {
-                    chastefile.add_package(pkg_builder.build()?)?
+                    chastefile.add_package(pkg)?
                 };
                 if installation_package_name != package_name {
                     aliased_pids.insert(p);
@@ -226,7 +279,7 @@ fn parse_contents(bun_lock: BunLock) -> Result<Chastefile<Meta>> {
                 .add_package_installation(InstallationBuilder::new(pid, module_path).build()?);
         }
     }
-    for (lock_key, lock_pkg) in &bun_lock.packages {
+    'packages: for (lock_key, lock_pkg) in &bun_lock.packages {
         let descriptor = match &lock_pkg[..] {
             [LockPackageElement::String(d), ..] => d.as_ref(),
             // This should have thrown an InvalidVariant earlier
@@ -240,12 +293,19 @@ fn parse_contents(bun_lock: BunLock) -> Result<Chastefile<Meta>> {
                     .iter()
                     .any(|e| matches!(e, LockPackageElement::Relations(_)))
                 {
-                    return Err(Error::InvalidVariant(lock_key.to_string()));
+                    warnings.skip(
+                        Error::InvalidVariant(lock_key.to_string())
+                            .at(["packages", lock_key.as_ref()]),
+                    )?;
+                    continue 'packages;
                 }
                 break;
             }
         }
-        let pid = *descript_to_pid.get(descriptor).unwrap();
+        // Packages that could not be parsed were skipped.
+        let Some(&pid) = descript_to_pid.get(descriptor) else {
+            continue;
+        };
         if let Some(relations) = relations {
             for (deps, kind_) in [
                 (&relations.dependencies, DependencyKind::Dependency),
@@ -283,9 +343,14 @@ fn parse_contents(bun_lock: BunLock) -> Result<Chastefile<Meta>> {
                             chastefile.add_dependency(dep.build());
                         }
                         None if kind.is_optional() => {}
-                        None => {
-                            return Err(Error::DependencyNotFound(format!("{dep_name}@{dep_svs}")))
-                        }
+                        None => warnings.skip(
+                            Error::DependencyNotFound(format!("{dep_name}@{dep_svs}")).at([
+                                "packages",
+                                lock_key.as_ref(),
+                                kind_.package_json_field(),
+                                dep_name.as_ref(),
+                            ]),
+                        )?,
                     };
                 }
             }
@@ -330,12 +395,31 @@ fn parse_contents(bun_lock: BunLock) -> Result<Chastefile<Meta>> {
                         chastefile.add_dependency(dep.build());
                     }
                     None if kind.is_optional() => {}
-                    None => return Err(Error::DependencyNotFound(format!("{dep_name}@{dep_svs}"))),
+                    None => warnings.skip(
+                        Error::DependencyNotFound(format!("{dep_name}@{dep_svs}")).at([
+                            "workspaces",
+                            ws_location.as_ref(),
+                            kind_.package_json_field(),
+                            dep_name.as_ref(),
+                        ]),
+                    )?,
                 };
             }
         }
     }
 
+    // Only overrides of all dependencies of a name are supported.
+    for (name, replacement) in &bun_lock.overrides {
+        match OverrideSelector::parse(name) {
+            Ok(selector) => {
+                let override_ = OverrideBuilder::new(selector, replacement.to_string());
+                chastefile.add_override(override_.build());
+            }
+            Err(e) => warnings.skip(Error::ChasteError(e).at(["overrides", name.as_ref()]))?,
+        }
+    }
+    chastefile.match_overrides();
+
     chastefile.build().map_err(Error::ChasteError)
 }
 
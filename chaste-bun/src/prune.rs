@@ -0,0 +1,169 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use chaste_types::indented::{edit_entries, entry_key, lines};
+use chaste_types::{Chastefile, ProviderMeta};
+
+use crate::types::{BunLock, LockPackageElement, PackageRelations};
+use crate::{parse_descriptor, parse_package_key, Result};
+
+/// Fields of a workspace member or a package with dependencies, as in package.json.
+const DEPENDENCY_FIELDS: [&str; 4] = [
+    "dependencies",
+    "devDependencies",
+    "peerDependencies",
+    "optionalDependencies",
+];
+
+type Dependencies<'a> = HashMap<Cow<'a, str>, Cow<'a, str>>;
+
+fn dependency_fields<'r, 'a>(
+    relations: &'r PackageRelations<'a>,
+) -> impl Iterator<Item = (&'static str, &'r Dependencies<'a>)> {
+    DEPENDENCY_FIELDS.into_iter().zip([
+        &relations.dependencies,
+        &relations.dev_dependencies,
+        &relations.peer_dependencies,
+        &relations.optional_dependencies,
+    ])
+}
+
+/// Key in `"packages"` of the package that `name` resolves to from the package at `from`,
+/// looked up in `node_modules` of it and of its parents, like Node.js does.
+fn resolve_key<'l>(bun_lock: &'l BunLock, from: &str, name: &str) -> Option<&'l str> {
+    let mut parent = Some(from).filter(|f| !f.is_empty());
+    while let Some(p) = parent {
+        if let Some((key, _)) = bun_lock
+            .packages
+            .get_key_value(format!("{p}/{name}").as_str())
+        {
+            return Some(key);
+        }
+        parent = match parse_package_key(p) {
+            Ok((Some((source_key, _)), _)) => Some(source_key),
+            _ => None,
+        };
+    }
+    bun_lock
+        .packages
+        .get_key_value(name)
+        .map(|(key, _)| key.as_ref())
+}
+
+/// Path of the workspace member that the entry in `"packages"` is, if it is one.
+fn workspace_path<'l>(elements: &'l [LockPackageElement]) -> Option<&'l str> {
+    let [LockPackageElement::String(descriptor), ..] = elements else {
+        return None;
+    };
+    let (_, svd) = parse_descriptor(descriptor).ok()?;
+    svd.strip_prefix("workspace:")
+}
+
+/// Write the bun.lock with contents `lockfile_contents` again, with only
+/// the workspace members in `pruned`, as made with [`Chastefile::prune`] or
+/// [`Chastefile::subgraph`] from the result of parsing the same contents,
+/// and the packages they need.
+///
+/// Workspace members keep only the dependencies that were not pruned away. Everything else
+/// is kept as it is, with the same formatting.
+pub fn write_pruned<P>(lockfile_contents: &str, pruned: &Chastefile<P>) -> Result<String>
+where
+    P: ProviderMeta,
+{
+    let bun_lock: BunLock = json5::from_str(lockfile_contents)?;
+
+    // Dependencies that are kept, by the path of the workspace member.
+    let mut workspaces: HashMap<&str, HashSet<(&str, &str)>> = HashMap::new();
+    for pid in [pruned.root_package_id()]
+        .iter()
+        .chain(pruned.workspace_member_ids())
+    {
+        for installation in pruned.package_installations(*pid) {
+            workspaces.insert(
+                installation.path().as_ref(),
+                pruned.declared_dependencies(*pid),
+            );
+        }
+    }
+
+    let mut packages: HashSet<&str> = bun_lock
+        .packages
+        .iter()
+        .filter(|(_, elements)| {
+            workspace_path(elements).is_some_and(|p| workspaces.contains_key(p))
+        })
+        .map(|(key, _)| key.as_ref())
+        .collect();
+    let mut queue: VecDeque<&str> = VecDeque::new();
+    for (path, member) in &bun_lock.workspaces {
+        let Some(declared) = workspaces.get(path.as_ref()) else {
+            continue;
+        };
+        // Dependencies only of a member can be under its name, like "balls/long".
+        let from = member.name.as_deref().unwrap_or_default();
+        for (field, dependencies) in dependency_fields(&member.relations) {
+            for name in dependencies.keys() {
+                if declared.contains(&(field, name.as_ref())) {
+                    queue.extend(resolve_key(&bun_lock, from, name));
+                }
+            }
+        }
+    }
+    while let Some(key) = queue.pop_front() {
+        let elements = &bun_lock.packages[key];
+        if workspace_path(elements).is_some() || !packages.insert(key) {
+            continue;
+        }
+        for element in elements {
+            if let LockPackageElement::Relations(relations) = element {
+                for (_, dependencies) in dependency_fields(relations) {
+                    for name in dependencies.keys() {
+                        queue.extend(resolve_key(&bun_lock, key, name));
+                    }
+                }
+            }
+        }
+    }
+
+    let (edited, _) = edit_entries(&lines(lockfile_contents), 2, |section| {
+        let (body, _) = match entry_key(section[0]).as_ref() {
+            "workspaces" => edit_entries(&section[1..], 4, |member| {
+                let declared = workspaces.get(entry_key(member[0]).as_ref())?;
+                Some(edit_member(member, declared))
+            }),
+            "packages" => edit_entries(&section[1..], 4, |package| {
+                packages
+                    .contains(entry_key(package[0]).as_ref())
+                    .then(|| package.to_vec())
+            }),
+            _ => return Some(section.to_vec()),
+        };
+        Some([&section[..1], &body[..]].concat())
+    });
+    Ok(edited.concat())
+}
+
+/// Lines of the entry of a workspace member in `"workspaces"`,
+/// with only the dependencies in `declared`.
+fn edit_member<'c>(member: &[&'c str], declared: &HashSet<(&str, &str)>) -> Vec<&'c str> {
+    let (body, kept) = edit_entries(&member[1..], 6, |field| {
+        let field_name = entry_key(field[0]);
+        let Some(field_name) = DEPENDENCY_FIELDS.iter().find(|f| **f == field_name) else {
+            return Some(field.to_vec());
+        };
+        let (dependencies, kept) = edit_entries(&field[1..], 8, |dependency| {
+            declared
+                .contains(&(*field_name, entry_key(dependency[0]).as_ref()))
+                .then(|| dependency.to_vec())
+        });
+        (kept > 0).then(|| [&field[..1], &dependencies[..]].concat())
+    });
+    if kept == 0 && member.len() > 1 {
+        // Nothing is left, which bun writes like `"": {},`.
+        return vec![member[0].trim_end(), "},\n"];
+    }
+    [&member[..1], &body[..]].concat()
+}
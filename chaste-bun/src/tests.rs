@@ -1,11 +1,11 @@
 // SPDX-FileCopyrightText: 2024 The Chaste Authors
 // SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
 
-use std::{path::PathBuf, sync::LazyLock};
+use std::{fs, path::PathBuf, sync::LazyLock};
 
 use chaste_types::{Chastefile, Package, PackageDerivation, PackageID, PackageSourceType};
 
-use crate::{parse, Meta, Result};
+use crate::{parse, parse_str, write_pruned, Meta, Result};
 
 static TEST_WORKSPACES: LazyLock<PathBuf> = LazyLock::new(|| PathBuf::from("test_workspaces"));
 
@@ -170,6 +170,20 @@ fn text_v1_overrides() -> Result<()> {
         .collect::<Vec<&str>>();
     assert_eq!(path_svss, ["0.1.10"]);
 
+    let [(ms_oid, ms_override), (path_oid, path_override)] = *chastefile.overrides_with_ids()
+    else {
+        panic!();
+    };
+    assert_eq!(ms_override.selector().name(), "ms");
+    assert_eq!(ms_override.replacement(), "^2.1");
+    assert_eq!(path_override.selector().name(), "path-to-regexp");
+    assert_eq!(path_override.parent(), None);
+    assert_eq!(chastefile.overridden_dependencies(ms_oid).len(), 3);
+    let [path_dep] = *chastefile.overridden_dependencies(path_oid) else {
+        panic!();
+    };
+    assert_eq!(path_dep.on, path_pid);
+
     // TODO: https://github.com/oven-sh/bun/issues/6608 ("2024 Q4" in roadmap, as of 2025-01-29)
     /*
     let [(scwm_pid, scwm_pkg)] = *chastefile
@@ -377,3 +391,98 @@ fn text_v1_workspace_basic() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn text_v1_basic_from_str() -> Result<()> {
+    let contents = fs::read_to_string(TEST_WORKSPACES.join("text_v1_basic/bun.lock"))?;
+    let chastefile = parse_str(&contents)?;
+    assert_eq!(
+        chastefile.root_package().name().unwrap(),
+        "@chastelock/test__text_v1_basic"
+    );
+    assert_eq!(chastefile.packages().len(), 9);
+
+    Ok(())
+}
+
+#[test]
+fn text_v1_basic_prune_prod() -> Result<()> {
+    let lockfile_contents =
+        fs::read_to_string(TEST_WORKSPACES.join("text_v1_basic").join("bun.lock"))?;
+    let chastefile = parse_str(&lockfile_contents)?;
+    let pruned = chastefile.prune(&[], true);
+    let pruned_contents = write_pruned(&lockfile_contents, &pruned)?;
+    assert_eq!(
+        pruned_contents,
+        r#"{
+  "lockfileVersion": 1,
+  "workspaces": {
+    "": {
+      "name": "@chastelock/test__text_v1_basic",
+      "dependencies": {
+        "is-even": "1.0.0",
+      },
+    },
+  },
+  "packages": {
+    "is-buffer": ["is-buffer@1.1.6", "", {}, "sha512-NcdALwpXkTm5Zvvbk7owOUSvVvBKDgKP5/ewfXEznmQFfs4ZRmanOeKBTjRVjka3QFoN6XJ+9F3USqfHqTaU5w=="],
+
+    "is-even": ["is-even@1.0.0", "", { "dependencies": { "is-odd": "^0.1.2" } }, "sha512-LEhnkAdJqic4Dbqn58A0y52IXoHWlsueqQkKfMfdEnIYG8A1sm/GHidKkS6yvXlMoRrkM34csHnXQtOqcb+Jzg=="],
+
+    "is-number": ["is-number@3.0.0", "", { "dependencies": { "kind-of": "^3.0.2" } }, "sha512-4cboCqIpliH+mAvFNegjZQ4kgKc3ZUhQVr3HvWbSh5q3WH2v82ct+T2Y1hdU5Gdtorx/cLifQjqCbL7bpznLTg=="],
+
+    "is-odd": ["is-odd@0.1.2", "", { "dependencies": { "is-number": "^3.0.0" } }, "sha512-Ri7C2K7o5IrUU9UEI8losXJCCD/UtsaIrkR5sxIcFg4xQ9cRJXlWA5DQvTE0yDc0krvSNLsRGXN11UPS6KyfBw=="],
+
+    "kind-of": ["kind-of@3.2.2", "", { "dependencies": { "is-buffer": "^1.1.5" } }, "sha512-NOW9QQXMoZGg/oqnVNoNTTIFEIid1627WCffUBJEdMxYApq7mNE7CpzucIPc+ZQg25Phej7IJSmX3hO+oblOtQ=="],
+  }
+}
+"#
+    );
+    assert_eq!(
+        parse_str(&pruned_contents)?.packages().len(),
+        pruned.packages().len()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn text_v1_workspace_basic_prune() -> Result<()> {
+    let lockfile_contents =
+        fs::read_to_string(TEST_WORKSPACES.join("text_v1_workspace_basic").join("bun.lock"))?;
+    let chastefile = parse_str(&lockfile_contents)?;
+    let [ligma_pid] = *chastefile
+        .workspace_member_ids()
+        .iter()
+        .copied()
+        .filter(|pid| chastefile.package(*pid).name().unwrap() == "ligma-api")
+        .collect::<Vec<PackageID>>()
+    else {
+        panic!();
+    };
+    let pruned = chastefile.prune(&[ligma_pid], false);
+    assert_eq!(
+        write_pruned(&lockfile_contents, &pruned)?,
+        r#"{
+  "lockfileVersion": 1,
+  "workspaces": {
+    "": {},
+    "ligma-api": {
+      "name": "ligma-api",
+      "version": "1.0.0",
+      "dependencies": {
+        "long": "^5.0.0",
+      },
+    },
+  },
+  "packages": {
+    "ligma-api": ["ligma-api@workspace:ligma-api"],
+
+    "long": ["long@5.2.4", "", {}, "sha512-qtzLbJE8hq7VabR3mISmVGtoXP8KGc2Z/AT8OuqlYD7JTR3oqrgwdjnk07wpj1twXxYmgDXgoKVWUG/fReSzHg=="],
+  }
+}
+"#
+    );
+
+    Ok(())
+}
@@ -3,7 +3,7 @@
 
 use std::{
     borrow::Cow,
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
 };
 
 use serde::Deserialize;
@@ -49,6 +49,8 @@ pub struct BunLock<'a> {
     pub lockfile_version: u8,
     pub workspaces: HashMap<Cow<'a, str>, WorkspaceMember<'a>>,
     #[serde(default)]
+    pub overrides: BTreeMap<Cow<'a, str>, Cow<'a, str>>,
+    #[serde(default)]
     pub patched_dependencies: HashMap<Cow<'a, str>, Cow<'a, str>>,
     pub packages: HashMap<Cow<'a, str>, Vec<LockPackageElement<'a>>>,
 }
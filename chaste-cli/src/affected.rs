@@ -0,0 +1,85 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, Result};
+use argh::FromArgs;
+use chaste::PackageID;
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "affected")]
+/// Which workspace members have dependencies that changed since another version of the lockfile?
+pub struct Affected {
+    #[argh(option)]
+    /// the lockfile to compare with, or a directory with the project it's in
+    base: PathBuf,
+
+    #[argh(switch)]
+    /// also follow devDependencies
+    all: bool,
+
+    #[argh(switch)]
+    /// print paths of the members instead of their names
+    paths: bool,
+}
+
+fn path(chastefile: &chaste::Chastefile<chaste::Meta>, pid: PackageID) -> String {
+    chastefile
+        .package_installations(pid)
+        .first()
+        .map(|i| i.path().as_ref().to_string())
+        .unwrap_or_else(|| "[unknown path]".to_string())
+}
+
+/// Parse the base lockfile. A lockfile outside of a project is parsed
+/// with the package.json files of the project at `root`.
+fn load_base(
+    base: &Path,
+    root: &Path,
+    implem: Option<chaste::Implementation>,
+) -> Result<chaste::Chastefile<chaste::Meta>> {
+    if base.is_dir() {
+        let parsed = match implem {
+            Some(implem) => chaste::from_root_path_with_implementation(base, implem),
+            None => chaste::from_root_path(base),
+        };
+        return parsed.with_context(|| format!("Could not parse the lockfile from {base:?}"));
+    }
+    let contents = fs::read_to_string(base)
+        .with_context(|| format!("Could not read the base lockfile {base:?}"))?;
+    let parsed = match implem {
+        Some(implem) => {
+            chaste::from_str_with_implementation(&contents, implem, root, &chaste::RealFileSource)
+        }
+        None => chaste::from_str(&contents, root, &chaste::RealFileSource),
+    };
+    parsed.with_context(|| format!("Could not parse the base lockfile {base:?}"))
+}
+
+pub fn run(
+    sub: Affected,
+    chastefile: chaste::Chastefile<chaste::Meta>,
+    cwd: &Path,
+    root: &Path,
+    implem: Option<chaste::Implementation>,
+) -> Result<()> {
+    // Compare with a lockfile of the same implementation.
+    let implem = implem.or_else(|| chastefile.meta().implementation());
+    let base = load_base(&cwd.join(&sub.base), root, implem)?;
+
+    let mut members: Vec<String> = chastefile
+        .affected_workspace_members(&base, !sub.all)
+        .into_iter()
+        .map(|pid| match chastefile.package(pid).name() {
+            Some(name) if !sub.paths => name.to_string(),
+            _ => path(&chastefile, pid),
+        })
+        .collect();
+    members.sort_unstable();
+    for member in members {
+        println!("{member}");
+    }
+    Ok(())
+}
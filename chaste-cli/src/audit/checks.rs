@@ -0,0 +1,460 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::collections::HashSet;
+use std::fmt;
+
+use chaste::types::ssri::Algorithm;
+use chaste::types::{PackageSource, PackageSourceType};
+use chaste::{Package, PackageID};
+
+use crate::audit::policy::{Policy, Rule};
+use crate::why::describe;
+
+/// Level of a finding, named as in SARIF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// What is audited, with which rules.
+pub(crate) struct Audited<'a> {
+    pub chastefile: &'a chaste::Chastefile<chaste::Meta>,
+    pub policy: &'a Policy,
+    /// Packages to check, other than the root package and workspace members.
+    pub packages: Vec<(PackageID, &'a Package)>,
+    /// Packages whose dependencies are checked, including the workspace member audited.
+    pub checked: HashSet<PackageID>,
+    /// Where paths to packages start from.
+    pub from: Vec<PackageID>,
+}
+
+/// A package failing a check, as found by it.
+struct Failure {
+    pid: PackageID,
+    message: String,
+    /// Where in the policy file the rule failed is set.
+    rule: Option<String>,
+}
+
+impl Failure {
+    fn new(pid: PackageID, message: String) -> Self {
+        Failure {
+            pid,
+            message,
+            rule: None,
+        }
+    }
+
+    fn by<T>(pid: PackageID, message: String, rule: &Rule<T>) -> Self {
+        Failure {
+            pid,
+            message,
+            rule: Some(rule.origin.clone()),
+        }
+    }
+}
+
+/// A specific check performed in the audit.
+pub(crate) struct Check {
+    /// Stable identifier, e.g. for SARIF rules and suppressions in the policy.
+    pub id: &'static str,
+    /// What failing packages have, as in "packages with no checksums".
+    pub description: &'static str,
+    pub severity: Severity,
+    /// How to fix a failing package.
+    pub remediation: &'static str,
+    /// Whether the policy asks for the check.
+    enabled: fn(&Policy) -> bool,
+    /// Failing packages, with what is wrong with each.
+    find: fn(&Audited) -> Vec<Failure>,
+}
+
+/// Why a finding does not fail the audit.
+pub(crate) struct Suppressed {
+    /// Where in the policy file the suppression is.
+    pub rule: String,
+    pub reason: Option<String>,
+}
+
+/// A package failing a check.
+pub(crate) struct Finding {
+    pub check: &'static Check,
+    pub severity: Severity,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// Packages from the root package or the workspace member down to this one.
+    pub path: Vec<String>,
+    pub message: String,
+    /// Where in the policy file the rule failed is set, if it is configurable.
+    pub rule: Option<String>,
+    pub suppressed: Option<Suppressed>,
+}
+
+fn always(_: &Policy) -> bool {
+    true
+}
+
+fn no_checksums(audited: &Audited) -> Vec<Failure> {
+    audited
+        .packages
+        .iter()
+        .filter(|(_, package)| package.checksums().is_none())
+        .map(|&(pid, _)| {
+            let message = format!("{} has no checksums", describe(audited.chastefile, pid));
+            Failure::new(pid, message)
+        })
+        .collect()
+}
+
+fn insecure_checksums(audited: &Audited) -> Vec<Failure> {
+    let rule = audited.policy.min_checksum.as_ref();
+    let weakest = rule.map(|r| r.value).unwrap_or(Algorithm::Sha256);
+    let mut found = Vec::new();
+    for &(pid, package) in &audited.packages {
+        let Some(checksums) = package.checksums() else {
+            continue;
+        };
+        // Algorithms are ordered from the strongest.
+        let algorithm = checksums.integrity().pick_algorithm();
+        if algorithm > weakest {
+            let message = format!(
+                "{} only has a {algorithm} checksum",
+                describe(audited.chastefile, pid)
+            );
+            found.push(match rule {
+                Some(rule) => Failure::by(pid, message, rule),
+                None => Failure::new(pid, message),
+            });
+        }
+    }
+    found
+}
+
+fn unrecognized_source(audited: &Audited) -> Vec<Failure> {
+    audited
+        .packages
+        .iter()
+        .filter(|(_, package)| package.source().is_none())
+        .map(|&(pid, _)| {
+            let message = format!(
+                "{} comes from an unrecognized source",
+                describe(audited.chastefile, pid)
+            );
+            Failure::new(pid, message)
+        })
+        .collect()
+}
+
+fn unsatisfied_range(audited: &Audited) -> Vec<Failure> {
+    let chastefile = audited.chastefile;
+    let mut found: Vec<Failure> = Vec::new();
+    for unsatisfied in chastefile.unsatisfied_ranges() {
+        let dependency = unsatisfied.dependency;
+        if !audited.checked.contains(&dependency.from)
+            || found.iter().any(|f| f.pid == dependency.on)
+        {
+            continue;
+        }
+        let message = format!(
+            "{} does not satisfy {:?} required by {}",
+            describe(chastefile, dependency.on),
+            unsatisfied.range,
+            describe(chastefile, dependency.from)
+        );
+        found.push(Failure::new(dependency.on, message));
+    }
+    found
+}
+
+fn source_type_name(source_type: PackageSourceType) -> &'static str {
+    match source_type {
+        PackageSourceType::Npm => "npm",
+        PackageSourceType::TarballURL => "tarball",
+        PackageSourceType::Git => "git",
+        _ => "unknown",
+    }
+}
+
+fn disallowed_source(audited: &Audited) -> Vec<Failure> {
+    let Some(rule) = &audited.policy.sources else {
+        return Vec::new();
+    };
+    let mut found = Vec::new();
+    for &(pid, package) in &audited.packages {
+        let Some(source_type) = package.source_type() else {
+            continue;
+        };
+        if !rule.value.contains(&source_type) {
+            let message = format!(
+                "{} comes from a {} source, which is not allowed",
+                describe(audited.chastefile, pid),
+                source_type_name(source_type)
+            );
+            found.push(Failure::by(pid, message, rule));
+        }
+    }
+    found
+}
+
+fn disallowed_registry(audited: &Audited) -> Vec<Failure> {
+    let Some(rule) = &audited.policy.registries else {
+        return Vec::new();
+    };
+    let mut found = Vec::new();
+    for &(pid, package) in &audited.packages {
+        let url = match package.source() {
+            Some(PackageSource::TarballURL { url }) | Some(PackageSource::Git { url }) => url,
+            _ => continue,
+        };
+        if !rule.value.iter().any(|prefix| url.starts_with(prefix)) {
+            let message = format!(
+                "{} comes from {url}, outside of the allowed registries",
+                describe(audited.chastefile, pid)
+            );
+            found.push(Failure::by(pid, message, rule));
+        }
+    }
+    found
+}
+
+fn banned(audited: &Audited) -> Vec<Failure> {
+    let mut found = Vec::new();
+    for &(pid, package) in &audited.packages {
+        let Some(name) = package.name() else {
+            continue;
+        };
+        let ban = audited.policy.banned.iter().find(|ban| {
+            ban.value.name == name.as_ref()
+                && match &ban.value.range {
+                    Some(range) => package.version().is_some_and(|v| range.satisfies(v)),
+                    None => true,
+                }
+        });
+        if let Some(ban) = ban {
+            let mut message = format!("{} is banned", describe(audited.chastefile, pid));
+            if let Some(reason) = &ban.value.reason {
+                message.push_str(&format!(": {reason}"));
+            }
+            found.push(Failure::by(pid, message, ban));
+        }
+    }
+    found
+}
+
+/// All packages of a source type, if there are more of them than allowed.
+fn over_limit(
+    audited: &Audited,
+    source_type: PackageSourceType,
+    rule: Option<&Rule<usize>>,
+) -> Vec<Failure> {
+    let Some(rule) = rule else {
+        return Vec::new();
+    };
+    let pids: Vec<PackageID> = audited
+        .packages
+        .iter()
+        .filter(|(_, package)| package.source_type() == Some(source_type))
+        .map(|(pid, _)| *pid)
+        .collect();
+    let count = pids.len();
+    if count <= rule.value {
+        return Vec::new();
+    }
+    pids.into_iter()
+        .map(|pid| {
+            let message = format!(
+                "{} is from a {} source, and {count} packages are, more than the {} allowed",
+                describe(audited.chastefile, pid),
+                source_type_name(source_type),
+                rule.value
+            );
+            Failure::by(pid, message, rule)
+        })
+        .collect()
+}
+
+fn too_many_git(audited: &Audited) -> Vec<Failure> {
+    over_limit(
+        audited,
+        PackageSourceType::Git,
+        audited.policy.max_git.as_ref(),
+    )
+}
+
+fn too_many_tarballs(audited: &Audited) -> Vec<Failure> {
+    over_limit(
+        audited,
+        PackageSourceType::TarballURL,
+        audited.policy.max_tarball.as_ref(),
+    )
+}
+
+fn expired_suppression(audited: &Audited) -> Vec<Failure> {
+    let mut found = Vec::new();
+    for &(pid, package) in &audited.packages {
+        for suppression in &audited.policy.expired_suppressions {
+            if !suppression.value.matches(package) {
+                continue;
+            }
+            let message = format!(
+                "{} is no longer suppressed from {}, the suppression expired on {}",
+                describe(audited.chastefile, pid),
+                suppression.value.check,
+                suppression.value.expires.as_deref().unwrap_or_default()
+            );
+            found.push(Failure::by(pid, message, suppression));
+        }
+    }
+    found
+}
+
+/// All checks, in the order they are reported in.
+pub(crate) static CHECKS: &[Check] = &[
+    Check {
+        id: "no-checksums",
+        description: "no checksums",
+        severity: Severity::Warning,
+        remediation: "Install the package from a registry or a tarball URL, \
+            which the package manager records checksums of, instead of e.g. a git repository.",
+        enabled: always,
+        find: no_checksums,
+    },
+    Check {
+        id: "insecure-checksums",
+        description: "insecure checksums",
+        severity: Severity::Error,
+        remediation: "Remove the package from the lockfile and install it again, \
+            so that a checksum with a strong enough algorithm is recorded.",
+        enabled: always,
+        find: insecure_checksums,
+    },
+    Check {
+        id: "unrecognized-source",
+        description: "unrecognized source",
+        severity: Severity::Warning,
+        remediation: "Check where the package comes from, and install it from \
+            a registry, a tarball URL or a git repository instead.",
+        enabled: always,
+        find: unrecognized_source,
+    },
+    Check {
+        id: "unsatisfied-range",
+        description: "versions not satisfying the requested range",
+        severity: Severity::Error,
+        remediation: "Install the dependencies again to update the lockfile \
+            to versions in the ranges in package.json.",
+        enabled: always,
+        find: unsatisfied_range,
+    },
+    Check {
+        id: "disallowed-source",
+        description: "sources not allowed by the policy",
+        severity: Severity::Error,
+        remediation: "Install the package from an allowed source instead, \
+            or allow its source type in the policy.",
+        enabled: |policy| policy.sources.is_some(),
+        find: disallowed_source,
+    },
+    Check {
+        id: "disallowed-registry",
+        description: "tarball or git URLs outside of the allowed registries",
+        severity: Severity::Error,
+        remediation: "Install the package from an allowed registry instead, \
+            or add the URL to the registries in the policy. Packages from npm registries \
+            are not checked, Chaste does not keep which one they come from.",
+        enabled: |policy| policy.registries.is_some(),
+        find: disallowed_registry,
+    },
+    Check {
+        id: "banned",
+        description: "banned versions",
+        severity: Severity::Error,
+        remediation: "Remove the package, or update it to a version that is not banned. \
+            To find out why it is installed, run chaste why.",
+        enabled: |policy| !policy.banned.is_empty(),
+        find: banned,
+    },
+    Check {
+        id: "too-many-git",
+        description: "git sources over the limit",
+        severity: Severity::Error,
+        remediation: "Install some of the packages from a registry instead.",
+        enabled: |policy| policy.max_git.is_some(),
+        find: too_many_git,
+    },
+    Check {
+        id: "too-many-tarballs",
+        description: "tarball URLs over the limit",
+        severity: Severity::Error,
+        remediation: "Install some of the packages from a registry instead.",
+        enabled: |policy| policy.max_tarball.is_some(),
+        find: too_many_tarballs,
+    },
+    Check {
+        id: "expired-suppression",
+        description: "expired suppressions",
+        severity: Severity::Warning,
+        remediation: "Fix what the suppression was for, \
+            or remove the suppression or set a later expiry date in the policy.",
+        enabled: |policy| !policy.expired_suppressions.is_empty(),
+        find: expired_suppression,
+    },
+];
+
+impl Check {
+    pub fn is_enabled(&self, policy: &Policy) -> bool {
+        (self.enabled)(policy)
+    }
+
+    pub fn findings(&'static self, audited: &Audited) -> Vec<Finding> {
+        let chastefile = audited.chastefile;
+        (self.find)(audited)
+            .into_iter()
+            .map(|failure| {
+                let pid = failure.pid;
+                let package = chastefile.package(pid);
+                let path = match chastefile
+                    .dependency_paths(&audited.from, &[pid], None, Some(1))
+                    .first()
+                {
+                    Some(chain) => [chain[0].from]
+                        .into_iter()
+                        .chain(chain.iter().map(|d| d.on))
+                        .map(|pid| describe(chastefile, pid))
+                        .collect(),
+                    None => vec![describe(chastefile, pid)],
+                };
+                let suppressed = audited
+                    .policy
+                    .suppressions
+                    .iter()
+                    .find(|s| s.value.check == self.id && s.value.matches(package))
+                    .map(|s| Suppressed {
+                        rule: s.origin.clone(),
+                        reason: s.value.reason.clone(),
+                    });
+                Finding {
+                    check: self,
+                    severity: self.severity,
+                    name: package.name().map(|n| n.to_string()),
+                    version: package.version().map(|v| v.to_string()),
+                    path,
+                    message: failure.message,
+                    rule: failure.rule,
+                    suppressed,
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,335 @@
+// SPDX-FileCopyrightText: 2025 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use argh::FromArgs;
+use chaste::types::ProviderMeta;
+use chaste::{Package, PackageID};
+
+use crate::audit::checks::{Audited, Check, Finding, CHECKS};
+use crate::audit::policy::{Policy, PolicyFile, POLICY_FILE_NAME};
+
+mod checks;
+mod policy;
+mod report;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+    Sarif,
+}
+
+fn format_from_name(name: &str) -> Result<Format, String> {
+    match name {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        "sarif" => Ok(Format::Sarif),
+        _ => Err(format!("Unknown audit format: {name:?}")),
+    }
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "audit")]
+/// Potential problems with your dependency tree
+pub struct Audit {
+    #[argh(switch)]
+    /// failing checks should not result in non-zero exit code
+    failures_ok: bool,
+
+    #[argh(switch)]
+    /// leave out packages that are only needed for development
+    prod: bool,
+
+    #[argh(option, default = "Format::Text", from_str_fn(format_from_name))]
+    /// format to print the results in: "text", "json" or "sarif". Default: text
+    format: Format,
+
+    #[argh(option)]
+    /// policy file with the rules to check. Default: chaste.yaml in the root, if there is one
+    policy: Option<PathBuf>,
+}
+
+/// A check with what it found, including findings suppressed by the policy.
+struct Outcome {
+    check: &'static Check,
+    findings: Vec<Finding>,
+}
+
+impl Outcome {
+    fn failures(&self) -> impl Iterator<Item = &Finding> {
+        self.findings.iter().filter(|f| f.suppressed.is_none())
+    }
+
+    fn passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+}
+
+/// Packages checked with the same rules, and the packages they are dependencies of.
+struct Scope<'a> {
+    policy: &'a Policy,
+    importers: Vec<PackageID>,
+    packages: Vec<(PackageID, &'a Package)>,
+}
+
+/// Splits the packages by the rules that apply to them. Packages of workspace members
+/// with their own rules are checked with those rules, and with the top level rules
+/// if other workspace members or the root package depend on them too.
+fn scopes<'a>(
+    chastefile: &'a chaste::Chastefile<chaste::Meta>,
+    policy_file: &'a PolicyFile,
+    member: Option<PackageID>,
+    packages: &[(PackageID, &'a Package)],
+) -> Vec<Scope<'a>> {
+    if let Some(member_pid) = member {
+        return vec![Scope {
+            policy: policy_file.member_policy(member_pid),
+            importers: vec![member_pid],
+            packages: packages.to_vec(),
+        }];
+    }
+    let (overridden, others): (Vec<PackageID>, Vec<PackageID>) = [chastefile.root_package_id()]
+        .into_iter()
+        .chain(chastefile.workspace_member_ids().iter().copied())
+        .partition(|pid| policy_file.members.iter().any(|(m, _)| m == pid));
+    let mut scopes = Vec::with_capacity(overridden.len() + 1);
+    let mut covered: HashSet<PackageID> = HashSet::new();
+    for member_pid in overridden {
+        let reachable = chastefile.reachability(&[member_pid]);
+        covered.extend(reachable.keys());
+        scopes.push(Scope {
+            policy: policy_file.member_policy(member_pid),
+            importers: vec![member_pid],
+            packages: packages
+                .iter()
+                .copied()
+                .filter(|(pid, _)| reachable.contains_key(pid))
+                .collect(),
+        });
+    }
+    let reachable = chastefile.reachability(&others);
+    scopes.insert(
+        0,
+        Scope {
+            policy: &policy_file.policy,
+            importers: others,
+            packages: packages
+                .iter()
+                .copied()
+                .filter(|(pid, _)| reachable.contains_key(pid) || !covered.contains(pid))
+                .collect(),
+        },
+    );
+    scopes
+}
+
+pub fn run(
+    sub: Audit,
+    chastefile: chaste::Chastefile<chaste::Meta>,
+    member: Option<PackageID>,
+    root: &Path,
+    lockfile: Option<&Path>,
+) -> Result<()> {
+    let policy_path = match sub.policy {
+        Some(path) => Some(path),
+        None => Some(root.join(POLICY_FILE_NAME)).filter(|p| p.is_file()),
+    };
+    let policy_file = match &policy_path {
+        Some(path) => PolicyFile::load(path, &chastefile)?,
+        None => PolicyFile::default(),
+    };
+
+    let mut packages = match member {
+        Some(member_pid) => {
+            let pids: HashSet<PackageID> = chastefile
+                .recursive_package_dependencies(member_pid)
+                .into_iter()
+                .map(|d| d.on)
+                .collect();
+            pids.into_iter()
+                .map(|pid| (pid, chastefile.package(pid)))
+                .collect()
+        }
+        None => chastefile.packages_with_ids(),
+    };
+    let root_pid = chastefile.root_package_id();
+    let member_pids = chastefile.workspace_member_ids();
+    if sub.prod {
+        let importers: Vec<PackageID> = match member {
+            Some(member_pid) => vec![member_pid],
+            None => [root_pid]
+                .into_iter()
+                .chain(member_pids.iter().copied())
+                .collect(),
+        };
+        let reachability = chastefile.reachability(&importers);
+        packages.retain(|(pid, _)| reachability.get(pid).is_some_and(|r| r.is_prod()));
+    }
+
+    let scopes = scopes(&chastefile, &policy_file, member, &packages);
+    let mut outcomes: Vec<Outcome> = CHECKS
+        .iter()
+        .filter(|check| scopes.iter().any(|s| check.is_enabled(s.policy)))
+        .map(|check| Outcome {
+            check,
+            findings: Vec::new(),
+        })
+        .collect();
+    for scope in &scopes {
+        let audited = Audited {
+            chastefile: &chastefile,
+            policy: scope.policy,
+            packages: scope
+                .packages
+                .iter()
+                .copied()
+                .filter(|(pid, _)| *pid != root_pid && !member_pids.contains(pid))
+                .collect(),
+            checked: scope
+                .packages
+                .iter()
+                .map(|(pid, _)| *pid)
+                .chain(scope.importers.iter().copied())
+                .collect(),
+            from: scope.importers.clone(),
+        };
+        for outcome in &mut outcomes {
+            outcome.findings.extend(outcome.check.findings(&audited));
+        }
+    }
+    for outcome in &mut outcomes {
+        // Packages checked with several rules are found by each of them.
+        let mut seen: HashMap<(Option<String>, Option<String>, String), usize> = HashMap::new();
+        let mut findings: Vec<Finding> = Vec::with_capacity(outcome.findings.len());
+        for finding in outcome.findings.drain(..) {
+            let key = (
+                finding.name.clone(),
+                finding.version.clone(),
+                finding.message.clone(),
+            );
+            match seen.get(&key) {
+                // Suppressed by one rule is not enough when another one fails it.
+                Some(&i) if findings[i].suppressed.is_some() && finding.suppressed.is_none() => {
+                    findings[i] = finding
+                }
+                Some(_) => {}
+                None => {
+                    seen.insert(key, findings.len());
+                    findings.push(finding);
+                }
+            }
+        }
+        findings.sort_by(|a, b| {
+            (&a.name, &a.version, &a.message).cmp(&(&b.name, &b.version, &b.message))
+        });
+        outcome.findings = findings;
+    }
+
+    match sub.format {
+        Format::Text => print_text(
+            &chastefile,
+            member,
+            policy_path
+                .as_deref()
+                .map(|p| p.strip_prefix(root).unwrap_or(p)),
+            packages.len(),
+            &outcomes,
+        ),
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report::to_json(
+                &chastefile,
+                member,
+                packages.len(),
+                &outcomes
+            ))?
+        ),
+        Format::Sarif => println!(
+            "{}",
+            serde_json::to_string_pretty(&report::to_sarif(&outcomes, lockfile))?
+        ),
+    }
+
+    let failed_checks = outcomes.iter().filter(|o| !o.passed()).count();
+    if !sub.failures_ok {
+        std::process::exit(failed_checks.try_into()?);
+    }
+    Ok(())
+}
+
+fn print_text(
+    chastefile: &chaste::Chastefile<chaste::Meta>,
+    member: Option<PackageID>,
+    policy_path: Option<&Path>,
+    packages: usize,
+    outcomes: &[Outcome],
+) {
+    let meta = chastefile.meta();
+    print!("Checked a {} ", meta.provider_name());
+    if let Some(lv) = meta.lockfile_version() {
+        print!("({lv}) ");
+    }
+    println!("lockfile.");
+    if let Some(member_pid) = member {
+        println!(
+            "Only dependencies of workspace member {}.",
+            chastefile
+                .package(member_pid)
+                .name()
+                .map(|n| n.as_ref())
+                .unwrap_or("[unnamed]")
+        );
+    }
+    if let Some(path) = policy_path {
+        println!("With the policy in {}.", path.display());
+    }
+
+    if outcomes.iter().all(Outcome::passed) {
+        print!("All good! ")
+    }
+    println!("Out of {packages} dependencies:");
+    for outcome in outcomes {
+        if outcome.passed() {
+            println!("✅ No packages with {}", outcome.check.description);
+        }
+    }
+    for outcome in outcomes {
+        let failed: Vec<&Finding> = outcome.failures().collect();
+        if !failed.is_empty() {
+            let len = failed.len();
+            let mut list: Vec<&str> = failed
+                .iter()
+                .map(|f| f.name.as_deref().unwrap_or("[unnamed]"))
+                .collect();
+            list.sort_unstable();
+            println!(
+                "❌ {} package{} with {}:\n\t{}",
+                len,
+                if len == 1 { "" } else { "s" },
+                outcome.check.description,
+                list.join(" ")
+            );
+            let mut rules: Vec<&str> = failed.iter().filter_map(|f| f.rule.as_deref()).collect();
+            rules.sort_unstable();
+            rules.dedup();
+            if !rules.is_empty() {
+                println!("\tBy rule {}", rules.join(", "));
+            }
+        }
+    }
+    let suppressed = outcomes
+        .iter()
+        .flat_map(|o| &o.findings)
+        .filter(|f| f.suppressed.is_some())
+        .count();
+    if suppressed > 0 {
+        println!(
+            "{suppressed} finding{} suppressed by the policy.",
+            if suppressed == 1 { "" } else { "s" }
+        );
+    }
+}
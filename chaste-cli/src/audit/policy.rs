@@ -0,0 +1,365 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context as _, Result};
+use chaste::types::ssri::Algorithm;
+use chaste::types::{PackageSourceType, VersionRange};
+use chaste::PackageID;
+use serde::Deserialize;
+
+use crate::audit::checks::CHECKS;
+
+/// Name of the policy file looked for in the root of the project.
+/// YAML rather than TOML, which the CLI has no parser for, while it reads YAML with serde_norway.
+pub(crate) const POLICY_FILE_NAME: &str = "chaste.yaml";
+
+/// Rules as written in the policy file, at the top level or for a workspace member.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct RawRules {
+    min_checksum: Option<String>,
+    sources: Option<Vec<String>>,
+    registries: Option<Vec<String>>,
+    max_git: Option<usize>,
+    max_tarball: Option<usize>,
+    #[serde(default)]
+    banned: Vec<RawBan>,
+    #[serde(default)]
+    suppressions: Vec<RawSuppression>,
+    #[serde(default)]
+    members: BTreeMap<String, RawRules>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct RawBan {
+    name: String,
+    range: Option<String>,
+    reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct RawSuppression {
+    check: String,
+    name: String,
+    range: Option<String>,
+    expires: Option<String>,
+    reason: Option<String>,
+}
+
+/// A setting of the policy, with where it is set.
+#[derive(Debug, Clone)]
+pub(crate) struct Rule<T> {
+    pub value: T,
+    /// Path to the setting in the policy file, like "members.web.banned[0]".
+    pub origin: String,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Ban {
+    pub name: String,
+    pub range: Option<VersionRange>,
+    pub reason: Option<String>,
+}
+
+/// A finding that should not fail the audit.
+#[derive(Debug, Clone)]
+pub(crate) struct Suppression {
+    pub check: &'static str,
+    pub name: String,
+    pub range: Option<VersionRange>,
+    pub expires: Option<String>,
+    pub reason: Option<String>,
+}
+
+impl Suppression {
+    /// Whether findings of its check for the package are the ones suppressed.
+    pub fn matches(&self, package: &chaste::Package) -> bool {
+        package.name().is_some_and(|n| n.as_ref() == self.name)
+            && match &self.range {
+                Some(range) => package.version().is_some_and(|v| range.satisfies(v)),
+                None => true,
+            }
+    }
+}
+
+/// Rules that apply to some packages, the ones of a workspace member
+/// including the top level rules it does not override.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Policy {
+    /// Weakest checksum algorithm allowed. Default: sha256.
+    pub min_checksum: Option<Rule<Algorithm>>,
+    /// Source types allowed. Default: all.
+    pub sources: Option<Rule<Vec<PackageSourceType>>>,
+    /// URL prefixes allowed for tarball and git sources. Lockfiles do not tell
+    /// which npm registry a package is from, so those are not checked.
+    pub registries: Option<Rule<Vec<String>>>,
+    pub max_git: Option<Rule<usize>>,
+    pub max_tarball: Option<Rule<usize>>,
+    pub banned: Vec<Rule<Ban>>,
+    /// Not including expired suppressions.
+    pub suppressions: Vec<Rule<Suppression>>,
+    /// Suppressions past their expiry date, which are reported instead.
+    pub expired_suppressions: Vec<Rule<Suppression>>,
+}
+
+/// The policy file, resolved against the lockfile.
+#[derive(Debug, Default)]
+pub(crate) struct PolicyFile {
+    pub policy: Policy,
+    /// Workspace members with their own rules, and the rules including them.
+    pub members: Vec<(PackageID, Policy)>,
+}
+
+impl PolicyFile {
+    pub fn load(path: &Path, chastefile: &chaste::Chastefile<chaste::Meta>) -> Result<Self> {
+        let contents =
+            fs::read_to_string(path).with_context(|| format!("Could not read {path:?}"))?;
+        let raw: RawRules = serde_norway::from_str(&contents)
+            .with_context(|| format!("Could not parse the policy file {path:?}"))?;
+        Self::resolve(raw, chastefile, &today())
+            .with_context(|| format!("Invalid policy file {path:?}"))
+    }
+
+    fn resolve(
+        mut raw: RawRules,
+        chastefile: &chaste::Chastefile<chaste::Meta>,
+        today: &str,
+    ) -> Result<Self> {
+        let raw_members = std::mem::take(&mut raw.members);
+        let policy = Policy::default().with(raw, "", today)?;
+        let mut members = Vec::with_capacity(raw_members.len());
+        for (key, rules) in raw_members {
+            let prefix = format!("members.{key}.");
+            if !rules.members.is_empty() {
+                bail!("{prefix}members: Only the top level can have rules for members");
+            }
+            let Some(member) = find_member(chastefile, &key) else {
+                bail!("members.{key}: There is no workspace member named {key:?}");
+            };
+            members.push((member, policy.clone().with(rules, &prefix, today)?));
+        }
+        Ok(PolicyFile { policy, members })
+    }
+
+    /// The rules that apply to a workspace member.
+    pub fn member_policy(&self, member: PackageID) -> &Policy {
+        self.members
+            .iter()
+            .find(|(pid, _)| *pid == member)
+            .map(|(_, policy)| policy)
+            .unwrap_or(&self.policy)
+    }
+}
+
+/// Workspace member by its name, or its path relative to the root.
+fn find_member(chastefile: &chaste::Chastefile<chaste::Meta>, key: &str) -> Option<PackageID> {
+    chastefile
+        .workspace_member_ids()
+        .iter()
+        .copied()
+        .find(|&pid| {
+            chastefile
+                .package(pid)
+                .name()
+                .is_some_and(|n| n.as_ref() == key)
+                || chastefile
+                    .package_installations(pid)
+                    .iter()
+                    .any(|i| i.path().as_ref() == key.trim_end_matches('/'))
+        })
+}
+
+fn rule<T>(value: T, prefix: &str, key: &str) -> Rule<T> {
+    Rule {
+        value,
+        origin: format!("{prefix}{key}"),
+    }
+}
+
+fn parse_range(range: Option<String>, origin: &str) -> Result<Option<VersionRange>> {
+    range
+        .map(|r| {
+            VersionRange::parse(&r).map_err(|_| anyhow::anyhow!("{origin}: Invalid range {r:?}"))
+        })
+        .transpose()
+}
+
+impl Policy {
+    /// These rules, with the ones set in `raw` instead, and its bans and suppressions added.
+    fn with(mut self, raw: RawRules, prefix: &str, today: &str) -> Result<Self> {
+        if let Some(name) = raw.min_checksum {
+            let algorithm = match name.as_str() {
+                "sha1" => Algorithm::Sha1,
+                "sha256" => Algorithm::Sha256,
+                "sha384" => Algorithm::Sha384,
+                "sha512" => Algorithm::Sha512,
+                _ => bail!("{prefix}min-checksum: Unknown checksum algorithm {name:?}"),
+            };
+            self.min_checksum = Some(rule(algorithm, prefix, "min-checksum"));
+        }
+        if let Some(names) = raw.sources {
+            let sources = names
+                .iter()
+                .map(|name| match name.as_str() {
+                    "npm" => Ok(PackageSourceType::Npm),
+                    "tarball" => Ok(PackageSourceType::TarballURL),
+                    "git" => Ok(PackageSourceType::Git),
+                    _ => bail!("{prefix}sources: Unknown source type {name:?}"),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            self.sources = Some(rule(sources, prefix, "sources"));
+        }
+        if let Some(registries) = raw.registries {
+            self.registries = Some(rule(registries, prefix, "registries"));
+        }
+        if let Some(max) = raw.max_git {
+            self.max_git = Some(rule(max, prefix, "max-git"));
+        }
+        if let Some(max) = raw.max_tarball {
+            self.max_tarball = Some(rule(max, prefix, "max-tarball"));
+        }
+        for (i, ban) in raw.banned.into_iter().enumerate() {
+            let origin = format!("{prefix}banned[{i}]");
+            self.banned.push(Rule {
+                value: Ban {
+                    name: ban.name,
+                    range: parse_range(ban.range, &origin)?,
+                    reason: ban.reason,
+                },
+                origin,
+            });
+        }
+        for (i, suppression) in raw.suppressions.into_iter().enumerate() {
+            let origin = format!("{prefix}suppressions[{i}]");
+            let Some(check) = CHECKS.iter().find(|c| c.id == suppression.check) else {
+                bail!("{origin}: Unknown check {:?}", suppression.check);
+            };
+            if let Some(expires) = &suppression.expires {
+                if !is_date(expires) {
+                    bail!("{origin}: Expiry date {expires:?} is not a YYYY-MM-DD date");
+                }
+            }
+            let expired = suppression
+                .expires
+                .as_deref()
+                .is_some_and(|expires| expires < today);
+            let rule = Rule {
+                value: Suppression {
+                    check: check.id,
+                    name: suppression.name,
+                    range: parse_range(suppression.range, &origin)?,
+                    expires: suppression.expires,
+                    reason: suppression.reason,
+                },
+                origin,
+            };
+            if expired {
+                self.expired_suppressions.push(rule);
+            } else {
+                self.suppressions.push(rule);
+            }
+        }
+        Ok(self)
+    }
+}
+
+fn is_date(date: &str) -> bool {
+    let bytes = date.as_bytes();
+    let is_formatted = bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        });
+    if !is_formatted {
+        return false;
+    }
+    let [year, month, day] =
+        [&date[0..4], &date[5..7], &date[8..10]].map(|n| n.parse::<u32>().unwrap());
+    let is_leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days_in_month).contains(&day)
+}
+
+/// The current date in UTC, as YYYY-MM-DD.
+fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() / 86400)
+        .unwrap_or(0) as i64;
+    civil_from_days(days)
+}
+
+/// The date `days` after 1970-01-01, as YYYY-MM-DD.
+fn civil_from_days(days: i64) -> String {
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{civil_from_days, is_date};
+
+    #[test]
+    fn dates() {
+        for date in [
+            "2026-01-31",
+            "2024-02-29",
+            "2000-02-29",
+            "2025-04-30",
+            "2025-12-31",
+        ] {
+            assert!(is_date(date), "{date}");
+        }
+        for date in [
+            "2026-1-31",
+            "2026-00-10",
+            "2026-13-01",
+            "2026-01-00",
+            "2026-01-32",
+            "2025-02-29",
+            "2100-02-29",
+            "2025-04-31",
+        ] {
+            assert!(!is_date(date), "{date}");
+        }
+    }
+
+    #[test]
+    fn days_to_dates() {
+        for (days, date) in [
+            (-1, "1969-12-31"),
+            (0, "1970-01-01"),
+            (11016, "2000-02-29"),
+            (11017, "2000-03-01"),
+            (19722, "2023-12-31"),
+            (19723, "2024-01-01"),
+            (19782, "2024-02-29"),
+            (47540, "2100-02-28"),
+            (47541, "2100-03-01"),
+        ] {
+            assert_eq!(civil_from_days(days), date);
+        }
+    }
+}
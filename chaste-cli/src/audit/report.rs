@@ -0,0 +1,140 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::path::Path;
+
+use chaste::types::ProviderMeta;
+use chaste::PackageID;
+use serde_json::{json, Value};
+
+use crate::audit::checks::Finding;
+use crate::audit::Outcome;
+
+fn finding_to_json(finding: &Finding) -> Value {
+    json!({
+        "check": finding.check.id,
+        "severity": finding.severity.to_string(),
+        "name": finding.name,
+        "version": finding.version,
+        "path": finding.path,
+        "message": finding.message,
+        "remediation": finding.check.remediation,
+        "rule": finding.rule,
+        "suppressed": finding.suppressed.as_ref().map(|s| json!({
+            "rule": s.rule,
+            "reason": s.reason,
+        })),
+    })
+}
+
+/// Checks run with whether they passed, and their findings.
+pub(super) fn to_json(
+    chastefile: &chaste::Chastefile<chaste::Meta>,
+    member: Option<PackageID>,
+    packages: usize,
+    outcomes: &[Outcome],
+) -> Value {
+    let meta = chastefile.meta();
+    json!({
+        "lockfile": {
+            "provider": meta.provider_name(),
+            "version": meta.lockfile_version().map(|lv| lv.to_string()),
+        },
+        "member": member.and_then(|pid| chastefile.package(pid).name().map(|n| n.to_string())),
+        "packagesChecked": packages,
+        "checks": outcomes
+            .iter()
+            .map(|outcome| json!({
+                "id": outcome.check.id,
+                "description": outcome.check.description,
+                "severity": outcome.check.severity.to_string(),
+                "passed": outcome.passed(),
+                "failures": outcome.failures().count(),
+            }))
+            .collect::<Vec<Value>>(),
+        "findings": outcomes
+            .iter()
+            .flat_map(|o| &o.findings)
+            .map(finding_to_json)
+            .collect::<Vec<Value>>(),
+    })
+}
+
+/// SARIF 2.1.0 log, with a rule for each check run, for code scanning dashboards.
+pub(super) fn to_sarif(outcomes: &[Outcome], lockfile: Option<&Path>) -> Value {
+    let rules: Vec<Value> = outcomes
+        .iter()
+        .map(|outcome| {
+            let check = outcome.check;
+            json!({
+                "id": check.id,
+                "shortDescription": { "text": format!("Packages with {}", check.description) },
+                "help": { "text": check.remediation },
+                "defaultConfiguration": { "level": check.severity.to_string() },
+            })
+        })
+        .collect();
+    let results: Vec<Value> = outcomes
+        .iter()
+        .enumerate()
+        .flat_map(|(i, outcome)| outcome.findings.iter().map(move |f| (i, f)))
+        .map(|(rule_index, finding)| {
+            let mut result = json!({
+                "ruleId": finding.check.id,
+                "ruleIndex": rule_index,
+                "level": finding.severity.to_string(),
+                "message": { "text": finding.message },
+                "logicalLocations": [{
+                    "name": finding.path.last(),
+                    "fullyQualifiedName": finding.path.join(" > "),
+                    "kind": "package",
+                }],
+            });
+            if let Some(lockfile) = lockfile {
+                result["locations"] = json!([{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": lockfile_uri(lockfile),
+                            "uriBaseId": "%SRCROOT%",
+                        },
+                    },
+                }]);
+            }
+            if let Some(rule) = &finding.rule {
+                result["properties"] = json!({ "policyRule": rule });
+            }
+            if let Some(suppressed) = &finding.suppressed {
+                let mut suppression = json!({ "kind": "external", "status": "accepted" });
+                if let Some(reason) = &suppressed.reason {
+                    suppression["justification"] = json!(reason);
+                }
+                result["suppressions"] = json!([suppression]);
+            }
+            result
+        })
+        .collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "chaste",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// Relative URI of the lockfile, with forward slashes also on Windows.
+fn lockfile_uri(lockfile: &Path) -> String {
+    lockfile
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
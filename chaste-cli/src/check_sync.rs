@@ -0,0 +1,44 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+use argh::FromArgs;
+use chaste::PackageID;
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "check-sync")]
+/// Check that the lockfile is up to date with package.json files
+pub struct CheckSync {
+    #[argh(switch)]
+    /// an outdated lockfile should not result in non-zero exit code
+    failures_ok: bool,
+}
+
+pub fn run(
+    sub: CheckSync,
+    chastefile: chaste::Chastefile<chaste::Meta>,
+    member: Option<PackageID>,
+    root: &Path,
+) -> Result<()> {
+    let drifts = chaste::drift::check(&chastefile, root, &chaste::RealFileSource)
+        .context("Could not compare the lockfile with package.json files")?;
+    // Inside a workspace member, only its package.json.
+    let drifts: Vec<_> = drifts
+        .into_iter()
+        .filter(|d| member.is_none_or(|m| d.importer == m))
+        .collect();
+
+    if drifts.is_empty() {
+        println!("The lockfile is up to date.");
+        return Ok(());
+    }
+    for drift in &drifts {
+        println!("{drift}");
+    }
+    if !sub.failures_ok {
+        std::process::exit(1);
+    }
+    Ok(())
+}
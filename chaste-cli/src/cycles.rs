@@ -0,0 +1,71 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use argh::FromArgs;
+use chaste::PackageID;
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "cycles")]
+/// Find packages that depend on each other in a cycle
+pub struct Cycles {
+    #[argh(switch)]
+    /// do not follow devDependencies
+    prod: bool,
+
+    #[argh(switch)]
+    /// only cycles between the root package and workspace members
+    workspace: bool,
+
+    #[argh(switch)]
+    /// finding cycles should not result in non-zero exit code
+    failures_ok: bool,
+}
+
+fn describe(chastefile: &chaste::Chastefile<chaste::Meta>, pid: PackageID) -> String {
+    let package = chastefile.package(pid);
+    let name = package.name().map(|n| n.as_ref()).unwrap_or("[unnamed]");
+    match package.version() {
+        Some(version) => format!("{name}@{version}"),
+        None => name.to_string(),
+    }
+}
+
+pub fn run(sub: Cycles, chastefile: chaste::Chastefile<chaste::Meta>) -> Result<()> {
+    let cycles = if sub.workspace {
+        chastefile.workspace_cycles(sub.prod)
+    } else {
+        chastefile.cycles(sub.prod)
+    };
+    if cycles.is_empty() {
+        println!("No cycles.");
+        return Ok(());
+    }
+
+    let mut cycles: Vec<Vec<String>> = cycles
+        .into_iter()
+        .map(|cycle| {
+            let mut packages: Vec<String> = cycle
+                .into_iter()
+                .map(|pid| describe(&chastefile, pid))
+                .collect();
+            packages.sort_unstable();
+            packages
+        })
+        .collect();
+    cycles.sort_unstable();
+    for packages in &cycles {
+        match &packages[..] {
+            [package] => println!("{package} depends on itself"),
+            packages => println!(
+                "Cycle of {} packages:\n\t{}",
+                packages.len(),
+                packages.join("\n\t")
+            ),
+        }
+    }
+    if !sub.failures_ok {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,110 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::{bail, Result};
+use argh::FromArgs;
+use chaste::{Package, PackageID};
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "dedupe")]
+/// Find packages locked in multiple versions, and plan how to have fewer of them
+pub struct Dedupe {
+    #[argh(switch)]
+    /// only print the plan. Changing the lockfile is not supported yet
+    dry_run: bool,
+}
+
+fn version(package: &Package) -> String {
+    package
+        .version()
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "[unversioned]".to_string())
+}
+
+fn describe(chastefile: &chaste::Chastefile<chaste::Meta>, pid: PackageID) -> String {
+    if pid == chastefile.root_package_id() {
+        return "[root]".to_string();
+    }
+    let package = chastefile.package(pid);
+    let name = package.name().map(|n| n.as_ref()).unwrap_or("[unnamed]");
+    match package.version() {
+        Some(version) => format!("{name}@{version}"),
+        None => name.to_string(),
+    }
+}
+
+pub fn run(sub: Dedupe, chastefile: chaste::Chastefile<chaste::Meta>) -> Result<()> {
+    if !sub.dry_run {
+        bail!("Only planning is supported, run with --dry-run");
+    }
+
+    let duplicates = chastefile.duplicates();
+    if duplicates.is_empty() {
+        println!("No duplicates.");
+        return Ok(());
+    }
+    let mut removable = 0;
+    for duplicate in &duplicates {
+        let installations: usize = duplicate
+            .versions
+            .iter()
+            .map(|v| v.installations.len())
+            .sum();
+        let count = duplicate.versions.len();
+        print!(
+            "{}: {count} version{}",
+            duplicate.name,
+            if count == 1 { "" } else { "s" }
+        );
+        if installations > 0 {
+            print!(
+                ", installed at {installations} path{}",
+                if installations == 1 { "" } else { "s" }
+            );
+        }
+        println!();
+        for dv in &duplicate.versions {
+            let paths: Vec<&str> = dv.installations.iter().map(|i| i.path().as_ref()).collect();
+            if paths.is_empty() {
+                println!("\t{}", version(dv.package));
+            } else {
+                println!("\t{} at {}", version(dv.package), paths.join(", "));
+            }
+            for dependent in &dv.dependents {
+                let from = describe(&chastefile, dependent.from);
+                match dependent.svs() {
+                    Some(svs) => println!("\t\t{from} wants {:?}", svs.as_ref()),
+                    None => println!("\t\t{from}"),
+                }
+            }
+        }
+
+        let plan = duplicate.plan();
+        if plan.moves.is_empty() && plan.remove.is_empty() {
+            println!("\tNothing to deduplicate.");
+            continue;
+        }
+        for dedupe_move in &plan.moves {
+            println!(
+                "\tMove {} from {} to {}",
+                describe(&chastefile, dedupe_move.dependency.from),
+                version(chastefile.package(dedupe_move.dependency.on)),
+                version(chastefile.package(dedupe_move.to)),
+            );
+        }
+        let mut removed: Vec<String> = plan
+            .remove
+            .iter()
+            .map(|pid| version(chastefile.package(*pid)))
+            .collect();
+        removed.sort_unstable();
+        println!("\tRemove {}", removed.join(", "));
+        removable += plan.remove.len();
+    }
+    println!();
+    println!(
+        "{removable} package{} can be removed.",
+        if removable == 1 { "" } else { "s" }
+    );
+    Ok(())
+}
@@ -0,0 +1,102 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context as _, Result};
+use argh::FromArgs;
+use chaste::sqlite::{lockfile_hash, to_sql, Project};
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "export")]
+/// Export the lockfile for other tools
+pub struct Export {
+    #[argh(subcommand)]
+    sub: ExportSubcommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum ExportSubcommand {
+    Sqlite(Sqlite),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "sqlite")]
+/// Export packages, dependencies and installations into an SQLite database,
+/// with the sqlite3 command
+struct Sqlite {
+    #[argh(positional)]
+    /// the database to write
+    output: PathBuf,
+
+    #[argh(switch)]
+    /// keep the other lockfiles exported for the same project path instead of replacing them.
+    /// Other projects are always kept
+    append: bool,
+
+    #[argh(option)]
+    /// path to store the project as, e.g. the name of the repository.
+    /// Default: the absolute path of the project
+    project: Option<String>,
+
+    #[argh(switch)]
+    /// write the SQL statements to the output instead, to run them some other way
+    sql: bool,
+}
+
+pub fn run(
+    sub: Export,
+    chastefile: chaste::Chastefile<chaste::Meta>,
+    cwd: &Path,
+    root: &Path,
+    contents: &str,
+) -> Result<()> {
+    let ExportSubcommand::Sqlite(sqlite) = sub.sub;
+    let path = match sqlite.project {
+        Some(project) => project,
+        None => {
+            let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+            root.to_string_lossy().into_owned()
+        }
+    };
+    let hash = lockfile_hash(contents);
+    let project = Project {
+        path: &path,
+        lockfile_hash: &hash,
+        keep_other_lockfiles: sqlite.append,
+    };
+    let output = cwd.join(&sqlite.output);
+
+    if sqlite.sql {
+        let mut sql = match sqlite.append {
+            true => fs::read_to_string(&output).unwrap_or_default(),
+            false => String::new(),
+        };
+        sql.push_str(&to_sql(&chastefile, &project));
+        fs::write(&output, sql).with_context(|| format!("Could not write {output:?}"))?;
+    } else {
+        run_sqlite3(&output, &to_sql(&chastefile, &project))
+            .with_context(|| format!("Could not export to {output:?} with sqlite3"))?;
+    }
+    Ok(())
+}
+
+fn run_sqlite3(database: &Path, sql: &str) -> Result<()> {
+    let mut child = Command::new("sqlite3")
+        .arg("-bail")
+        .arg(database)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    child.stdin.take().unwrap().write_all(sql.as_bytes())?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
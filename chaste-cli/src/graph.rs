@@ -0,0 +1,49 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use argh::FromArgs;
+use chaste::graph::{Graph as DependencyGraph, GraphFormat};
+use chaste::PackageID;
+
+use crate::why::matching_packages;
+
+fn format_from_name(name: &str) -> Result<GraphFormat, String> {
+    GraphFormat::from_name(name).ok_or_else(|| format!("Unknown graph format: {name:?}"))
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "graph")]
+/// Draw the graph of dependencies, from the root package or around a package
+pub struct Graph {
+    #[argh(option, default = "GraphFormat::Dot", from_str_fn(format_from_name))]
+    /// format to draw in: "dot", "mermaid" or "graphml". Default: dot
+    format: GraphFormat,
+
+    #[argh(option)]
+    /// only draw this package, what it depends on and what depends on it,
+    /// optionally with a version range, like "ms@^2.1.0".
+    /// Inside a workspace member, the member is drawn this way by default
+    focus: Option<String>,
+
+    #[argh(option)]
+    /// only draw packages this many dependencies away. Default: no limit
+    depth: Option<usize>,
+}
+
+pub fn run(
+    sub: Graph,
+    chastefile: chaste::Chastefile<chaste::Meta>,
+    member: Option<PackageID>,
+) -> Result<()> {
+    let focus = match &sub.focus {
+        Some(spec) => Some(matching_packages(&chastefile, spec)?),
+        None => member.map(|member_pid| vec![member_pid]),
+    };
+    let graph = match focus {
+        Some(focus) => DependencyGraph::around(&chastefile, &focus, sub.depth),
+        None => DependencyGraph::new(&chastefile, sub.depth),
+    };
+    print!("{}", graph.render(sub.format));
+    Ok(())
+}
@@ -0,0 +1,121 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use anyhow::{bail, Context as _, Result};
+use argh::FromArgs;
+use chaste::git::{self, GitFileSource};
+use chaste::Implementation;
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "history")]
+/// How did the dependencies change in each commit that touched the lockfile?
+pub struct History {
+    #[argh(option, default = "String::from(\"HEAD\")")]
+    /// revision to walk the history back from
+    revision: String,
+
+    #[argh(option)]
+    /// only show commits that changed this package
+    package: Option<String>,
+}
+
+/// Package name to the set of its versions.
+type Versions = BTreeMap<String, BTreeSet<String>>;
+
+fn versions(chastefile: &chaste::Chastefile<chaste::Meta>) -> Versions {
+    let mut versions = Versions::new();
+    for pkg in chastefile.packages() {
+        let Some(name) = pkg.name() else {
+            continue;
+        };
+        versions
+            .entry(name.to_string())
+            .or_default()
+            .extend(pkg.version().map(|v| v.to_string()));
+    }
+    versions
+}
+
+fn join(versions: &BTreeSet<String>) -> String {
+    versions.iter().cloned().collect::<Vec<String>>().join(", ")
+}
+
+fn with_versions(name: &str, versions: &BTreeSet<String>) -> String {
+    if versions.is_empty() {
+        name.to_string()
+    } else {
+        format!("{name}@{}", join(versions))
+    }
+}
+
+fn changes(before: &Versions, after: &Versions, package: Option<&str>) -> Vec<String> {
+    let names: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    names
+        .into_iter()
+        .filter(|n| package.is_none_or(|p| p == n.as_str()))
+        .filter_map(|name| match (before.get(name), after.get(name)) {
+            (None, Some(a)) => Some(format!("+ {}", with_versions(name, a))),
+            (Some(b), None) => Some(format!("- {}", with_versions(name, b))),
+            (Some(b), Some(a)) if a != b => Some(format!("~ {name} {} -> {}", join(b), join(a))),
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn run(sub: History, root: &Path, implem: Option<Implementation>) -> Result<()> {
+    let repo_root = git::repository_root(root)
+        .with_context(|| format!("Could not find a git repository at {root:?}"))?;
+    let root_path = root
+        .canonicalize()?
+        .strip_prefix(repo_root.canonicalize()?)?
+        .to_path_buf();
+
+    let implem = match implem {
+        Some(i) => i,
+        None => {
+            let source = GitFileSource::new(&repo_root, &sub.revision)?;
+            match *chaste::implementations_from_source(&root_path, &source) {
+                [] => bail!("No lockfile was found at {}", sub.revision),
+                [i] => i,
+                ref implems => bail!("Multiple lockfiles found at {}: {implems:?}", sub.revision),
+            }
+        }
+    };
+    let lockfile_paths = implem.lockfile_names().iter().map(|n| root_path.join(n));
+    let commits = GitFileSource::log(&repo_root, &sub.revision, lockfile_paths)?;
+
+    let mut previous = Versions::new();
+    for commit in commits {
+        let source = GitFileSource::new(&repo_root, &commit.hash)?;
+        let header = format!(
+            "{} {} {}",
+            &commit.hash[..10.min(commit.hash.len())],
+            &commit.date[..10.min(commit.date.len())],
+            commit.subject
+        );
+        let chastefile = match chaste::from_source_with_implementation(&root_path, implem, &source)
+        {
+            Ok(c) => c,
+            Err(e) => {
+                if sub.package.is_none() {
+                    println!("{header}\n  could not parse the lockfile: {e}");
+                }
+                continue;
+            }
+        };
+        let current = versions(&chastefile);
+        let lines = changes(&previous, &current, sub.package.as_deref());
+        if sub.package.is_none() || !lines.is_empty() {
+            println!("{header} ({} packages)", chastefile.packages().len());
+            for line in lines {
+                println!("  {line}");
+            }
+        }
+        previous = current;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use argh::FromArgs;
+use chaste::compare::{all_from_root_path, disagreements};
+use chaste::package_manager::preferred_implementation;
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "lockfiles")]
+/// Parse every lockfile present and show where they disagree
+pub struct Lockfiles {
+    #[argh(switch)]
+    /// disagreements should not result in non-zero exit code
+    failures_ok: bool,
+}
+
+pub fn run(sub: Lockfiles, root: &Path) -> Result<()> {
+    let parsed = all_from_root_path(root);
+    if parsed.is_empty() {
+        bail!("No lockfile was found in {root:?}");
+    }
+    let implems: Vec<chaste::Implementation> = parsed.iter().map(|(i, _)| *i).collect();
+    println!(
+        "Found lockfiles of: {}",
+        implems
+            .iter()
+            .map(|i| i.name())
+            .collect::<Vec<&str>>()
+            .join(", ")
+    );
+    if implems.len() > 1 {
+        match preferred_implementation(root, &implems) {
+            Some((implem, preference)) => {
+                println!("In use: {} (from {preference})", implem.name())
+            }
+            None => println!("Could not tell which one is in use."),
+        }
+    }
+
+    let mut chastefiles = Vec::with_capacity(parsed.len());
+    for (implem, result) in parsed {
+        match result {
+            Ok(chastefile) => chastefiles.push((implem, chastefile)),
+            Err(e) => println!("❌ Could not parse the {} lockfile: {e}", implem.name()),
+        }
+    }
+
+    let disagreements = disagreements(&chastefiles);
+    if disagreements.is_empty() {
+        println!("✅ Lockfiles agree on all package versions");
+        return Ok(());
+    }
+    println!(
+        "❌ {} package{} locked differently:",
+        disagreements.len(),
+        if disagreements.len() == 1 { "" } else { "s" }
+    );
+    for disagreement in &disagreements {
+        let versions: Vec<String> = disagreement
+            .versions
+            .iter()
+            .map(|(implem, versions)| {
+                if versions.is_empty() {
+                    format!("{} (missing)", implem.name())
+                } else {
+                    let versions: Vec<&str> = versions.iter().map(String::as_str).collect();
+                    format!("{} {}", implem.name(), versions.join(", "))
+                }
+            })
+            .collect();
+        println!("\t{}: {}", disagreement.package_name, versions.join("; "));
+    }
+
+    if !sub.failures_ok {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,291 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use anyhow::Result;
+use argh::FromArgs;
+use chaste::{Dependency, DependencyKind, PackageID};
+use serde_json::{Map, Value};
+
+use crate::why::matching_packages;
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "ls")]
+/// Print the tree of dependencies, from the root package or the current workspace member
+pub struct Ls {
+    #[argh(positional)]
+    /// only show branches that lead to this package, optionally with a version range,
+    /// like "ms@^2.1.0"
+    package: Option<String>,
+
+    #[argh(option)]
+    /// only show dependencies this deep. Default: no limit
+    depth: Option<usize>,
+
+    #[argh(switch)]
+    /// do not show devDependencies
+    prod: bool,
+
+    #[argh(switch)]
+    /// print the tree as JSON
+    json: bool,
+}
+
+/// How a package got into the tree.
+#[derive(Clone, Copy)]
+enum Edge<'a> {
+    Start,
+    Dependency(&'a Dependency),
+    /// A workspace member under the root package, as members are not dependencies of the root.
+    Workspace,
+}
+
+struct Node<'a> {
+    pid: PackageID,
+    edge: Edge<'a>,
+    /// Already shown with its dependencies somewhere above.
+    deduped: bool,
+    children: Vec<Node<'a>>,
+}
+
+struct Builder<'a> {
+    chastefile: &'a chaste::Chastefile<chaste::Meta>,
+    prod: bool,
+    depth: Option<usize>,
+    /// Packages the branches have to lead to, if filtering.
+    leads: Option<HashSet<PackageID>>,
+    levels: HashMap<PackageID, usize>,
+    expanded: HashSet<PackageID>,
+}
+
+impl<'a> Builder<'a> {
+    fn sort_key(&self, pid: PackageID, edge: Edge) -> (String, String) {
+        let package = self.chastefile.package(pid);
+        let name = match edge {
+            Edge::Dependency(d) => d.alias_name().map(|a| a.to_string()),
+            _ => None,
+        };
+        (
+            name.or_else(|| package.name().map(|n| n.to_string()))
+                .unwrap_or_default(),
+            package.version().map(|v| v.to_string()).unwrap_or_default(),
+        )
+    }
+
+    /// Packages shown under `pid`.
+    fn edges(&self, pid: PackageID) -> Vec<(PackageID, Edge<'a>)> {
+        let mut edges: Vec<(PackageID, Edge<'a>)> = Vec::new();
+        for dependency in self.chastefile.package_dependencies(pid) {
+            if !(self.prod && dependency.kind.is_dev()) {
+                edges.push((dependency.on, Edge::Dependency(dependency)));
+            }
+        }
+        if pid == self.chastefile.root_package_id() {
+            for &member in self.chastefile.workspace_member_ids() {
+                if !edges.iter().any(|(on, _)| *on == member) {
+                    edges.push((member, Edge::Workspace));
+                }
+            }
+        }
+        if let Some(leads) = &self.leads {
+            edges.retain(|(on, _)| leads.contains(on));
+        }
+        edges.sort_by_cached_key(|(on, edge)| self.sort_key(*on, *edge));
+        edges
+    }
+
+    /// How deep in the tree each package is first found.
+    fn find_levels(&mut self, start: PackageID) {
+        self.levels.insert(start, 0);
+        let mut q: VecDeque<PackageID> = VecDeque::from([start]);
+        while let Some(pid) = q.pop_front() {
+            let level = self.levels[&pid] + 1;
+            for (on, _) in self.edges(pid) {
+                if let Entry::Vacant(entry) = self.levels.entry(on) {
+                    entry.insert(level);
+                    q.push_back(on);
+                }
+            }
+        }
+    }
+
+    fn build(&mut self, pid: PackageID, edge: Edge<'a>, level: usize) -> Node<'a> {
+        let mut node = Node {
+            pid,
+            edge,
+            deduped: false,
+            children: Vec::new(),
+        };
+        if self.depth.is_some_and(|depth| level >= depth) {
+            return node;
+        }
+        // Dependencies are shown where the package is the least deep, the first time.
+        if self.levels[&pid] < level || !self.expanded.insert(pid) {
+            node.deduped = true;
+            return node;
+        }
+        node.children = self
+            .edges(pid)
+            .into_iter()
+            .map(|(on, edge)| self.build(on, edge, level + 1))
+            .collect();
+        node
+    }
+}
+
+/// Packages from which any of `targets` can be reached.
+fn leading_to(
+    chastefile: &chaste::Chastefile<chaste::Meta>,
+    targets: &[PackageID],
+) -> HashSet<PackageID> {
+    let mut leads: HashSet<PackageID> = targets.iter().copied().collect();
+    let mut q: VecDeque<PackageID> = targets.iter().copied().collect();
+    while let Some(pid) = q.pop_front() {
+        let mut dependents: Vec<PackageID> = chastefile
+            .package_dependents(pid)
+            .into_iter()
+            .map(|d| d.from)
+            .collect();
+        if chastefile.workspace_member_ids().contains(&pid) {
+            dependents.push(chastefile.root_package_id());
+        }
+        for dependent in dependents {
+            if leads.insert(dependent) {
+                q.push_back(dependent);
+            }
+        }
+    }
+    leads
+}
+
+fn kind_name(kind: DependencyKind) -> &'static str {
+    match kind {
+        DependencyKind::DevDependency => "dev",
+        DependencyKind::OptionalDependency => "optional",
+        DependencyKind::PeerDependency => "peer",
+        DependencyKind::OptionalPeerDependency => "optional peer",
+        _ => "prod",
+    }
+}
+
+fn label(chastefile: &chaste::Chastefile<chaste::Meta>, node: &Node) -> String {
+    let package = chastefile.package(node.pid);
+    let name = match package.name() {
+        Some(name) => name.to_string(),
+        None if node.pid == chastefile.root_package_id() => "[root]".to_string(),
+        None => "[unnamed]".to_string(),
+    };
+    let mut label = match package.version() {
+        Some(version) => format!("{name}@{version}"),
+        None => name,
+    };
+    let mut notes: Vec<String> = Vec::new();
+    match node.edge {
+        Edge::Dependency(dependency) => {
+            if let Some(alias) = dependency.alias_name() {
+                label = format!("{alias}@npm:{label}");
+            }
+            if dependency.kind != DependencyKind::Dependency {
+                notes.push(kind_name(dependency.kind).to_string());
+            }
+        }
+        Edge::Workspace => notes.push("workspace".to_string()),
+        Edge::Start => {}
+    }
+    if let Some(patch) = package.derivation_meta().and_then(|d| d.patch()) {
+        notes.push(format!("patched with {}", patch.path()));
+    }
+    if !notes.is_empty() {
+        label.push_str(&format!(" ({})", notes.join(", ")));
+    }
+    if node.deduped {
+        label.push_str(" deduped");
+    }
+    label
+}
+
+fn print_tree(chastefile: &chaste::Chastefile<chaste::Meta>, node: &Node, prefix: &str) {
+    for (i, child) in node.children.iter().enumerate() {
+        let last = i == node.children.len() - 1;
+        let (branch, indent) = match last {
+            true => ("└── ", "    "),
+            false => ("├── ", "│   "),
+        };
+        println!("{prefix}{branch}{}", label(chastefile, child));
+        print_tree(chastefile, child, &format!("{prefix}{indent}"));
+    }
+}
+
+fn to_json(chastefile: &chaste::Chastefile<chaste::Meta>, node: &Node) -> Value {
+    let package = chastefile.package(node.pid);
+    let mut object = Map::new();
+    object.insert(
+        "name".to_string(),
+        package.name().map(|n| n.to_string()).into(),
+    );
+    object.insert(
+        "version".to_string(),
+        package.version().map(|v| v.to_string()).into(),
+    );
+    match node.edge {
+        Edge::Dependency(dependency) => {
+            object.insert("kind".to_string(), kind_name(dependency.kind).into());
+            if let Some(alias) = dependency.alias_name() {
+                object.insert("alias".to_string(), alias.to_string().into());
+            }
+        }
+        Edge::Workspace => {
+            object.insert("kind".to_string(), "workspace".into());
+        }
+        Edge::Start => {}
+    }
+    if let Some(patch) = package.derivation_meta().and_then(|d| d.patch()) {
+        object.insert("patch".to_string(), patch.path().into());
+    }
+    if node.deduped {
+        object.insert("deduped".to_string(), true.into());
+    }
+    if !node.children.is_empty() {
+        let children = node.children.iter().map(|c| to_json(chastefile, c));
+        object.insert("dependencies".to_string(), children.collect());
+    }
+    Value::Object(object)
+}
+
+pub fn run(
+    sub: Ls,
+    chastefile: chaste::Chastefile<chaste::Meta>,
+    member: Option<PackageID>,
+) -> Result<()> {
+    let leads = match &sub.package {
+        Some(spec) => {
+            let targets = matching_packages(&chastefile, spec)?;
+            Some(leading_to(&chastefile, &targets))
+        }
+        None => None,
+    };
+    let start = member.unwrap_or(chastefile.root_package_id());
+    let mut builder = Builder {
+        chastefile: &chastefile,
+        prod: sub.prod,
+        depth: sub.depth,
+        leads,
+        levels: HashMap::new(),
+        expanded: HashSet::new(),
+    };
+    builder.find_levels(start);
+    let tree = builder.build(start, Edge::Start, 0);
+
+    if sub.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&to_json(&chastefile, &tree))?
+        );
+    } else {
+        println!("{}", label(&chastefile, &tree));
+        print_tree(&chastefile, &tree, "");
+    }
+    Ok(())
+}
@@ -1,13 +1,29 @@
 // SPDX-FileCopyrightText: 2024 The Chaste Authors
 // SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
 
-use std::path::PathBuf;
+use std::fs;
+use std::io::{self, Read as _};
+use std::path::{Path, PathBuf};
 
-use anyhow::{Context as _, Result};
+use anyhow::{bail, Context as _, Result};
 use argh::FromArgs;
+use chaste::types::Severity;
 
+mod affected;
 mod audit;
+mod check_sync;
+mod cycles;
+mod dedupe;
+mod export;
+mod graph;
+mod history;
+mod lockfiles;
+mod ls;
+mod prune;
+mod query;
+mod stats;
 mod why;
+mod workspaces;
 
 fn implem_from_name(name: &str) -> Result<chaste::Implementation, String> {
     chaste::Implementation::from_name(name)
@@ -27,13 +43,241 @@ struct Args {
     #[argh(option)]
     /// directory to be checked
     cwd: Option<PathBuf>,
+
+    #[argh(option)]
+    /// lockfile to be checked instead of the one in the directory, or "-" to read it from stdin
+    lockfile: Option<PathBuf>,
+
+    #[argh(switch)]
+    /// skip packages and dependencies that cannot be resolved, with a warning, instead of failing
+    lenient: bool,
 }
 
 #[derive(FromArgs)]
 #[argh(subcommand)]
 enum Subcommand {
+    Affected(affected::Affected),
     Audit(audit::Audit),
+    CheckSync(check_sync::CheckSync),
+    Cycles(cycles::Cycles),
+    Dedupe(dedupe::Dedupe),
+    Export(export::Export),
+    Graph(graph::Graph),
+    History(history::History),
+    Lockfiles(lockfiles::Lockfiles),
+    Ls(ls::Ls),
+    Prune(prune::Prune),
+    Query(query::Query),
+    Stats(stats::Stats),
     Why(why::Why),
+    Workspaces(workspaces::Workspaces),
+}
+
+type Parsed = chaste::error::Result<(chaste::Chastefile<chaste::Meta>, Vec<chaste::error::Error>)>;
+
+fn strict(result: chaste::error::Result<chaste::Chastefile<chaste::Meta>>) -> Parsed {
+    result.map(|chastefile| (chastefile, Vec::new()))
+}
+
+/// Parse the lockfile, and find the workspace member `cwd` is in, if it's not the root.
+///
+/// If `lenient`, problems with single packages or dependencies are shown as warnings.
+fn load_chastefile(
+    cwd: &Path,
+    lockfile: Option<PathBuf>,
+    implem: Option<chaste::Implementation>,
+    lenient: bool,
+) -> Result<(chaste::Chastefile<chaste::Meta>, Option<chaste::PackageID>)> {
+    load_chastefile_skipping(cwd, lockfile, implem, lenient, |_| true)
+}
+
+/// Like [`load_chastefile`], but if `lenient`, only the problems that `skip` returns true for
+/// are shown as warnings. The first other one fails the parse.
+fn load_chastefile_skipping(
+    cwd: &Path,
+    lockfile: Option<PathBuf>,
+    implem: Option<chaste::Implementation>,
+    lenient: bool,
+    skip: fn(&chaste::error::Error) -> bool,
+) -> Result<(chaste::Chastefile<chaste::Meta>, Option<chaste::PackageID>)> {
+    let root = root_path(cwd, lockfile.as_deref());
+    let source = &chaste::RealFileSource;
+    // The parse result, the lockfile to point diagnostics to, and what to say on failure.
+    let (parsed, lockfile_text, context): (Parsed, Option<(PathBuf, String)>, String) =
+        match (lockfile, implem) {
+            (Some(lockfile), implem) if lockfile == Path::new("-") => {
+                let mut contents = String::new();
+                io::stdin().read_to_string(&mut contents)?;
+                let parsed = match (implem, lenient) {
+                    (Some(i), false) => strict(chaste::from_str_with_implementation(
+                        &contents, i, cwd, source,
+                    )),
+                    (Some(i), true) => {
+                        chaste::from_str_with_implementation_lenient(&contents, i, cwd, source)
+                    }
+                    (None, false) => strict(chaste::from_str(&contents, cwd, source)),
+                    (None, true) => chaste::from_str_lenient(&contents, cwd, source),
+                };
+                (
+                    parsed,
+                    Some(("<stdin>".into(), contents)),
+                    "Could not parse the lockfile from stdin".to_string(),
+                )
+            }
+            (Some(lockfile), implem) => {
+                let lockfile = cwd.join(lockfile);
+                let parsed = match (implem, lenient) {
+                    (Some(i), false) => {
+                        strict(chaste::from_lockfile_path_with_implementation(&lockfile, i))
+                    }
+                    (Some(i), true) => fs::read_to_string(&lockfile)
+                        .map_err(chaste::error::Error::from)
+                        .and_then(|contents| {
+                            chaste::from_str_with_implementation_lenient(
+                                &contents, i, &root, source,
+                            )
+                        }),
+                    (None, false) => strict(chaste::from_lockfile_path(&lockfile)),
+                    (None, true) => chaste::from_lockfile_path_lenient(&lockfile),
+                };
+                (
+                    parsed,
+                    read_lockfile(cwd, Some(&lockfile)),
+                    format!("Could not parse the lockfile {lockfile:?}"),
+                )
+            }
+            (None, Some(implem)) => {
+                let lockfile = lockfile_in_root(&root, implem);
+                let parsed = match (&lockfile, lenient) {
+                    (Some(lockfile), true) => fs::read_to_string(lockfile)
+                        .map_err(chaste::error::Error::from)
+                        .and_then(|contents| {
+                            chaste::from_str_with_implementation_lenient(
+                                &contents, implem, &root, source,
+                            )
+                        }),
+                    _ => strict(chaste::from_root_path_with_implementation(&root, implem)),
+                };
+                (
+                    parsed,
+                    read_lockfile(cwd, lockfile.as_deref()),
+                    format!("Could not parse the lockfile from {root:?}"),
+                )
+            }
+            (None, None) => {
+                let parsed = if lenient {
+                    chaste::from_root_path_lenient(&root)
+                } else {
+                    strict(chaste::from_root_path(&root))
+                };
+                let lockfile =
+                    implementation_in_root(&root).and_then(|i| lockfile_in_root(&root, i));
+                (
+                    parsed,
+                    read_lockfile(cwd, lockfile.as_deref()),
+                    format!("Could not parse the lockfile from {root:?}"),
+                )
+            }
+        };
+    let parsed = parsed.and_then(|(chastefile, mut warnings)| {
+        match warnings.iter().position(|w| !skip(w)) {
+            Some(i) => Err(warnings.swap_remove(i)),
+            None => Ok((chastefile, warnings)),
+        }
+    });
+    let (chastefile, warnings) = match parsed {
+        Ok(p) => p,
+        Err(e) => {
+            if let Some((shown, contents)) = &lockfile_text {
+                print_diagnostic(&e, Severity::Error, shown, contents);
+            }
+            return Err(e).context(context);
+        }
+    };
+    for warning in &warnings {
+        match &lockfile_text {
+            Some((shown, contents)) => {
+                print_diagnostic(warning, Severity::Warning, shown, contents)
+            }
+            None => eprintln!("warning: {warning}"),
+        }
+    }
+    let member =
+        member_at(&chastefile, &root, cwd).filter(|pid| *pid != chastefile.root_package_id());
+    Ok((chastefile, member))
+}
+
+/// The directory of the project, that package.json files are read relative to.
+fn root_path(cwd: &Path, lockfile: Option<&Path>) -> PathBuf {
+    match lockfile {
+        Some(lockfile) if lockfile != Path::new("-") => cwd
+            .join(lockfile)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| cwd.to_path_buf()),
+        Some(_) => cwd.to_path_buf(),
+        None => chaste::find_root_path(cwd).unwrap_or_else(|| cwd.to_path_buf()),
+    }
+}
+
+/// The implementation whose lockfile in `root` is in use, if it can be told.
+fn implementation_in_root(root: &Path) -> Option<chaste::Implementation> {
+    let implems = chaste::implementations_from_root_path(root);
+    match *implems {
+        [implem] => Some(implem),
+        _ => chaste::package_manager::preferred_implementation(root, &implems)
+            .map(|(implem, _)| implem),
+    }
+}
+
+fn lockfile_in_root(root: &Path, implem: chaste::Implementation) -> Option<PathBuf> {
+    implem
+        .lockfile_names()
+        .iter()
+        .map(|name| root.join(name))
+        .find(|path| path.is_file())
+}
+
+/// The lockfile contents, with its path as it should be shown.
+fn read_lockfile(cwd: &Path, lockfile: Option<&Path>) -> Option<(PathBuf, String)> {
+    let lockfile = lockfile?;
+    let contents = fs::read_to_string(lockfile).ok()?;
+    let shown = lockfile.strip_prefix(cwd).unwrap_or(lockfile);
+    Some((shown.to_path_buf(), contents))
+}
+
+/// Show where in the lockfile the problem is, if the error tells.
+fn print_diagnostic(
+    err: &chaste::error::Error,
+    severity: Severity,
+    lockfile: &Path,
+    contents: &str,
+) {
+    match err.diagnostic(lockfile, contents) {
+        Some(mut diagnostic) => {
+            diagnostic.severity = severity;
+            eprintln!("{}", diagnostic.render(contents));
+        }
+        // Errors are shown anyway when returned from main.
+        None if severity == Severity::Warning => eprintln!("{severity}: {err}"),
+        None => {}
+    }
+}
+
+fn member_at(
+    chastefile: &chaste::Chastefile<chaste::Meta>,
+    root: &Path,
+    cwd: &Path,
+) -> Option<chaste::PackageID> {
+    let cwd = cwd.canonicalize().ok()?;
+    let root = root.canonicalize().ok()?;
+    let relative = cwd.strip_prefix(root).ok()?;
+    let relative = relative
+        .components()
+        .map(|c| c.as_os_str().to_str())
+        .collect::<Option<Vec<&str>>>()?
+        .join("/");
+    Some(chastefile.workspace_member_containing(&relative))
 }
 
 fn main() -> Result<()> {
@@ -43,15 +287,140 @@ fn main() -> Result<()> {
         Some(p) => p,
         None => std::env::current_dir()?,
     };
-    let chastefile = if let Some(implem) = args.implem {
-        chaste::from_root_path_with_implementation(&cwd, implem)
-    } else {
-        chaste::from_root_path(&cwd)
-    }
-    .with_context(|| format!("Could not parse the lockfile from {cwd:?}"))?;
 
     match args.subcommand {
-        Subcommand::Audit(audit) => audit::run(audit, chastefile),
-        Subcommand::Why(why) => why::run(why, chastefile),
+        Subcommand::Affected(affected) => {
+            let root = root_path(&cwd, args.lockfile.as_deref());
+            let (chastefile, _) = load_chastefile(&cwd, args.lockfile, args.implem, args.lenient)?;
+            affected::run(affected, chastefile, &cwd, &root, args.implem)
+        }
+        Subcommand::Audit(audit) => {
+            let root = root_path(&cwd, args.lockfile.as_deref());
+            let (chastefile, member) =
+                load_chastefile(&cwd, args.lockfile.clone(), args.implem, args.lenient)?;
+            let lockfile = match args.lockfile {
+                Some(lockfile) if lockfile == Path::new("-") => None,
+                Some(lockfile) => Some(cwd.join(lockfile)),
+                None => chastefile
+                    .meta()
+                    .implementation()
+                    .and_then(|implem| lockfile_in_root(&root, implem)),
+            };
+            // Relative to the project, where code scanning expects files to be.
+            let lockfile =
+                lockfile.map(|l| l.strip_prefix(&root).map(Path::to_path_buf).unwrap_or(l));
+            audit::run(audit, chastefile, member, &root, lockfile.as_deref())
+        }
+        Subcommand::CheckSync(check_sync) => {
+            let root = root_path(&cwd, args.lockfile.as_deref());
+            let implem = args.implem.or_else(|| match args.lockfile.as_deref() {
+                Some(lockfile) if lockfile == Path::new("-") => None,
+                Some(lockfile) => lockfile
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .and_then(chaste::Implementation::from_lockfile_name),
+                None => implementation_in_root(&root),
+            });
+            // Yarn resolves from package.json files, so dependencies changed there
+            // cannot be found in the lockfile. They are reported as drift instead of failing,
+            // anything else wrong with the lockfile still fails without --lenient.
+            let (chastefile, member) =
+                if implem.is_some_and(|i| i.name() == "yarn") && !args.lenient {
+                    load_chastefile_skipping(&cwd, args.lockfile, args.implem, true, |e| {
+                        e.kind() == chaste::error::ErrorKind::DanglingReference
+                    })?
+                } else {
+                    load_chastefile(&cwd, args.lockfile, args.implem, args.lenient)?
+                };
+            check_sync::run(check_sync, chastefile, member, &root)
+        }
+        Subcommand::Cycles(cycles) => {
+            let (chastefile, _) = load_chastefile(&cwd, args.lockfile, args.implem, args.lenient)?;
+            cycles::run(cycles, chastefile)
+        }
+        Subcommand::Dedupe(dedupe) => {
+            let (chastefile, _) = load_chastefile(&cwd, args.lockfile, args.implem, args.lenient)?;
+            dedupe::run(dedupe, chastefile)
+        }
+        Subcommand::Export(export) => {
+            if args.lockfile.as_deref() == Some(Path::new("-")) {
+                bail!("--lockfile - cannot be used with export, the lockfile is read again");
+            }
+            let root = root_path(&cwd, args.lockfile.as_deref());
+            let (chastefile, _) =
+                load_chastefile(&cwd, args.lockfile.clone(), args.implem, args.lenient)?;
+            let lockfile = match args.lockfile {
+                Some(lockfile) => Some(cwd.join(lockfile)),
+                None => chastefile
+                    .meta()
+                    .implementation()
+                    .and_then(|implem| lockfile_in_root(&root, implem)),
+            };
+            let Some((_, contents)) = read_lockfile(&cwd, lockfile.as_deref()) else {
+                bail!("Could not read the lockfile again");
+            };
+            export::run(export, chastefile, &cwd, &root, &contents)
+        }
+        Subcommand::Graph(graph) => {
+            let (chastefile, member) =
+                load_chastefile(&cwd, args.lockfile, args.implem, args.lenient)?;
+            graph::run(graph, chastefile, member)
+        }
+        Subcommand::History(history) => {
+            if args.lockfile.is_some() {
+                bail!("--lockfile cannot be used with history, lockfiles are read from git");
+            }
+            let root = chaste::find_root_path(&cwd).unwrap_or(cwd);
+            history::run(history, &root, args.implem)
+        }
+        Subcommand::Lockfiles(lockfiles) => {
+            if args.lockfile.is_some() || args.implem.is_some() {
+                bail!("--lockfile and --implem cannot be used with lockfiles");
+            }
+            let root = chaste::find_root_path(&cwd).unwrap_or(cwd);
+            lockfiles::run(lockfiles, &root)
+        }
+        Subcommand::Ls(ls) => {
+            let (chastefile, member) =
+                load_chastefile(&cwd, args.lockfile, args.implem, args.lenient)?;
+            ls::run(ls, chastefile, member)
+        }
+        Subcommand::Prune(prune) => {
+            if args.lockfile.as_deref() == Some(Path::new("-")) {
+                bail!("--lockfile - cannot be used with prune, the lockfile is read again");
+            }
+            let root = root_path(&cwd, args.lockfile.as_deref());
+            let (chastefile, member) =
+                load_chastefile(&cwd, args.lockfile.clone(), args.implem, args.lenient)?;
+            let lockfile = match args.lockfile {
+                Some(lockfile) => Some(cwd.join(lockfile)),
+                None => chastefile
+                    .meta()
+                    .implementation()
+                    .and_then(|implem| lockfile_in_root(&root, implem)),
+            };
+            let Some((_, contents)) = read_lockfile(&cwd, lockfile.as_deref()) else {
+                bail!("Could not read the lockfile again");
+            };
+            prune::run(prune, chastefile, member, &contents)
+        }
+        Subcommand::Query(query) => {
+            let (chastefile, member) =
+                load_chastefile(&cwd, args.lockfile, args.implem, args.lenient)?;
+            query::run(query, chastefile, member)
+        }
+        Subcommand::Stats(stats) => {
+            let (chastefile, _) = load_chastefile(&cwd, args.lockfile, args.implem, args.lenient)?;
+            stats::run(stats, chastefile)
+        }
+        Subcommand::Why(why) => {
+            let (chastefile, member) =
+                load_chastefile(&cwd, args.lockfile, args.implem, args.lenient)?;
+            why::run(why, chastefile, member)
+        }
+        Subcommand::Workspaces(workspaces) => {
+            let (chastefile, _) = load_chastefile(&cwd, args.lockfile, args.implem, args.lenient)?;
+            workspaces::run(workspaces, chastefile)
+        }
     }
 }
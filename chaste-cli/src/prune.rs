@@ -0,0 +1,73 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::fs;
+use std::io::{self, Write as _};
+use std::path::PathBuf;
+
+use anyhow::{bail, Context as _, Result};
+use argh::FromArgs;
+use chaste::PackageID;
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "prune")]
+/// Write the lockfile with only what some workspace members need, e.g. for docker builds
+pub struct Prune {
+    #[argh(option)]
+    /// name or directory of a workspace member to keep, can be given multiple times.
+    /// Defaults to the workspace member the command is run in
+    member: Vec<String>,
+
+    #[argh(switch)]
+    /// leave out devDependencies
+    prod: bool,
+
+    #[argh(option, short = 'o')]
+    /// file to write the pruned lockfile to, instead of stdout
+    output: Option<PathBuf>,
+}
+
+fn find_member(chastefile: &chaste::Chastefile<chaste::Meta>, member: &str) -> Result<PackageID> {
+    let path = member.trim_start_matches("./").trim_end_matches('/');
+    let found = chastefile
+        .workspace_member_ids()
+        .iter()
+        .copied()
+        .find(|pid| {
+            chastefile.package(*pid).name().is_some_and(|n| n == member)
+                || chastefile
+                    .package_installations(*pid)
+                    .iter()
+                    .any(|i| i.path().as_ref().trim_end_matches('/') == path)
+        });
+    match found {
+        Some(pid) => Ok(pid),
+        None => bail!("There is no workspace member {member:?}"),
+    }
+}
+
+pub fn run(
+    sub: Prune,
+    chastefile: chaste::Chastefile<chaste::Meta>,
+    member: Option<PackageID>,
+    lockfile_contents: &str,
+) -> Result<()> {
+    let members = match (&*sub.member, member) {
+        ([], Some(member)) => vec![member],
+        ([], None) => bail!("No workspace member to keep, use --member or run in one"),
+        (names, _) => names
+            .iter()
+            .map(|name| find_member(&chastefile, name))
+            .collect::<Result<Vec<PackageID>>>()?,
+    };
+
+    let pruned = chastefile.prune(&members, sub.prod);
+    let contents = chaste::write_pruned(lockfile_contents, &pruned)
+        .context("Could not write the pruned lockfile")?;
+    match sub.output {
+        Some(output) => fs::write(&output, contents)
+            .with_context(|| format!("Could not write to {output:?}"))?,
+        None => io::stdout().write_all(contents.as_bytes())?,
+    }
+    Ok(())
+}
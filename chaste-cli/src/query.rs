@@ -0,0 +1,71 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::{Context as _, Result};
+use argh::FromArgs;
+use chaste::types::Query as Selectors;
+use chaste::PackageID;
+use serde_json::{json, Value};
+
+use crate::why::describe;
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "query")]
+/// Find packages with CSS-like selectors, like `npm query`
+pub struct Query {
+    #[argh(positional)]
+    /// selectors like ":root > .dev[name^=@types/]" or ".prod:attr(source=git)".
+    /// Inside a workspace member, :scope is the member
+    selector: String,
+
+    #[argh(switch)]
+    /// print the packages as JSON
+    json: bool,
+
+    #[argh(switch)]
+    /// exit with a non-zero code if any package matches, e.g. to forbid some in CI
+    fail_if_any: bool,
+}
+
+fn package_json(chastefile: &chaste::Chastefile<chaste::Meta>, pid: PackageID) -> Value {
+    let package = chastefile.package(pid);
+    let paths: Vec<&str> = chastefile
+        .package_installations(pid)
+        .into_iter()
+        .map(|i| i.path().as_ref())
+        .collect();
+    json!({
+        "name": package.name().map(|n| n.to_string()),
+        "version": package.version().map(|v| v.to_string()),
+        "integrity": package.checksums().map(|c| c.integrity().to_string()),
+        "paths": paths,
+    })
+}
+
+pub fn run(
+    sub: Query,
+    chastefile: chaste::Chastefile<chaste::Meta>,
+    member: Option<PackageID>,
+) -> Result<()> {
+    let selectors = Selectors::parse(&sub.selector)
+        .with_context(|| format!("Could not parse the selector {:?}", sub.selector))?;
+    let scope = member.unwrap_or(chastefile.root_package_id());
+    let matching = chastefile.query_from(&selectors, &[scope]);
+
+    if sub.json {
+        let packages: Vec<Value> = matching
+            .iter()
+            .map(|&pid| package_json(&chastefile, pid))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&packages)?);
+    } else {
+        for &pid in &matching {
+            println!("{}", describe(&chastefile, pid));
+        }
+    }
+
+    if sub.fail_if_any && !matching.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,164 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use argh::FromArgs;
+use chaste::types::Stats as LockfileStats;
+use chaste::PackageID;
+use serde_json::{json, Value};
+
+use crate::why::describe;
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "stats")]
+/// Summarize the lockfile: packages by source, reachability and checksums,
+/// depth, the biggest and most depended on packages, duplicates and workspace members
+pub struct Stats {
+    #[argh(option, default = "10")]
+    /// list this many of the packages with the most dependencies and dependents. Default: 10
+    top: usize,
+
+    #[argh(switch)]
+    /// print the summary as JSON
+    json: bool,
+}
+
+fn member_name(chastefile: &chaste::Chastefile<chaste::Meta>, pid: PackageID) -> String {
+    match chastefile.package(pid).name() {
+        Some(name) => name.to_string(),
+        None => chastefile
+            .package_installations(pid)
+            .first()
+            .map(|i| i.path().as_ref().to_string())
+            .unwrap_or_else(|| "[unnamed]".to_string()),
+    }
+}
+
+fn to_json(chastefile: &chaste::Chastefile<chaste::Meta>, stats: &LockfileStats) -> Value {
+    let counts = |counts: &[(PackageID, usize)]| -> Vec<Value> {
+        counts
+            .iter()
+            .map(|&(pid, count)| {
+                let package = chastefile.package(pid);
+                json!({
+                    "name": package.name().map(|n| n.to_string()),
+                    "version": package.version().map(|v| v.to_string()),
+                    "count": count,
+                })
+            })
+            .collect()
+    };
+    json!({
+        "packages": stats.packages,
+        "dependencies": stats.dependencies,
+        "sources": {
+            "npm": stats.sources.npm,
+            "tarball": stats.sources.tarball_url,
+            "git": stats.sources.git,
+            "none": stats.sources.none,
+        },
+        "reachability": {
+            "prod": stats.reachability.prod,
+            "dev": stats.reachability.dev,
+            "optional": stats.reachability.optional,
+            "devOptional": stats.reachability.dev_optional,
+            "peer": stats.reachability.peer,
+            "unreachable": stats.reachability.unreachable,
+        },
+        "checksums": stats
+            .checksum_algorithms
+            .iter()
+            .map(|(algorithm, count)| (algorithm.to_string(), json!(count)))
+            .chain([("none".to_string(), json!(stats.without_checksums))])
+            .collect::<serde_json::Map<String, Value>>(),
+        "maxDepth": stats.max_depth,
+        "averageDepth": stats.average_depth,
+        "largestClosures": counts(&stats.largest_closures),
+        "mostDependents": counts(&stats.most_dependents),
+        "duplicateNames": stats
+            .duplicate_names
+            .iter()
+            .map(|(name, versions)| json!({ "name": name, "versions": versions }))
+            .collect::<Vec<Value>>(),
+        "patched": stats.patched,
+        "workspaceMembers": stats
+            .members
+            .iter()
+            .map(|m| json!({
+                "name": member_name(chastefile, m.member),
+                "dependencies": m.dependencies,
+                "prodDependencies": m.prod_dependencies,
+            }))
+            .collect::<Vec<Value>>(),
+    })
+}
+
+fn print_counts(
+    chastefile: &chaste::Chastefile<chaste::Meta>,
+    title: &str,
+    counts: &[(PackageID, usize)],
+) {
+    if counts.is_empty() {
+        return;
+    }
+    println!("{title}:");
+    for &(pid, count) in counts {
+        println!("  {count:>6}  {}", describe(chastefile, pid));
+    }
+}
+
+pub fn run(sub: Stats, chastefile: chaste::Chastefile<chaste::Meta>) -> Result<()> {
+    let stats = chastefile.stats(sub.top);
+    if sub.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&to_json(&chastefile, &stats))?
+        );
+        return Ok(());
+    }
+
+    println!("Packages: {}", stats.packages);
+    println!("Dependencies: {}", stats.dependencies);
+    let sources = stats.sources;
+    println!(
+        "Sources: {} npm, {} tarball, {} git, {} none",
+        sources.npm, sources.tarball_url, sources.git, sources.none
+    );
+    let r = stats.reachability;
+    println!(
+        "Reachability: {} prod, {} dev, {} optional, {} dev or optional, {} peer, {} unreachable",
+        r.prod, r.dev, r.optional, r.dev_optional, r.peer, r.unreachable
+    );
+    let mut checksums: Vec<String> = stats
+        .checksum_algorithms
+        .iter()
+        .map(|(algorithm, count)| format!("{count} {algorithm}"))
+        .collect();
+    checksums.push(format!("{} none", stats.without_checksums));
+    println!("Checksums: {}", checksums.join(", "));
+    println!(
+        "Depth: {} max, {:.2} average",
+        stats.max_depth, stats.average_depth
+    );
+    println!("Patched: {}", stats.patched);
+    print_counts(&chastefile, "Largest closures", &stats.largest_closures);
+    print_counts(&chastefile, "Most dependents", &stats.most_dependents);
+    if !stats.duplicate_names.is_empty() {
+        println!("Duplicate names: {}", stats.duplicate_names.len());
+        for (name, versions) in &stats.duplicate_names {
+            println!("  {versions:>6}  {name}");
+        }
+    }
+    if !stats.members.is_empty() {
+        println!("Workspace members:");
+        for member in &stats.members {
+            println!(
+                "  {:>6}  {} ({} prod)",
+                member.dependencies,
+                member_name(&chastefile, member.member),
+                member.prod_dependencies
+            );
+        }
+    }
+    Ok(())
+}
@@ -1,81 +1,203 @@
 // SPDX-FileCopyrightText: 2024 The Chaste Authors
 // SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
 
-use anyhow::Result;
+use anyhow::{bail, Context as _, Result};
 use argh::FromArgs;
-use chaste::{Dependency, PackageID};
+use chaste::types::VersionRange;
+use chaste::{Dependency, DependencyKind, PackageID};
+use serde_json::{json, Value};
 
 #[derive(FromArgs)]
 #[argh(subcommand, name = "why")]
 /// Why is this package here?
 pub struct Why {
     #[argh(positional)]
-    /// package name
-    package_name: String,
+    /// package name, optionally with a version range, like "ms@^2.1.0"
+    package: String,
+
+    #[argh(option, default = "10")]
+    /// show at most this many chains of dependencies, the shortest ones. Default: 10
+    max_paths: usize,
+
+    #[argh(option)]
+    /// only show chains of at most this many dependencies
+    depth: Option<usize>,
+
+    #[argh(switch)]
+    /// show chains as a tree from each package, like `npm explain`
+    tree: bool,
+
+    #[argh(switch)]
+    /// print the chains as JSON
+    json: bool,
 }
 
-fn permute<'a, G>(preceding: Vec<&'a Dependency>, generator: G) -> Vec<Vec<&'a Dependency>>
-where
-    G: Fn(PackageID) -> Vec<&'a Dependency> + Clone,
-{
-    let last = preceding.last().unwrap();
-    let generated = generator(last.from);
-    match generated.len() {
-        0 => vec![preceding],
-        1 => {
-            let mut res = preceding;
-            let g = generated.first().unwrap();
-            if res.iter().any(|d| d.from == g.from || d.on == g.on) {
-                return Vec::new();
-            }
-            res.push(g);
-            permute(res, generator)
-        }
-        _ => {
-            let mut res = Vec::new();
-            for g in generated {
-                let mut branch = preceding.clone();
-                if branch.iter().any(|d| d.from == g.from || d.on == g.on) {
-                    continue;
-                }
-                branch.push(g);
-                res.extend(permute(branch, generator.clone()));
-            }
-            res
-        }
-    }
+/// Split "name@range" into the name and the range, if there is one.
+fn parse_spec(spec: &str) -> Result<(&str, Option<VersionRange>)> {
+    // Scoped names start with "@".
+    let Some(at) = spec.get(1..).and_then(|s| s.find('@')).map(|i| i + 1) else {
+        return Ok((spec, None));
+    };
+    let range = VersionRange::parse(&spec[at + 1..])
+        .with_context(|| format!("Invalid version range in {spec:?}"))?;
+    Ok((&spec[..at], Some(range)))
 }
 
-pub fn run(sub: Why, chastefile: chaste::Chastefile<chaste::Meta>) -> Result<()> {
-    let packages = chastefile
+/// Packages of the name, and in the version range, if `spec` is "name@range".
+pub(crate) fn matching_packages(
+    chastefile: &chaste::Chastefile<chaste::Meta>,
+    spec: &str,
+) -> Result<Vec<PackageID>> {
+    let (name, range) = parse_spec(spec)?;
+    let targets: Vec<PackageID> = chastefile
         .packages_with_ids()
         .into_iter()
-        .filter(|(_, pkg)| pkg.name().is_some_and(|n| n == sub.package_name));
-    let mut permutations = Vec::new();
-    for (current_pid, _package) in packages {
-        for dep in chastefile.package_dependents(current_pid) {
-            permutations.extend(permute(vec![dep], |pid| chastefile.package_dependents(pid)));
+        .filter(|(_, pkg)| pkg.name().is_some_and(|n| n == name))
+        .filter(|(_, pkg)| match (&range, pkg.version()) {
+            (None, _) => true,
+            (Some(range), Some(version)) => range.satisfies(version),
+            (Some(_), None) => false,
+        })
+        .map(|(pid, _)| pid)
+        .collect();
+    if targets.is_empty() {
+        bail!("No package matching {spec:?} is in the lockfile");
+    }
+    Ok(targets)
+}
+
+pub(crate) fn describe(chastefile: &chaste::Chastefile<chaste::Meta>, pid: PackageID) -> String {
+    let package = chastefile.package(pid);
+    let name = match package.name() {
+        Some(name) => name.as_ref(),
+        None if pid == chastefile.root_package_id() => "[root]",
+        None => "[unnamed]",
+    };
+    match package.version() {
+        Some(version) => format!("{name}@{version}"),
+        None => name.to_string(),
+    }
+}
+
+fn print_chain(chastefile: &chaste::Chastefile<chaste::Meta>, path: &[&Dependency]) {
+    let mut line = describe(chastefile, path[0].from);
+    for dependency in path {
+        line.push_str(&format!(" -{:?}", dependency.kind));
+        if let Some(svs) = dependency.svs() {
+            line.push_str(&format!(" {:?}", svs.as_ref()));
+        }
+        line.push_str(&format!("-> {}", describe(chastefile, dependency.on)));
+    }
+    println!("{line}");
+}
+
+/// How `npm explain` says what a dependency is.
+fn explain(chastefile: &chaste::Chastefile<chaste::Meta>, dependency: &Dependency) -> String {
+    let kind = match dependency.kind {
+        DependencyKind::DevDependency => "dev ",
+        DependencyKind::OptionalDependency => "optional ",
+        DependencyKind::PeerDependency => "peer ",
+        DependencyKind::OptionalPeerDependency => "peerOptional ",
+        _ => "",
+    };
+    let name = match dependency.alias_name() {
+        Some(alias) => alias.to_string(),
+        None => chastefile
+            .package(dependency.on)
+            .name()
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| "[unnamed]".to_string()),
+    };
+    let specifier = match dependency.svs() {
+        Some(svs) => format!("{name}@{:?}", svs.as_ref()),
+        None => name,
+    };
+    let from = match dependency.from {
+        pid if pid == chastefile.root_package_id() => "the root project".to_string(),
+        pid => describe(chastefile, pid),
+    };
+    format!("{kind}{specifier} from {from}")
+}
+
+fn print_tree(chastefile: &chaste::Chastefile<chaste::Meta>, paths: &[Vec<&Dependency>]) {
+    let mut targets: Vec<PackageID> = Vec::new();
+    for path in paths {
+        let target = path.last().unwrap().on;
+        if !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+    for (i, &target) in targets.iter().enumerate() {
+        if i != 0 {
+            println!();
+        }
+        println!("{}", describe(chastefile, target));
+        // Lines shared with the chain above are not repeated.
+        let mut previous: Vec<&Dependency> = Vec::new();
+        for path in paths.iter().filter(|p| p.last().unwrap().on == target) {
+            let reversed: Vec<&Dependency> = path.iter().rev().copied().collect();
+            let shared = reversed
+                .iter()
+                .zip(&previous)
+                .take_while(|(a, b)| std::ptr::eq(**a, **b))
+                .count();
+            for (depth, dependency) in reversed.iter().enumerate().skip(shared) {
+                let indent = "  ".repeat(depth + 1);
+                println!("{indent}{}", explain(chastefile, dependency));
+            }
+            previous = reversed;
         }
     }
-    for mut permut in permutations {
-        permut.reverse();
-        let initial_pkg = chastefile.package(permut.first().unwrap().from);
-        print!(
-            "{}",
-            initial_pkg
-                .name()
-                .map(|n| n.as_ref())
-                .unwrap_or("[unnamed]")
-        );
-        for d in permut {
-            let pkg = chastefile.package(d.on);
-            print!(
-                " -{:?}-> {}",
-                d.kind,
-                pkg.name().map(|n| n.as_ref()).unwrap_or("[unnamed]")
-            );
+}
+
+fn package_json(chastefile: &chaste::Chastefile<chaste::Meta>, pid: PackageID) -> Value {
+    let package = chastefile.package(pid);
+    json!({
+        "name": package.name().map(|n| n.to_string()),
+        "version": package.version().map(|v| v.to_string()),
+    })
+}
+
+fn print_json(chastefile: &chaste::Chastefile<chaste::Meta>, paths: &[Vec<&Dependency>]) {
+    let paths: Vec<Value> = paths
+        .iter()
+        .map(|path| {
+            path.iter()
+                .map(|dependency| {
+                    json!({
+                        "from": package_json(chastefile, dependency.from),
+                        "kind": format!("{:?}", dependency.kind),
+                        "specifier": dependency.svs().map(|s| s.as_ref()),
+                        "on": package_json(chastefile, dependency.on),
+                    })
+                })
+                .collect()
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&paths).unwrap());
+}
+
+pub fn run(
+    sub: Why,
+    chastefile: chaste::Chastefile<chaste::Meta>,
+    member: Option<PackageID>,
+) -> Result<()> {
+    let targets = matching_packages(&chastefile, &sub.package)?;
+    // Inside a workspace member, only chains starting from it.
+    let from = match member {
+        Some(member_pid) => vec![member_pid],
+        None => chastefile.top_package_ids(),
+    };
+    let paths = chastefile.dependency_paths(&from, &targets, sub.depth, Some(sub.max_paths));
+
+    if sub.json {
+        print_json(&chastefile, &paths);
+    } else if sub.tree {
+        print_tree(&chastefile, &paths);
+    } else {
+        for path in &paths {
+            print_chain(&chastefile, path);
         }
-        println!();
     }
 
     Ok(())
@@ -0,0 +1,85 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::{bail, Result};
+use argh::FromArgs;
+use chaste::PackageID;
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "workspaces")]
+/// Work with workspace members
+pub struct Workspaces {
+    #[argh(subcommand)]
+    sub: WorkspacesSubcommand,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum WorkspacesSubcommand {
+    Order(Order),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "order")]
+/// Print workspace members in the order they can be built in, one level per line.
+/// Members on the same line can be built in parallel
+struct Order {
+    #[argh(switch)]
+    /// do not follow devDependencies, e.g. to publish members that are already built
+    prod: bool,
+
+    #[argh(switch)]
+    /// print paths of the members instead of their names
+    paths: bool,
+}
+
+fn path(chastefile: &chaste::Chastefile<chaste::Meta>, pid: PackageID) -> String {
+    chastefile
+        .package_installations(pid)
+        .first()
+        .map(|i| i.path().as_ref().to_string())
+        .unwrap_or_else(|| "[unknown path]".to_string())
+}
+
+fn describe(chastefile: &chaste::Chastefile<chaste::Meta>, pid: PackageID) -> String {
+    match chastefile.package(pid).name() {
+        Some(name) => name.to_string(),
+        None => path(chastefile, pid),
+    }
+}
+
+fn order(sub: Order, chastefile: chaste::Chastefile<chaste::Meta>) -> Result<()> {
+    let show = |pid| match sub.paths {
+        true => path(&chastefile, pid),
+        false => describe(&chastefile, pid),
+    };
+    let levels = match chastefile.workspace_build_order(sub.prod) {
+        Ok(levels) => levels,
+        Err(cycles) => {
+            let cycles: Vec<String> = cycles
+                .into_iter()
+                .map(|cycle| {
+                    let mut members: Vec<String> = cycle.into_iter().map(show).collect();
+                    members.sort_unstable();
+                    members.join(", ")
+                })
+                .collect();
+            bail!(
+                "Workspace members depend on each other in cycles: {}",
+                cycles.join("; ")
+            );
+        }
+    };
+    for level in levels {
+        let mut members: Vec<String> = level.into_iter().map(show).collect();
+        members.sort_unstable();
+        println!("{}", members.join(" "));
+    }
+    Ok(())
+}
+
+pub fn run(sub: Workspaces, chastefile: chaste::Chastefile<chaste::Meta>) -> Result<()> {
+    match sub.sub {
+        WorkspacesSubcommand::Order(sub) => order(sub, chastefile),
+    }
+}
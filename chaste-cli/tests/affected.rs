@@ -0,0 +1,48 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use assert_cmd::Command;
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_affected() -> Result<()> {
+    // "long" was updated, which "web" depends on through "@chastelock/lib".
+    Command::cargo_bin("chaste")?
+        .args(["affected", "--base", "base-package-lock.json"])
+        .current_dir("test_workspaces/npm_v3_workspace_affected")
+        .assert()
+        .success()
+        .stdout("@chastelock/lib\nweb\n");
+
+    // "app" only depends on "long" for development.
+    Command::cargo_bin("chaste")?
+        .args([
+            "affected",
+            "--base",
+            "base-package-lock.json",
+            "--all",
+            "--paths",
+        ])
+        .current_dir("test_workspaces/npm_v3_workspace_affected")
+        .assert()
+        .success()
+        .stdout("app\nlib\nweb\n");
+
+    Command::cargo_bin("chaste")?
+        .args(["affected", "--base", "package-lock.json", "--all"])
+        .current_dir("test_workspaces/npm_v3_workspace_affected")
+        .assert()
+        .success()
+        .stdout("");
+
+    // Members not in the base are affected.
+    Command::cargo_bin("chaste")?
+        .args(["affected", "--base", "../npm_v3_workspace_prune", "--all"])
+        .current_dir("test_workspaces/npm_v3_workspace_affected")
+        .assert()
+        .success()
+        .stdout("@chastelock/lib\nweb\n");
+
+    Ok(())
+}
@@ -0,0 +1,284 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use assert_cmd::Command;
+use serde_json::{json, Value};
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_unsatisfied_range() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["audit"])
+        .current_dir("test_workspaces/npm_v3_unsatisfied_range")
+        .assert()
+        .code(1)
+        .stdout(concat!(
+            "Checked a npm (3) lockfile.\n",
+            "Out of 3 dependencies:\n",
+            "✅ No packages with no checksums\n",
+            "✅ No packages with insecure checksums\n",
+            "✅ No packages with unrecognized source\n",
+            "❌ 1 package with versions not satisfying the requested range:\n",
+            "\tms\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_all_good() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["audit"])
+        .current_dir("test_workspaces/npm_v3_workspace_basic")
+        .assert()
+        .success()
+        .stdout(concat!(
+            "Checked a npm (3) lockfile.\n",
+            "All good! Out of 4 dependencies:\n",
+            "✅ No packages with no checksums\n",
+            "✅ No packages with insecure checksums\n",
+            "✅ No packages with unrecognized source\n",
+            "✅ No packages with versions not satisfying the requested range\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_member_prod() -> Result<()> {
+    // "long" is only a devDependency of the member.
+    Command::cargo_bin("chaste")?
+        .args(["audit", "--prod"])
+        .current_dir("test_workspaces/npm_v3_workspace_prune/app")
+        .assert()
+        .success()
+        .stdout(concat!(
+            "Checked a npm (3) lockfile.\n",
+            "Only dependencies of workspace member app.\n",
+            "All good! Out of 1 dependencies:\n",
+            "✅ No packages with no checksums\n",
+            "✅ No packages with insecure checksums\n",
+            "✅ No packages with unrecognized source\n",
+            "✅ No packages with versions not satisfying the requested range\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_unsatisfied_range_json() -> Result<()> {
+    let output = Command::cargo_bin("chaste")?
+        .args(["audit", "--format", "json"])
+        .current_dir("test_workspaces/npm_v3_unsatisfied_range")
+        .output()?;
+    assert_eq!(output.status.code(), Some(1));
+    let report: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(report["packagesChecked"], json!(3));
+    assert_eq!(report["checks"][0]["passed"], json!(true));
+    assert_eq!(report["checks"][3]["id"], json!("unsatisfied-range"));
+    assert_eq!(report["checks"][3]["passed"], json!(false));
+    let findings = report["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0]["check"], json!("unsatisfied-range"));
+    assert_eq!(findings[0]["severity"], json!("error"));
+    assert_eq!(findings[0]["name"], json!("ms"));
+    assert_eq!(findings[0]["version"], json!("2.1.3"));
+    assert_eq!(
+        findings[0]["path"],
+        json!(["@chastelock/testcase", "debug@2.6.9", "ms@2.1.3"])
+    );
+    assert!(findings[0]["remediation"].is_string());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_unsatisfied_range_sarif() -> Result<()> {
+    let output = Command::cargo_bin("chaste")?
+        .args(["audit", "--format", "sarif", "--failures-ok"])
+        .current_dir("test_workspaces/npm_v3_unsatisfied_range")
+        .output()?;
+    assert!(output.status.success());
+    let sarif: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(sarif["version"], json!("2.1.0"));
+    let run = &sarif["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], json!("chaste"));
+    assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 4);
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["ruleId"], json!("unsatisfied-range"));
+    assert_eq!(results[0]["level"], json!("error"));
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        json!("package-lock.json")
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "yarn-classic")]
+fn yarn_v1_tarball_url_sarif() -> Result<()> {
+    let output = Command::cargo_bin("chaste")?
+        .args(["audit", "--format", "sarif", "--failures-ok"])
+        .current_dir("../chaste-yarn/test_workspaces/c1_tarball_url")
+        .output()?;
+    assert!(output.status.success());
+    let sarif: Value = serde_json::from_slice(&output.stdout)?;
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["ruleId"], json!("insecure-checksums"));
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        json!("yarn.lock")
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_git_no_checksums_json() -> Result<()> {
+    let output = Command::cargo_bin("chaste")?
+        .args(["audit", "--format", "json"])
+        .current_dir("../chaste-npm/test_workspaces/v3_git_url")
+        .output()?;
+    let report: Value = serde_json::from_slice(&output.stdout)?;
+    let findings = report["findings"].as_array().unwrap();
+    assert!(findings.iter().any(|f| f["check"] == json!("no-checksums")
+        && f["name"] == json!("minimatch")
+        && f["severity"] == json!("warning")));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_policy() -> Result<()> {
+    let output = Command::cargo_bin("chaste")?
+        .args(["audit"])
+        .current_dir("test_workspaces/npm_v3_workspace_policy")
+        .output()?;
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        concat!(
+            "Checked a npm (3) lockfile.\n",
+            "With the policy in chaste.yaml.\n",
+            "Out of 4 dependencies:\n",
+            "✅ No packages with no checksums\n",
+            "✅ No packages with insecure checksums\n",
+            "✅ No packages with unrecognized source\n",
+            "✅ No packages with versions not satisfying the requested range\n",
+            "✅ No packages with sources not allowed by the policy\n",
+            "✅ No packages with git sources over the limit\n",
+            "❌ 1 package with banned versions:\n",
+            "\tlong\n",
+            "\tBy rule banned[0]\n",
+            "❌ 1 package with expired suppressions:\n",
+            "\tlong\n",
+            "\tBy rule members.ligma-api.suppressions[1]\n",
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_policy_member_suppression() -> Result<()> {
+    let output = Command::cargo_bin("chaste")?
+        .args(["audit", "--format", "json"])
+        .current_dir("test_workspaces/npm_v3_workspace_policy/ligma-api")
+        .output()?;
+    // Only the expired suppression fails.
+    assert_eq!(output.status.code(), Some(1));
+    let report: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(report["member"], json!("ligma-api"));
+    let banned = report["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["id"] == json!("banned"))
+        .unwrap();
+    assert_eq!(banned["passed"], json!(true));
+    let findings = report["findings"].as_array().unwrap();
+    assert_eq!(findings.len(), 2);
+    assert_eq!(findings[0]["rule"], json!("banned[0]"));
+    assert_eq!(
+        findings[0]["suppressed"],
+        json!({
+            "rule": "members.ligma-api.suppressions[0]",
+            "reason": "being replaced",
+        })
+    );
+    assert_eq!(findings[1]["check"], json!("expired-suppression"));
+    assert_eq!(findings[1]["severity"], json!("warning"));
+    assert_eq!(
+        findings[1]["rule"],
+        json!("members.ligma-api.suppressions[1]")
+    );
+    assert_eq!(
+        findings[1]["message"],
+        json!(
+            "long@5.2.3 is no longer suppressed from banned, the suppression expired on 2000-01-01"
+        )
+    );
+    assert_eq!(findings[1]["suppressed"], Value::Null);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_git_policy_sarif() -> Result<()> {
+    let policy = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/test_workspaces/audit_policies/git.yaml"
+    );
+    let output = Command::cargo_bin("chaste")?
+        .args(["audit", "--format", "sarif", "--policy", policy])
+        .current_dir("../chaste-npm/test_workspaces/v3_git_url")
+        .output()?;
+    // no-checksums, disallowed-registry and too-many-git
+    assert_eq!(output.status.code(), Some(3));
+    let sarif: Value = serde_json::from_slice(&output.stdout)?;
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    let rules: Vec<(&Value, &Value)> = results
+        .iter()
+        .map(|r| (&r["ruleId"], &r["properties"]["policyRule"]))
+        .collect();
+    assert_eq!(
+        rules,
+        [
+            (&json!("no-checksums"), &Value::Null),
+            (&json!("disallowed-registry"), &json!("registries")),
+            (&json!("too-many-git"), &json!("max-git")),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_invalid_policy() -> Result<()> {
+    let policy = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/test_workspaces/audit_policies/unknown_member.yaml"
+    );
+    let output = Command::cargo_bin("chaste")?
+        .args(["audit", "--policy", policy])
+        .current_dir("test_workspaces/npm_v3_workspace_basic")
+        .output()?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?
+        .contains("members.frontend: There is no workspace member named \"frontend\""));
+
+    Ok(())
+}
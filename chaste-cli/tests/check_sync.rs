@@ -0,0 +1,168 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use assert_cmd::Command;
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_in_sync() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["check-sync"])
+        .current_dir("test_workspaces/npm_v3_workspace_basic")
+        .assert()
+        .success()
+        .stdout("The lockfile is up to date.\n");
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_drift() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["check-sync"])
+        .current_dir("test_workspaces/npm_v3_workspace_drift")
+        .assert()
+        .code(1)
+        .stdout(concat!(
+            "ligma-api/package.json: dependencies \"long\" \"^6.0.0\" is not satisfied by locked 5.2.3\n",
+            "ligma-api/package.json: dependencies \"ms\" \"^2.0.0\" is not in the lockfile\n",
+        ));
+
+    // Only the package.json of the member the command is run in.
+    Command::cargo_bin("chaste")?
+        .args(["check-sync"])
+        .current_dir("test_workspaces/npm_v3_workspace_drift/balls")
+        .assert()
+        .success()
+        .stdout("The lockfile is up to date.\n");
+
+    Command::cargo_bin("chaste")?
+        .args(["check-sync", "--failures-ok"])
+        .current_dir("test_workspaces/npm_v3_workspace_drift")
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "yarn-classic")]
+fn yarn_v1_drift() -> Result<()> {
+    // Yarn cannot resolve "ms" from the lockfile at all, which is drift, not a parse failure.
+    Command::cargo_bin("chaste")?
+        .args(["check-sync"])
+        .current_dir("test_workspaces/yarn_v1_drift")
+        .assert()
+        .code(1)
+        .stdout("package.json: dependencies \"ms\" \"^2.0.0\" is not in the lockfile\n");
+
+    // Anything else wrong with the lockfile still fails, unless --lenient.
+    let broken = std::fs::read_to_string("test_workspaces/yarn_v1_drift/yarn.lock")?.replacen(
+        "version \"1.1.6\"",
+        "version \"one\"",
+        1,
+    );
+    Command::cargo_bin("chaste")?
+        .args(["--lockfile", "-", "--implem", "yarn", "check-sync"])
+        .current_dir("test_workspaces/yarn_v1_drift")
+        .write_stdin(broken.clone())
+        .assert()
+        .failure()
+        .stdout("");
+    Command::cargo_bin("chaste")?
+        .args([
+            "--lockfile",
+            "-",
+            "--implem",
+            "yarn",
+            "--lenient",
+            "check-sync",
+        ])
+        .current_dir("test_workspaces/yarn_v1_drift")
+        .write_stdin(broken)
+        .assert()
+        .code(1)
+        .stdout("package.json: dependencies \"ms\" \"^2.0.0\" is not in the lockfile\n");
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "pnpm")]
+fn pnpm_v9_workspace() -> Result<()> {
+    let workspace = "../chaste-pnpm/test_workspaces/v9_workspace_basic";
+    Command::cargo_bin("chaste")?
+        .args(["check-sync"])
+        .current_dir(workspace)
+        .assert()
+        .success()
+        .stdout("The lockfile is up to date.\n");
+
+    let drifted =
+        std::fs::read_to_string(format!("{workspace}/pnpm-lock.yaml"))?.replace("5.2.3", "4.2.3");
+    Command::cargo_bin("chaste")?
+        .args(["--lockfile", "-", "--implem", "pnpm", "check-sync"])
+        .current_dir(workspace)
+        .write_stdin(drifted)
+        .assert()
+        .code(1)
+        .stdout(
+            "ligma-api/package.json: dependencies \"long\" \"^5.0.0\" is not satisfied by locked 4.2.3\n",
+        );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "yarn-berry")]
+fn yarn_berry_v10_workspace() -> Result<()> {
+    let workspace = "../chaste-yarn/test_workspaces/b10_workspace_basic";
+    Command::cargo_bin("chaste")?
+        .args(["check-sync"])
+        .current_dir(workspace)
+        .assert()
+        .success()
+        .stdout("The lockfile is up to date.\n");
+
+    let drifted =
+        std::fs::read_to_string(format!("{workspace}/yarn.lock"))?.replace("^5.0.0", "^5.1.0");
+    Command::cargo_bin("chaste")?
+        .args(["--lockfile", "-", "--implem", "yarn", "check-sync"])
+        .current_dir(workspace)
+        .write_stdin(drifted)
+        .assert()
+        .code(1)
+        .stdout(
+            "ligma-api/package.json: dependencies \"long\" is \"^5.0.0\", but \"npm:^5.1.0\" in the lockfile\n",
+        );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "bun")]
+fn bun_text_v1_workspace() -> Result<()> {
+    let workspace = "../chaste-bun/test_workspaces/text_v1_workspace_basic";
+    Command::cargo_bin("chaste")?
+        .args(["check-sync"])
+        .current_dir(workspace)
+        .assert()
+        .success()
+        .stdout("The lockfile is up to date.\n");
+
+    let drifted = std::fs::read_to_string(format!("{workspace}/bun.lock"))?
+        .replace("long@5.2.4", "long@4.2.4");
+    Command::cargo_bin("chaste")?
+        .args(["--lockfile", "-", "--implem", "bun", "check-sync"])
+        .current_dir(workspace)
+        .write_stdin(drifted)
+        .assert()
+        .code(1)
+        .stdout(
+            "ligma-api/package.json: dependencies \"long\" \"^5.0.0\" is not satisfied by locked 4.2.4\n",
+        );
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use assert_cmd::Command;
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_infinite_recursion() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["cycles"])
+        .current_dir("test_workspaces/npm_v3_infinite_recursion")
+        .assert()
+        .code(1)
+        .stdout(concat!(
+            "Cycle of 2 packages:\n",
+            "\t@chastelock/recursion-a@0.1.0\n",
+            "\t@chastelock/recursion-b@0.1.0\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "yarn-classic")]
+fn yarn_v1_infinite_recursion() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["cycles", "--failures-ok"])
+        .current_dir("test_workspaces/yarn_v1_infinite_recursion")
+        .assert()
+        .success()
+        .stdout(concat!(
+            "Cycle of 2 packages:\n",
+            "\t@chastelock/recursion-a@0.1.0\n",
+            "\t@chastelock/recursion-b@0.1.0\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_cycle() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["cycles", "--workspace"])
+        .current_dir("test_workspaces/npm_v3_workspace_cycle")
+        .assert()
+        .code(1)
+        .stdout("Cycle of 2 packages:\n\ta@1.0.0\n\tb@1.0.0\n");
+
+    // "b" only depends on "a" for development.
+    Command::cargo_bin("chaste")?
+        .args(["cycles", "--workspace", "--prod"])
+        .current_dir("test_workspaces/npm_v3_workspace_cycle")
+        .assert()
+        .success()
+        .stdout("No cycles.\n");
+
+    Ok(())
+}
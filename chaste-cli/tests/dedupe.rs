@@ -0,0 +1,48 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use assert_cmd::Command;
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_duplicates() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["dedupe", "--dry-run"])
+        .current_dir("test_workspaces/npm_v3_duplicates")
+        .assert()
+        .success()
+        .stdout(concat!(
+            "ms: 2 versions, installed at 2 paths\n",
+            "\t2.0.0 at node_modules/@chastelock/a/node_modules/ms\n",
+            "\t\t@chastelock/a@1.0.0 wants \"^2.0.0\"\n",
+            "\t2.1.3 at node_modules/ms\n",
+            "\t\t@chastelock/b@1.0.0 wants \"^2.1.1\"\n",
+            "\tMove @chastelock/a@1.0.0 from 2.0.0 to 2.1.3\n",
+            "\tRemove 2.0.0\n",
+            "\n",
+            "1 package can be removed.\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_no_duplicates() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["dedupe", "--dry-run"])
+        .current_dir("test_workspaces/npm_v3_workspace_basic")
+        .assert()
+        .success()
+        .stdout("No duplicates.\n");
+
+    // Changing the lockfile is not supported.
+    Command::cargo_bin("chaste")?
+        .args(["dedupe"])
+        .current_dir("test_workspaces/npm_v3_duplicates")
+        .assert()
+        .failure();
+
+    Ok(())
+}
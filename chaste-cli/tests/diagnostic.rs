@@ -0,0 +1,61 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use assert_cmd::Command;
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_dangling_dependency() -> Result<()> {
+    let output = Command::cargo_bin("chaste")?
+        .args(["why", "ms"])
+        .current_dir("test_workspaces/npm_v3_dangling_dependency")
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.starts_with("error: Dependency \"ms\" not found\n"));
+    assert!(stderr.contains("package-lock.json:16:10\n"));
+    assert!(stderr.contains(
+        r#"
+15 |       "dependencies": {
+16 |         "ms": "2.1.2"
+   |          ^ "packages" → "node_modules/debug" → "dependencies" → "ms"
+"#
+    ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_syntax_error_stdin() -> Result<()> {
+    let output = Command::cargo_bin("chaste")?
+        .args(["--lockfile", "-", "--implem", "npm", "why", "ms"])
+        .write_stdin("{\n  \"lockfileVersion\": 3,\n  \"packages\": {\n    \"\": {,\n")
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains(" --> <stdin>:4:10\n"));
+    assert!(stderr.contains("4 |     \"\": {,\n  |          ^\n"));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_dangling_dependency_lenient() -> Result<()> {
+    let output = Command::cargo_bin("chaste")?
+        .args(["--lenient", "why", "debug"])
+        .current_dir("test_workspaces/npm_v3_dangling_dependency")
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        "@chastelock/testcase -Dependency \"4.3.4\"-> debug@4.3.4\n"
+    );
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.starts_with("warning: Dependency \"ms\" not found\n"));
+    assert!(stderr.contains("package-lock.json:16:10\n"));
+
+    Ok(())
+}
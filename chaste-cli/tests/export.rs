@@ -0,0 +1,99 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use anyhow::Result;
+use assert_cmd::Command;
+
+#[allow(dead_code)]
+fn output_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("chaste-export-{}-{name}", process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[allow(dead_code)]
+fn has_sqlite3() -> bool {
+    process::Command::new("sqlite3")
+        .arg("-version")
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+#[allow(dead_code)]
+fn sqlite3(database: &PathBuf, sql: &str) -> Result<String> {
+    let output = process::Command::new("sqlite3")
+        .arg(database)
+        .arg(sql)
+        .output()?;
+    assert!(output.status.success());
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_sql() -> Result<()> {
+    let output = output_path("workspace.sql");
+    Command::cargo_bin("chaste")?
+        .args(["export", "sqlite", "--sql", "--project", "affected"])
+        .arg(&output)
+        .current_dir("test_workspaces/npm_v3_workspace_affected")
+        .assert()
+        .success();
+    let sql = fs::read_to_string(&output)?;
+    fs::remove_file(&output)?;
+    assert!(sql.contains("VALUES ('affected', 'sha256-"));
+    assert!(sql.contains("SELECT id, 2, 'long', '5.2.3', 0, 0 FROM current_project;"));
+    assert!(sql.contains("SELECT id, 1, 2, 'dev', '^5.2.2', NULL FROM current_project;"));
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_append() -> Result<()> {
+    if !has_sqlite3() {
+        return Ok(());
+    }
+    let database = output_path("workspace.db");
+    let export = |args: &[&str]| -> Result<()> {
+        Command::cargo_bin("chaste")?
+            .args(args)
+            .args(["export", "sqlite", "--project", "affected", "--append"])
+            .arg(&database)
+            .current_dir("test_workspaces/npm_v3_workspace_affected")
+            .assert()
+            .success();
+        Ok(())
+    };
+    export(&[])?;
+    export(&["--lockfile", "base-package-lock.json"])?;
+    // Exporting the same lockfile again replaces it.
+    export(&[])?;
+    let versions = sqlite3(
+        &database,
+        "SELECT projects.id, version FROM packages JOIN projects ON projects.id = project_id \
+         WHERE name = 'long' ORDER BY projects.id",
+    )?;
+    assert_eq!(versions, "2|5.2.2\n3|5.2.3\n");
+
+    // Without --append, only the lockfiles of the same project are replaced.
+    let export_project = |project: &str| -> Result<()> {
+        Command::cargo_bin("chaste")?
+            .args(["export", "sqlite", "--project", project])
+            .arg(&database)
+            .current_dir("test_workspaces/npm_v3_workspace_affected")
+            .assert()
+            .success();
+        Ok(())
+    };
+    export_project("other")?;
+    assert_eq!(sqlite3(&database, "SELECT count(*) FROM projects")?, "3\n");
+    export_project("affected")?;
+    let projects = sqlite3(&database, "SELECT path FROM projects ORDER BY path")?;
+    assert_eq!(projects, "affected\nother\n");
+    fs::remove_file(&database)?;
+    Ok(())
+}
@@ -0,0 +1,91 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use assert_cmd::Command;
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_mermaid() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["graph", "--format", "mermaid"])
+        .current_dir("test_workspaces/npm_v3_workspace_affected")
+        .assert()
+        .success()
+        .stdout(
+            r#"flowchart LR
+  classDef tarball stroke-dasharray: 6 3
+  classDef git stroke-dasharray: 2 2
+  subgraph m0 ["@chastelock/lib"]
+    p0["@chastelock/lib@1.0.0"]
+  end
+  subgraph m1 ["app"]
+    p1["app@1.0.0"]
+    p3["ms@2.1.3"]
+  end
+  subgraph m2 ["web"]
+    p5["web@1.0.0"]
+  end
+  p2["long@5.2.3"]
+  p4["npm_v3_workspace_affected"]
+  p0 --> p2
+  p1 -.->|dev| p2
+  p1 --> p3
+  p5 --> p0
+"#,
+        );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_focus_dot() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["graph", "--focus", "@chastelock/lib", "--depth", "1"])
+        .current_dir("test_workspaces/npm_v3_workspace_affected")
+        .assert()
+        .success()
+        .stdout(
+            r#"digraph dependencies {
+  rankdir=LR;
+  node [shape=box];
+  subgraph cluster_0 {
+    label="@chastelock/lib";
+    p0 [label="@chastelock/lib\n1.0.0", name="@chastelock/lib", version="1.0.0"];
+  }
+  subgraph cluster_1 {
+    label="web";
+    p2 [label="web\n1.0.0", name="web", version="1.0.0"];
+  }
+  p1 [label="long\n5.2.3", name="long", version="5.2.3", source="npm"];
+  p0 -> p1 [kind="prod"];
+  p2 -> p0 [kind="prod"];
+}
+"#,
+        );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_member_graphml() -> Result<()> {
+    let output = Command::cargo_bin("chaste")?
+        .args(["graph", "--format", "graphml"])
+        .current_dir("test_workspaces/npm_v3_workspace_affected/web")
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    // Inside a member, only what is around it.
+    assert_eq!(stdout.matches("<node ").count(), 3);
+    assert!(stdout.contains("<data key=\"specifier\">^1.0.0</data>"));
+    Ok(())
+}
+
+#[test]
+fn unknown_format() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["graph", "--format", "svg"])
+        .assert()
+        .failure();
+    Ok(())
+}
@@ -0,0 +1,115 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::fs;
+use std::path::Path;
+use std::process;
+
+use anyhow::Result;
+use assert_cmd::Command;
+
+fn git(repo: &Path, args: &[&str]) {
+    let status = process::Command::new("git")
+        .args([
+            "-c",
+            "user.name=Chaste",
+            "-c",
+            "user.email=chaste@example.com",
+            "-c",
+            "commit.gpgsign=false",
+        ])
+        .args(args)
+        .current_dir(repo)
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+fn package_lock(packages: &[(&str, &str)]) -> String {
+    let mut entries = vec![r#""": { "name": "@chastelock/testcase" }"#.to_string()];
+    for (name, version) in packages {
+        entries.push(format!(
+            r#""node_modules/{name}": {{ "version": "{version}", "resolved": "https://registry.npmjs.org/{name}/-/{name}-{version}.tgz" }}"#
+        ));
+    }
+    format!(
+        r#"{{ "name": "@chastelock/testcase", "lockfileVersion": 3, "requires": true, "packages": {{ {} }} }}"#,
+        entries.join(", ")
+    )
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_history() -> Result<()> {
+    let repo = Path::new(env!("CARGO_TARGET_TMPDIR")).join("npm_history");
+    if repo.exists() {
+        fs::remove_dir_all(&repo)?;
+    }
+    fs::create_dir_all(&repo)?;
+    git(&repo, &["init", "-q"]);
+    fs::write(
+        repo.join("package.json"),
+        r#"{ "name": "@chastelock/testcase" }"#,
+    )?;
+    fs::write(repo.join("package-lock.json"), package_lock(&[]))?;
+    git(&repo, &["add", "-A"]);
+    git(&repo, &["commit", "-q", "-m", "Initial"]);
+    fs::write(
+        repo.join("package-lock.json"),
+        package_lock(&[("kleur", "4.1.4"), ("ms", "2.1.3")]),
+    )?;
+    git(&repo, &["commit", "-q", "-am", "Add kleur and ms"]);
+    fs::write(repo.join("README"), "unrelated")?;
+    git(&repo, &["add", "-A"]);
+    git(&repo, &["commit", "-q", "-m", "Add README"]);
+    fs::write(
+        repo.join("package-lock.json"),
+        package_lock(&[("kleur", "4.1.5")]),
+    )?;
+    git(&repo, &["commit", "-q", "-am", "Upgrade kleur, drop ms"]);
+
+    let output = Command::cargo_bin("chaste")?
+        .arg("history")
+        .current_dir(&repo)
+        .output()?;
+    assert!(output.status.success());
+    let lines: Vec<String> = String::from_utf8(output.stdout.clone())?
+        .lines()
+        // Strip the commit hash and date
+        .map(|l| match l.strip_prefix("  ") {
+            Some(change) => format!("  {change}"),
+            None => l.splitn(3, ' ').nth(2).unwrap().to_string(),
+        })
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            "Initial (1 packages)",
+            "  + @chastelock/testcase",
+            "Add kleur and ms (3 packages)",
+            "  + kleur@4.1.4",
+            "  + ms@2.1.3",
+            "Upgrade kleur, drop ms (2 packages)",
+            "  ~ kleur 4.1.4 -> 4.1.5",
+            "  - ms@2.1.3",
+        ]
+    );
+
+    // From a directory inside the project, the history is of the lockfile of the project.
+    fs::create_dir_all(repo.join("src"))?;
+    let from_subdirectory = Command::cargo_bin("chaste")?
+        .arg("history")
+        .current_dir(repo.join("src"))
+        .output()?;
+    assert!(from_subdirectory.status.success());
+    assert_eq!(from_subdirectory.stdout, output.stdout);
+
+    let output = Command::cargo_bin("chaste")?
+        .args(["history", "--package", "ms"])
+        .current_dir(&repo)
+        .output()?;
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout)?.lines().count(), 4);
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::fs;
+
+use anyhow::Result;
+use assert_cmd::Command;
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_lockfile_path() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args([
+            "--lockfile",
+            "test_workspaces/npm_v3_infinite_recursion/package-lock.json",
+            "why",
+            "@chastelock/recursion-a",
+        ])
+        .assert()
+        .success()
+        .stdout("@chastelock/testcase -Dependency \"^0.1.0\"-> @chastelock/recursion-a@0.1.0\n");
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_lockfile_stdin() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["--lockfile", "-", "why", "@chastelock/recursion-a"])
+        .write_stdin(fs::read_to_string(
+            "test_workspaces/npm_v3_infinite_recursion/package-lock.json",
+        )?)
+        .assert()
+        .success()
+        .stdout("@chastelock/testcase -Dependency \"^0.1.0\"-> @chastelock/recursion-a@0.1.0\n");
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "yarn-classic")]
+fn yarn_v1_lockfile_stdin() -> Result<()> {
+    // package.json is still read from the working directory.
+    Command::cargo_bin("chaste")?
+        .args(["--lockfile", "-", "why", "@chastelock/recursion-a"])
+        .current_dir("test_workspaces/yarn_v1_infinite_recursion")
+        .write_stdin(fs::read_to_string(
+            "test_workspaces/yarn_v1_infinite_recursion/yarn.lock",
+        )?)
+        .assert()
+        .success()
+        .stdout("@chastelock/testcase -Dependency \"^0.1.0\"-> @chastelock/recursion-a@0.1.0\n");
+
+    Ok(())
+}
+
+#[test]
+fn unrecognized_lockfile_stdin() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["--lockfile", "-", "why", "@chastelock/recursion-a"])
+        .write_stdin("hello")
+        .assert()
+        .failure();
+
+    Ok(())
+}
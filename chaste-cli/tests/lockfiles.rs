@@ -0,0 +1,49 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use assert_cmd::Command;
+
+static DISAGREEMENT: &str = "❌ 1 package locked differently:
+\t@chastelock/recursion-b: npm (missing); yarn 0.1.0
+";
+
+#[test]
+#[cfg(all(feature = "npm", feature = "yarn-classic"))]
+fn package_manager_field() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["why", "@chastelock/recursion-b"])
+        .current_dir("test_workspaces/multiple_lockfiles")
+        .assert()
+        .success()
+        .stdout(concat!(
+            "@chastelock/testcase -Dependency \"^0.1.0\"-> @chastelock/recursion-a@0.1.0",
+            " -Dependency \"^0.1.0\"-> @chastelock/recursion-b@0.1.0\n",
+        ));
+
+    Command::cargo_bin("chaste")?
+        .arg("lockfiles")
+        .current_dir("test_workspaces/multiple_lockfiles")
+        .assert()
+        .code(1)
+        .stdout(format!(
+            "Found lockfiles of: npm, yarn\nIn use: yarn (from packageManager in package.json)\n{DISAGREEMENT}"
+        ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(all(feature = "npm", feature = "yarn-classic"))]
+fn yarnrc_marker() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["lockfiles", "--failures-ok"])
+        .current_dir("test_workspaces/multiple_lockfiles_marker")
+        .assert()
+        .success()
+        .stdout(format!(
+            "Found lockfiles of: npm, yarn\nIn use: yarn (from .yarnrc)\n{DISAGREEMENT}"
+        ));
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use assert_cmd::Command;
+use serde_json::json;
+
+/// The tree under the first line, which is the root package,
+/// whose version differs between implementations.
+#[allow(dead_code)]
+fn ls_tree(dir: &str, args: &[&str]) -> Result<String> {
+    let output = Command::cargo_bin("chaste")?
+        .arg("ls")
+        .args(args)
+        .current_dir(dir)
+        .output()?;
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout)?;
+    Ok(stdout.split_once('\n').unwrap().1.to_string())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_npm_aliased() -> Result<()> {
+    let tree = ls_tree("../chaste-npm/test_workspaces/v3_npm_aliased", &[])?;
+    assert_eq!(tree, "└── pakig@npm:nop@1.0.0\n");
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "pnpm")]
+fn pnpm_v9_npm_aliased() -> Result<()> {
+    let tree = ls_tree("../chaste-pnpm/test_workspaces/v9_npm_aliased", &[])?;
+    assert_eq!(tree, "└── pakig@npm:nop@1.0.0\n");
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "yarn-classic")]
+fn yarn_c1_npm_aliased() -> Result<()> {
+    let tree = ls_tree("../chaste-yarn/test_workspaces/c1_npm_aliased", &[])?;
+    assert_eq!(tree, "└── pakig@npm:nop@1.0.0\n");
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "yarn-berry")]
+fn yarn_b10_npm_aliased() -> Result<()> {
+    let tree = ls_tree("../chaste-yarn/test_workspaces/b10_npm_aliased", &[])?;
+    assert_eq!(tree, "└── pakig@npm:nop@1.0.0\n");
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "bun")]
+fn bun_text_v1_npm_aliased() -> Result<()> {
+    let tree = ls_tree("../chaste-bun/test_workspaces/text_v1_npm_aliased", &[])?;
+    assert_eq!(tree, "└── pakig@npm:nop@1.0.0\n");
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "pnpm")]
+fn pnpm_v9_patch() -> Result<()> {
+    let tree = ls_tree("../chaste-pnpm/test_workspaces/v9_patch", &[])?;
+    assert_eq!(
+        tree,
+        concat!(
+            "└── @chastelock/recursion-a@0.1.0\n",
+            "    └── @chastelock/recursion-b@0.1.0",
+            " (patched with patches/@chastelock__recursion-b.patch)\n",
+            "        └── @chastelock/recursion-a@0.1.0 deduped\n",
+        )
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace() -> Result<()> {
+    // Members are shown under the root, with their dependencies where they're the least deep.
+    Command::cargo_bin("chaste")?
+        .arg("ls")
+        .current_dir("test_workspaces/npm_v3_workspace_prune")
+        .assert()
+        .success()
+        .stdout(concat!(
+            "npm_v3_workspace_prune\n",
+            "├── app@1.0.0 (workspace)\n",
+            "│   ├── long@5.2.3 (dev) deduped\n",
+            "│   └── ms@2.1.3\n",
+            "├── long@5.2.3 (dev)\n",
+            "└── web@1.0.0 (workspace)\n",
+            "    └── long@5.2.3 deduped\n",
+        ));
+
+    Command::cargo_bin("chaste")?
+        .args(["ls", "--prod", "ms"])
+        .current_dir("test_workspaces/npm_v3_workspace_prune")
+        .assert()
+        .success()
+        .stdout(concat!(
+            "npm_v3_workspace_prune\n",
+            "└── app@1.0.0 (workspace)\n",
+            "    └── ms@2.1.3\n",
+        ));
+
+    Command::cargo_bin("chaste")?
+        .args(["ls", "--depth", "1"])
+        .current_dir("test_workspaces/npm_v3_workspace_basic/balls")
+        .assert()
+        .success()
+        .stdout("@chastelock/balls@1.0.0\n└── ligma-api@1.0.0\n");
+
+    let output = Command::cargo_bin("chaste")?
+        .args(["ls", "--prod", "--json"])
+        .current_dir("test_workspaces/npm_v3_workspace_prune/app")
+        .output()?;
+    assert!(output.status.success());
+    let tree: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        tree,
+        json!({
+            "name": "app",
+            "version": "1.0.0",
+            "dependencies": [{"name": "ms", "version": "2.1.3", "kind": "prod"}],
+        })
+    );
+
+    Ok(())
+}
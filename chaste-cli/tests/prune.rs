@@ -0,0 +1,248 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use assert_cmd::Command;
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_prune_prod() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["prune", "--member", "app", "--prod"])
+        .current_dir("test_workspaces/npm_v3_workspace_prune")
+        .assert()
+        .success()
+        .stdout(
+            r#"{
+  "name": "npm_v3_workspace_prune",
+  "lockfileVersion": 3,
+  "requires": true,
+  "packages": {
+    "": {
+      "name": "npm_v3_workspace_prune",
+      "workspaces": [
+        "app",
+        "web"
+      ]
+    },
+    "app": {
+      "version": "1.0.0",
+      "dependencies": {
+        "ms": "^2.1.3"
+      }
+    },
+    "node_modules/app": {
+      "resolved": "app",
+      "link": true
+    },
+    "node_modules/ms": {
+      "version": "2.1.3",
+      "resolved": "https://registry.npmjs.org/ms/-/ms-2.1.3.tgz",
+      "integrity": "sha512-6FlzubTLZG3J2a/NVCAleEhjzq5oxgHyaCU9yYXvcLsvoVaHJq/s5xXI6/XXP6tz7R9xAOtHnSO/tXtF3WRTlA==",
+      "license": "MIT"
+    }
+  }
+}
+"#,
+        );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_prune_in_member() -> Result<()> {
+    // The member the command is run in is kept, with devDependencies of the root package.
+    Command::cargo_bin("chaste")?
+        .args(["prune"])
+        .current_dir("test_workspaces/npm_v3_workspace_prune/web")
+        .assert()
+        .success()
+        .stdout(
+            r#"{
+  "name": "npm_v3_workspace_prune",
+  "lockfileVersion": 3,
+  "requires": true,
+  "packages": {
+    "": {
+      "name": "npm_v3_workspace_prune",
+      "workspaces": [
+        "app",
+        "web"
+      ],
+      "devDependencies": {
+        "long": "^5.2.3"
+      }
+    },
+    "node_modules/long": {
+      "version": "5.2.3",
+      "resolved": "https://registry.npmjs.org/long/-/long-5.2.3.tgz",
+      "integrity": "sha512-lcHwpNoggQTObv5apGNCTdJrO69eHOZMi4BNC+rTLER8iHAqGrUVeLh/irVIM7zTw2bOXA8T6uNPeujwOLg/2Q==",
+      "license": "Apache-2.0"
+    },
+    "node_modules/web": {
+      "resolved": "web",
+      "link": true
+    },
+    "web": {
+      "version": "1.0.0",
+      "dependencies": {
+        "long": "^5.2.3"
+      }
+    }
+  }
+}
+"#,
+        );
+
+    Command::cargo_bin("chaste")?
+        .args(["prune", "--member", "api"])
+        .current_dir("test_workspaces/npm_v3_workspace_prune")
+        .assert()
+        .failure();
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "pnpm")]
+fn pnpm_v9_workspace_prune() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["prune", "--member", "ligma-api"])
+        .current_dir("../chaste-pnpm/test_workspaces/v9_workspace_basic")
+        .assert()
+        .success()
+        .stdout(
+            "lockfileVersion: '9.0'
+
+settings:
+  autoInstallPeers: true
+  excludeLinksFromLockfile: false
+
+importers:
+
+  .: {}
+
+  ligma-api:
+    dependencies:
+      long:
+        specifier: ^5.0.0
+        version: 5.2.3
+
+packages:
+
+  long@5.2.3:
+    resolution: {integrity: sha512-lcHwpNoggQTObv5apGNCTdJrO69eHOZMi4BNC+rTLER8iHAqGrUVeLh/irVIM7zTw2bOXA8T6uNPeujwOLg/2Q==}
+
+snapshots:
+
+  long@5.2.3: {}
+",
+        );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "yarn-berry")]
+fn yarn_berry_v10_workspace_prune() -> Result<()> {
+    let pruned = r#"# This file is generated by running "yarn install" inside your project.
+# Manual changes might be lost - proceed with caution!
+
+__metadata:
+  version: 10
+  cacheKey: 10
+
+"ligma-api@npm:^1.0.0, ligma-api@workspace:ligma-api":
+  version: 0.0.0-use.local
+  resolution: "ligma-api@workspace:ligma-api"
+  dependencies:
+    long: "npm:^5.0.0"
+  languageName: unknown
+  linkType: soft
+
+"long@npm:^5.0.0":
+  version: 5.2.3
+  resolution: "long@npm:5.2.3"
+  checksum: 10/9167ec6947a825b827c30da169a7384eec6c0c9ec2f0b9c74da2e93d81159bbe39fb09c3f13dae9721d4b807ccfa09797a7dd1012f5d478e3e33ca3c78b608e6
+  languageName: node
+  linkType: hard
+
+"root-workspace-0b6124@workspace:.":
+  version: 0.0.0-use.local
+  resolution: "root-workspace-0b6124@workspace:."
+  languageName: unknown
+  linkType: soft
+"#;
+    Command::cargo_bin("chaste")?
+        .args(["prune", "--member", "ligma-api"])
+        .current_dir("../chaste-yarn/test_workspaces/b10_workspace_basic")
+        .assert()
+        .success()
+        .stdout(pruned);
+
+    Command::cargo_bin("chaste")?
+        .args(["--lockfile", "yarn.lock", "prune", "--member", "ligma-api"])
+        .current_dir("../chaste-yarn/test_workspaces/b10_workspace_basic")
+        .assert()
+        .success()
+        .stdout(pruned);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "yarn-classic")]
+fn yarn_classic_v1_workspace_prune() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["prune", "--member", "ligma-api"])
+        .current_dir("../chaste-yarn/test_workspaces/c1_workspace_basic")
+        .assert()
+        .success()
+        .stdout(
+            r#"# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+long@^5.0.0:
+  version "5.2.3"
+  resolved "https://registry.yarnpkg.com/long/-/long-5.2.3.tgz#a3ba97f3877cf1d778eccbcb048525ebb77499e1"
+  integrity sha512-lcHwpNoggQTObv5apGNCTdJrO69eHOZMi4BNC+rTLER8iHAqGrUVeLh/irVIM7zTw2bOXA8T6uNPeujwOLg/2Q==
+"#,
+        );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "bun")]
+fn bun_text_v1_workspace_prune() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["prune", "--member", "ligma-api"])
+        .current_dir("../chaste-bun/test_workspaces/text_v1_workspace_basic")
+        .assert()
+        .success()
+        .stdout(
+            r#"{
+  "lockfileVersion": 1,
+  "workspaces": {
+    "": {},
+    "ligma-api": {
+      "name": "ligma-api",
+      "version": "1.0.0",
+      "dependencies": {
+        "long": "^5.0.0",
+      },
+    },
+  },
+  "packages": {
+    "ligma-api": ["ligma-api@workspace:ligma-api"],
+
+    "long": ["long@5.2.4", "", {}, "sha512-qtzLbJE8hq7VabR3mISmVGtoXP8KGc2Z/AT8OuqlYD7JTR3oqrgwdjnk07wpj1twXxYmgDXgoKVWUG/fReSzHg=="],
+  }
+}
+"#,
+        );
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use assert_cmd::Command;
+use serde_json::{json, Value};
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_git_url() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["query", ":root > :attr(source=git)"])
+        .current_dir("../chaste-npm/test_workspaces/v3_git_url")
+        .assert()
+        .success()
+        .stdout("minimatch@10.0.1\n");
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["query", ".workspace:has(> #long)"])
+        .current_dir("test_workspaces/npm_v3_workspace_affected")
+        .assert()
+        .success()
+        .stdout("@chastelock/lib@1.0.0\napp@1.0.0\n");
+    // Inside a workspace member, :scope is the member.
+    Command::cargo_bin("chaste")?
+        .args(["query", ":scope > *"])
+        .current_dir("test_workspaces/npm_v3_workspace_affected/app")
+        .assert()
+        .success()
+        .stdout("long@5.2.3\nms@2.1.3\n");
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_fail_if_any() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["query", "#long", "--fail-if-any"])
+        .current_dir("test_workspaces/npm_v3_workspace_affected")
+        .assert()
+        .failure()
+        .stdout("long@5.2.3\n");
+    Command::cargo_bin("chaste")?
+        .args(["query", "#long@<5", "--fail-if-any"])
+        .current_dir("test_workspaces/npm_v3_workspace_affected")
+        .assert()
+        .success()
+        .stdout("");
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_invalid_selector() -> Result<()> {
+    let output = Command::cargo_bin("chaste")?
+        .args(["query", ":outdated"])
+        .current_dir("test_workspaces/npm_v3_workspace_affected")
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("Could not parse the selector \":outdated\""));
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "pnpm")]
+fn pnpm_v9_patch() -> Result<()> {
+    let output = Command::cargo_bin("chaste")?
+        .args(["query", ":derived", "--json"])
+        .current_dir("../chaste-pnpm/test_workspaces/v9_patch")
+        .output()?;
+    assert!(output.status.success());
+    let packages: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(packages.as_array().unwrap().len(), 1);
+    assert_eq!(packages[0]["name"], json!("@chastelock/recursion-b"));
+    assert_eq!(packages[0]["version"], json!("0.1.0"));
+    Ok(())
+}
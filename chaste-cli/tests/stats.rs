@@ -0,0 +1,60 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use assert_cmd::Command;
+use serde_json::{json, Value};
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["stats", "--top", "2"])
+        .current_dir("test_workspaces/npm_v3_workspace_affected")
+        .assert()
+        .success()
+        .stdout(
+            "Packages: 6
+Dependencies: 4
+Sources: 2 npm, 0 tarball, 0 git, 4 none
+Reachability: 6 prod, 0 dev, 0 optional, 0 dev or optional, 0 peer, 0 unreachable
+Checksums: 2 sha512, 4 none
+Depth: 2 max, 1.40 average
+Patched: 0
+Largest closures:
+       2  app@1.0.0
+       2  web@1.0.0
+Most dependents:
+       2  long@5.2.3
+       1  @chastelock/lib@1.0.0
+Workspace members:
+       1  @chastelock/lib (1 prod)
+       2  app (1 prod)
+       2  web (2 prod)
+",
+        );
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "pnpm")]
+fn pnpm_v9_patch_json() -> Result<()> {
+    let output = Command::cargo_bin("chaste")?
+        .args(["stats", "--json"])
+        .current_dir("../chaste-pnpm/test_workspaces/v9_patch")
+        .output()?;
+    assert!(output.status.success());
+    let stats: Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(stats["patched"], json!(1));
+    assert_eq!(stats["workspaceMembers"], json!([]));
+    assert_eq!(
+        stats["packages"].as_u64().unwrap(),
+        stats["sources"]
+            .as_object()
+            .unwrap()
+            .values()
+            .map(|v| v.as_u64().unwrap())
+            .sum::<u64>()
+    );
+    Ok(())
+}
@@ -3,6 +3,7 @@
 
 use anyhow::Result;
 use assert_cmd::Command;
+use serde_json::json;
 
 #[test]
 #[cfg(feature = "npm")]
@@ -12,14 +13,17 @@ fn npm_v3_infinite_recursion() -> Result<()> {
         .current_dir("test_workspaces/npm_v3_infinite_recursion")
         .assert()
         .success()
-        .stdout("@chastelock/testcase -Dependency-> @chastelock/recursion-a\n");
+        .stdout("@chastelock/testcase -Dependency \"^0.1.0\"-> @chastelock/recursion-a@0.1.0\n");
 
     Command::cargo_bin("chaste")?
         .args(["why", "@chastelock/recursion-b"])
         .current_dir("test_workspaces/npm_v3_infinite_recursion")
         .assert()
         .success()
-        .stdout("@chastelock/testcase -Dependency-> @chastelock/recursion-a -Dependency-> @chastelock/recursion-b\n");
+        .stdout(concat!(
+            "@chastelock/testcase -Dependency \"^0.1.0\"-> @chastelock/recursion-a@0.1.0",
+            " -Dependency \"^0.1.0\"-> @chastelock/recursion-b@0.1.0\n",
+        ));
 
     Ok(())
 }
@@ -32,14 +36,93 @@ fn yarn_v1_infinite_recursion() -> Result<()> {
         .current_dir("test_workspaces/yarn_v1_infinite_recursion")
         .assert()
         .success()
-        .stdout("@chastelock/testcase -Dependency-> @chastelock/recursion-a\n");
+        .stdout("@chastelock/testcase -Dependency \"^0.1.0\"-> @chastelock/recursion-a@0.1.0\n");
 
     Command::cargo_bin("chaste")?
         .args(["why", "@chastelock/recursion-b"])
         .current_dir("test_workspaces/yarn_v1_infinite_recursion")
         .assert()
         .success()
-        .stdout("@chastelock/testcase -Dependency-> @chastelock/recursion-a -Dependency-> @chastelock/recursion-b\n");
+        .stdout(concat!(
+            "@chastelock/testcase -Dependency \"^0.1.0\"-> @chastelock/recursion-a@0.1.0",
+            " -Dependency \"^0.1.0\"-> @chastelock/recursion-b@0.1.0\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_duplicates_range() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["why", "ms@^2.1.0", "--tree"])
+        .current_dir("test_workspaces/npm_v3_duplicates")
+        .assert()
+        .success()
+        .stdout(concat!(
+            "ms@2.1.3\n",
+            "  ms@\"^2.1.1\" from @chastelock/b@1.0.0\n",
+            "    @chastelock/b@\"^1.0.0\" from the root project\n",
+        ));
+
+    Command::cargo_bin("chaste")?
+        .args(["why", "ms", "--max-paths", "1"])
+        .current_dir("test_workspaces/npm_v3_duplicates")
+        .assert()
+        .success()
+        .stdout(concat!(
+            "npm_v3_duplicates -Dependency \"^1.0.0\"-> @chastelock/a@1.0.0",
+            " -Dependency \"^2.0.0\"-> ms@2.0.0\n",
+        ));
+
+    Command::cargo_bin("chaste")?
+        .args(["why", "ms@^3"])
+        .current_dir("test_workspaces/npm_v3_duplicates")
+        .assert()
+        .failure();
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_json() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["why", "long", "--depth", "1"])
+        .current_dir("test_workspaces/npm_v3_workspace_affected")
+        .assert()
+        .success()
+        .stdout("app@1.0.0 -DevDependency \"^5.2.2\"-> long@5.2.3\n");
+
+    let output = Command::cargo_bin("chaste")?
+        .args(["why", "long", "--json"])
+        .current_dir("test_workspaces/npm_v3_workspace_affected")
+        .output()?;
+    assert!(output.status.success());
+    let paths: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let package = |name: &str, version: &str| json!({"name": name, "version": version});
+    assert_eq!(
+        paths,
+        json!([
+            [{
+                "from": package("app", "1.0.0"),
+                "kind": "DevDependency",
+                "specifier": "^5.2.2",
+                "on": package("long", "5.2.3"),
+            }],
+            [{
+                "from": package("web", "1.0.0"),
+                "kind": "Dependency",
+                "specifier": "^1.0.0",
+                "on": package("@chastelock/lib", "1.0.0"),
+            }, {
+                "from": package("@chastelock/lib", "1.0.0"),
+                "kind": "Dependency",
+                "specifier": "^5.2.2",
+                "on": package("long", "5.2.3"),
+            }],
+        ])
+    );
 
     Ok(())
 }
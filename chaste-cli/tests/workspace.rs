@@ -0,0 +1,51 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use assert_cmd::Command;
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_member() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["why", "long"])
+        .current_dir("test_workspaces/npm_v3_workspace_basic")
+        .assert()
+        .success()
+        .stdout(concat!(
+            "@chastelock/balls@1.0.0 -Dependency \"^1.0.0\"-> ligma-api@1.0.0",
+            " -Dependency \"^5.0.0\"-> long@5.2.3\n",
+        ));
+
+    // The lockfile is found in the parent directory.
+    Command::cargo_bin("chaste")?
+        .args(["why", "long"])
+        .current_dir("test_workspaces/npm_v3_workspace_basic/balls")
+        .assert()
+        .success()
+        .stdout(concat!(
+            "@chastelock/balls@1.0.0 -Dependency \"^1.0.0\"-> ligma-api@1.0.0",
+            " -Dependency \"^5.0.0\"-> long@5.2.3\n",
+        ));
+
+    // Chains through other members are cut to start at the current one.
+    Command::cargo_bin("chaste")?
+        .args(["why", "long"])
+        .current_dir("test_workspaces/npm_v3_workspace_basic/ligma-api")
+        .assert()
+        .success()
+        .stdout("ligma-api@1.0.0 -Dependency \"^5.0.0\"-> long@5.2.3\n");
+
+    Command::cargo_bin("chaste")?
+        .args([
+            "--cwd",
+            "test_workspaces/npm_v3_workspace_basic/ligma-api",
+            "why",
+            "ligma-api",
+        ])
+        .assert()
+        .success()
+        .stdout("");
+
+    Ok(())
+}
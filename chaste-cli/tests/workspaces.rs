@@ -0,0 +1,59 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use anyhow::Result;
+use assert_cmd::Command;
+
+#[test]
+#[cfg(feature = "pnpm")]
+fn pnpm_v9_workspace_order() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["workspaces", "order"])
+        .current_dir("test_workspaces/pnpm_v9_workspace_order")
+        .assert()
+        .success()
+        .stdout(concat!(
+            "@chastelock/utils\n",
+            "@chastelock/ui\n",
+            "@chastelock/app\n",
+            "@chastelock/docs\n",
+        ));
+
+    // "docs" only depends on "app" for development.
+    Command::cargo_bin("chaste")?
+        .args(["workspaces", "order", "--prod", "--paths"])
+        .current_dir("test_workspaces/pnpm_v9_workspace_order")
+        .assert()
+        .success()
+        .stdout("docs utils\nui\napp\n");
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "npm")]
+fn npm_v3_workspace_order() -> Result<()> {
+    Command::cargo_bin("chaste")?
+        .args(["workspaces", "order"])
+        .current_dir("test_workspaces/npm_v3_workspace_basic")
+        .assert()
+        .success()
+        .stdout("ligma-api\n@chastelock/balls\n");
+
+    let output = Command::cargo_bin("chaste")?
+        .args(["workspaces", "order"])
+        .current_dir("test_workspaces/npm_v3_workspace_cycle")
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("Workspace members depend on each other in cycles: a, b\n"));
+
+    Command::cargo_bin("chaste")?
+        .args(["workspaces", "order", "--prod"])
+        .current_dir("test_workspaces/npm_v3_workspace_cycle")
+        .assert()
+        .success()
+        .stdout("b\na\n");
+
+    Ok(())
+}
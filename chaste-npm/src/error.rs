@@ -1,8 +1,9 @@
 // SPDX-FileCopyrightText: 2024 The Chaste Authors
 // SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
 
-use std::io;
+use std::{io, path};
 
+use chaste_types::{ErrorKind, Locate, Located, Position};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -26,8 +27,61 @@ pub enum Error {
     #[error("Serde error: {0:?}")]
     SerdeError(#[from] serde_json::Error),
 
+    #[error("Serde error trying to read {1:?}: {0:?}")]
+    SerdeInWorkspace(serde_json::Error, path::PathBuf),
+
     #[error("SSRI error: {0:?}")]
     SSRIError(#[from] chaste_types::SSRIError),
+
+    #[error(transparent)]
+    At(#[from] Located<Box<Error>>),
+}
+
+impl Error {
+    /// What went wrong, as an [`ErrorKind`] shared with the other implementations.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::DependencyNotFound(_) | Error::WorkspaceMemberNotFound(_) => {
+                ErrorKind::DanglingReference
+            }
+            Error::UnknownLockVersion(_) => ErrorKind::UnsupportedVersion,
+            Error::ChasteError(e) => e.kind(),
+            Error::IoError(_) => ErrorKind::Io,
+            Error::SerdeError(e) if e.is_io() => ErrorKind::Io,
+            Error::SerdeError(_) => ErrorKind::MalformedSyntax,
+            Error::SerdeInWorkspace(e, _) if e.is_io() => ErrorKind::Io,
+            Error::SerdeInWorkspace(..) => ErrorKind::MalformedSyntax,
+            Error::SSRIError(_) => ErrorKind::InvalidChecksum,
+            Error::At(located) => located.source.kind(),
+        }
+    }
+
+    /// Where the syntax error is, if the lockfile could not be deserialized.
+    pub fn position(&self) -> Option<Position> {
+        match self.inner() {
+            Error::SerdeError(e) if e.line() != 0 => Some(Position {
+                line: e.line(),
+                column: e.column(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Locate for Error {
+    fn located(&self) -> Option<&Located<Box<Error>>> {
+        match self {
+            Error::At(located) => Some(located),
+            _ => None,
+        }
+    }
+
+    fn into_located(self) -> Result<Located<Box<Error>>, Error> {
+        match self {
+            Error::At(located) => Ok(located),
+            e => Err(e),
+        }
+    }
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
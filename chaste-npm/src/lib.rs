@@ -3,18 +3,20 @@
 
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::io;
 use std::path::Path;
-use std::{fs, io};
 
 use chaste_types::{
     Chastefile, ChastefileBuilder, Checksums, Dependency, DependencyBuilder, DependencyKind,
-    InstallationBuilder, Integrity, LockfileVersion, ModulePath, PackageBuilder, PackageID,
-    PackageName, PackageSource, ProviderMeta, SourceVersionSpecifier,
+    FileSource, InstallationBuilder, Integrity, Locate as _, LockfileVersion, ModulePath, Override,
+    OverrideBuilder, OverrideSelector, PackageBuilder, PackageID, PackageName, PackageSource,
+    ProviderMeta, RealFileSource, SourceVersionSpecifier, Warnings, PACKAGE_JSON_FILENAME,
 };
 
 pub use crate::error::{Error, Result};
+pub use crate::prune::write_pruned;
 
-use crate::types::{DependencyTreePackage, PeerDependencyMeta};
+use crate::types::{DependencyTreePackage, PackageJson, PeerDependencyMeta};
 
 #[cfg(feature = "fuzzing")]
 pub use crate::types::PackageLock;
@@ -22,6 +24,7 @@ pub use crate::types::PackageLock;
 use crate::types::PackageLock;
 
 mod error;
+mod prune;
 #[cfg(test)]
 mod tests;
 mod types;
@@ -47,8 +50,10 @@ impl ProviderMeta for Meta {
 
 struct PackageParser<'a> {
     package_lock: &'a PackageLock<'a>,
+    overrides: Vec<Override>,
     chastefile_builder: ChastefileBuilder<Meta>,
     path_pid: HashMap<&'a Cow<'a, str>, PackageID>,
+    warnings: Warnings<Error>,
 }
 
 fn recognize_source(resolved: &str) -> Option<PackageSource> {
@@ -126,6 +131,7 @@ fn parse_dependencies<'a>(
     tree_package: &'a DependencyTreePackage,
     path_pid: &HashMap<&'a Cow<'a, str>, PackageID>,
     self_pid: PackageID,
+    warnings: &mut Warnings<Error>,
 ) -> Result<Vec<Dependency>> {
     let capacity = tree_package.dependencies.len() + tree_package.dev_dependencies.len();
     let mut dependencies = Vec::with_capacity(capacity);
@@ -173,28 +179,34 @@ fn parse_dependencies<'a>(
                 // https://github.com/npm/rfcs/blob/main/implemented/0025-install-peer-deps.md
                 Err(Error::DependencyNotFound(_)) if kind.is_peer() || kind.is_optional() => {}
 
-                Err(e) => return Err(e),
+                Err(e) => warnings.skip(e.at(["packages", path, kind_.package_json_field(), n]))?,
             }
         }
     }
 
-    debug_assert!(dependencies.len() >= capacity);
+    debug_assert!(warnings.is_lenient() || dependencies.len() >= capacity);
 
     Ok(dependencies)
 }
 
 impl<'a> PackageParser<'a> {
-    fn new(package_lock: &'a PackageLock) -> Self {
+    fn new(
+        package_lock: &'a PackageLock,
+        overrides: Vec<Override>,
+        warnings: Warnings<Error>,
+    ) -> Self {
         Self {
             package_lock,
+            overrides,
             chastefile_builder: ChastefileBuilder::new(Meta {
                 lockfile_version: package_lock.lockfile_version,
             }),
             path_pid: HashMap::with_capacity(package_lock.packages.len()),
+            warnings,
         }
     }
 
-    fn resolve(mut self) -> Result<Chastefile<Meta>> {
+    fn resolve(mut self) -> Result<(Chastefile<Meta>, Warnings<Error>)> {
         // First, go through all packages, but ignore entries that say to link to another package.
         // We have to do that before we can resolve the links to their respective packages.
         for (package_path, tree_package) in self
@@ -204,11 +216,21 @@ impl<'a> PackageParser<'a> {
             .filter(|(_, tp)| tp.link != Some(true))
         {
             let module_path = ModulePath::new(package_path.to_string())?;
-            let mut package = parse_package(&module_path, tree_package)?;
+            let mut package = match parse_package(&module_path, tree_package) {
+                Ok(p) => p,
+                Err(e) => {
+                    self.warnings
+                        .skip(e.at(["packages", package_path.as_ref()]))?;
+                    continue;
+                }
+            };
             if package_path.is_empty() && package.get_name().is_none() {
                 package.name(Some(PackageName::new(self.package_lock.name.to_string())?));
             }
-            let pid = match self.chastefile_builder.add_package(package.build()?) {
+            let package = self
+                .warnings
+                .build_package(package, |e| e.at(["packages", package_path.as_ref()]))?;
+            let pid = match self.chastefile_builder.add_package(package) {
                 Ok(pid) => pid,
                 // If the package is already checked in, reuse it.
                 Err(chaste_types::Error::DuplicatePackage(pid)) => pid,
@@ -240,7 +262,9 @@ impl<'a> PackageParser<'a> {
                 .as_ref()
                 .and_then(|lt| self.path_pid.get(lt))
             else {
-                return Err(Error::WorkspaceMemberNotFound(package_path.to_string()));
+                self.warnings
+                    .skip(Error::WorkspaceMemberNotFound(package_path.to_string()))?;
+                continue;
             };
             self.path_pid.insert(package_path, pid);
             let module_path = ModulePath::new(package_path.to_string())?;
@@ -255,43 +279,142 @@ impl<'a> PackageParser<'a> {
             .iter()
             .filter(|(_, tp)| tp.link != Some(true))
         {
-            let pid = *self.path_pid.get(package_path).unwrap();
-            let dependencies = parse_dependencies(package_path, tree_package, &self.path_pid, pid)?;
+            // Packages that could not be parsed were skipped.
+            let Some(&pid) = self.path_pid.get(package_path) else {
+                continue;
+            };
+            let dependencies = parse_dependencies(
+                package_path,
+                tree_package,
+                &self.path_pid,
+                pid,
+                &mut self.warnings,
+            )?;
             self.chastefile_builder
                 .add_dependencies(dependencies.into_iter());
         }
-        Ok(self.chastefile_builder.build()?)
+        for override_ in self.overrides {
+            self.chastefile_builder.add_override(override_);
+        }
+        self.chastefile_builder.match_overrides();
+        Ok((self.chastefile_builder.build()?, self.warnings))
     }
 }
 
+/// Flatten `"overrides"` of package.json. Object values are for dependencies anywhere under
+/// the packages of their keys, and their `"."` is for those packages themselves.
+fn parse_overrides(
+    overrides: &serde_json::Map<String, serde_json::Value>,
+    ancestor: Option<&OverrideSelector>,
+    parsed: &mut Vec<Override>,
+    warnings: &mut Warnings<Error>,
+) -> Result<()> {
+    for (key, value) in overrides {
+        if key == "." {
+            continue;
+        }
+        let selector = match OverrideSelector::parse(key) {
+            Ok(s) => s,
+            Err(e) => {
+                warnings.skip(Error::ChasteError(e))?;
+                continue;
+            }
+        };
+        let replacement = match value {
+            serde_json::Value::String(r) => Some(r.as_str()),
+            serde_json::Value::Object(o) => o.get(".").and_then(|r| r.as_str()),
+            _ => None,
+        };
+        if let Some(replacement) = replacement {
+            let mut override_ = OverrideBuilder::new(selector.clone(), replacement.to_string());
+            if let Some(ancestor) = ancestor {
+                override_.ancestor(ancestor.clone());
+            }
+            parsed.push(override_.build());
+        }
+        if let serde_json::Value::Object(nested) = value {
+            parse_overrides(nested, Some(&selector), parsed, warnings)?;
+        }
+    }
+    Ok(())
+}
+
 mod parse_lock_ {
-    use super::{Chastefile, Error, Meta, PackageLock, PackageParser, Result};
+    use super::{Chastefile, Error, Meta, Override, PackageLock, PackageParser, Result, Warnings};
+    #[cfg(feature = "fuzzing")]
     pub fn parse_lock(package_lock: &PackageLock) -> Result<Chastefile<Meta>> {
+        let (chastefile, _) =
+            parse_lock_with_overrides(package_lock, Vec::new(), Warnings::strict())?;
+        Ok(chastefile)
+    }
+
+    pub(crate) fn parse_lock_with_overrides(
+        package_lock: &PackageLock,
+        overrides: Vec<Override>,
+        warnings: Warnings<Error>,
+    ) -> Result<(Chastefile<Meta>, Warnings<Error>)> {
         if ![2, 3].contains(&package_lock.lockfile_version) {
             return Err(Error::UnknownLockVersion(package_lock.lockfile_version));
         }
-        let parser = PackageParser::new(package_lock);
-        let chastefile = parser.resolve()?;
-        Ok(chastefile)
+        let parser = PackageParser::new(package_lock, overrides, warnings);
+        parser.resolve()
     }
 }
 
 #[cfg(feature = "fuzzing")]
 pub use parse_lock_::parse_lock;
-#[cfg(not(feature = "fuzzing"))]
-use parse_lock_::parse_lock;
+use parse_lock_::parse_lock_with_overrides;
 
 pub fn parse<P>(root_dir: P) -> Result<Chastefile<Meta>>
 where
     P: AsRef<Path>,
 {
-    let lockfile_contents = match fs::read_to_string(root_dir.as_ref().join(SHRINKWRAP_NAME)) {
+    let root_dir = root_dir.as_ref();
+    let lockfile_contents = match RealFileSource.read_to_string(&root_dir.join(SHRINKWRAP_NAME)) {
         Ok(c) => c,
         Err(e) if e.kind() == io::ErrorKind::NotFound => {
-            fs::read_to_string(root_dir.as_ref().join(LOCKFILE_NAME))?
+            RealFileSource.read_to_string(&root_dir.join(LOCKFILE_NAME))?
         }
         Err(e) => return Err(Error::IoError(e)),
     };
-    let package_lock: PackageLock = serde_json::from_str(&lockfile_contents)?;
-    parse_lock(&package_lock)
+    let (chastefile, _) = parse_str(
+        &lockfile_contents,
+        root_dir,
+        &RealFileSource,
+        Warnings::strict(),
+    )?;
+    Ok(chastefile)
+}
+
+/// Parse the contents of a package-lock.json or npm-shrinkwrap.json. The lockfile is enough,
+/// but `"overrides"` are only in the root package.json, read from `file_source` if it is
+/// in `root_dir`.
+///
+/// With [`Warnings::lenient`], problems with single packages or dependencies are skipped
+/// and returned in the warnings, instead of failing the whole parse.
+pub fn parse_str<P, S>(
+    lockfile_contents: &str,
+    root_dir: P,
+    file_source: &S,
+    mut warnings: Warnings<Error>,
+) -> Result<(Chastefile<Meta>, Warnings<Error>)>
+where
+    P: AsRef<Path>,
+    S: FileSource + ?Sized,
+{
+    let package_lock: PackageLock = serde_json::from_str(lockfile_contents)?;
+    let mut overrides = Vec::new();
+    let package_json_path = root_dir.as_ref().join(PACKAGE_JSON_FILENAME);
+    match file_source.read_to_string(&package_json_path) {
+        Ok(contents) => match serde_json::from_str::<PackageJson>(&contents) {
+            Ok(package_json) => {
+                parse_overrides(&package_json.overrides, None, &mut overrides, &mut warnings)?
+            }
+            // Not the lockfile's syntax error, and the lockfile can be read without overrides.
+            Err(e) => warnings.skip(Error::SerdeInWorkspace(e, package_json_path))?,
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(Error::IoError(e)),
+    }
+    parse_lock_with_overrides(&package_lock, overrides, warnings)
 }
@@ -0,0 +1,94 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::collections::HashSet;
+
+use chaste_types::{Chastefile, DependencyKind, ProviderMeta};
+use serde::Serialize as _;
+use serde_json::ser::PrettyFormatter;
+use serde_json::{Map, Serializer, Value};
+
+use crate::error::Result;
+
+/// Drop entries of the legacy `"dependencies"` tree, of lockfile version 2,
+/// that are not installed at a kept path.
+fn retain_legacy_dependencies(
+    dependencies: &mut Map<String, Value>,
+    parent: &str,
+    kept: &HashSet<&str>,
+) {
+    dependencies.retain(|name, entry| {
+        let path = match parent {
+            "" => format!("node_modules/{name}"),
+            parent => format!("{parent}/node_modules/{name}"),
+        };
+        if !kept.contains(path.as_str()) {
+            return false;
+        }
+        if let Some(Value::Object(nested)) = entry.get_mut("dependencies") {
+            retain_legacy_dependencies(nested, &path, kept);
+        }
+        true
+    });
+}
+
+/// Write the package-lock.json with contents `lockfile_contents` again, with only
+/// the entries of packages in `pruned`, as made with [`Chastefile::prune`]
+/// or [`Chastefile::subgraph`] from the result of parsing the same contents.
+///
+/// Entries are kept as they are, except for `"devDependencies"` that were pruned away.
+pub fn write_pruned<P>(lockfile_contents: &str, pruned: &Chastefile<P>) -> Result<String>
+where
+    P: ProviderMeta,
+{
+    let mut package_lock: Value = serde_json::from_str(lockfile_contents)?;
+    let mut kept: HashSet<&str> = HashSet::new();
+    // Names in "devDependencies" that are still there, by the path of the depending package.
+    let mut dev_names: Vec<(&str, HashSet<&str>)> = Vec::new();
+    for (pid, _) in pruned.packages_with_ids() {
+        let names: HashSet<&str> = pruned
+            .declared_dependencies(pid)
+            .into_iter()
+            .filter(|(field, _)| *field == DependencyKind::DevDependency.package_json_field())
+            .map(|(_, name)| name)
+            .collect();
+        for installation in pruned.package_installations(pid) {
+            kept.insert(installation.path().as_ref());
+            dev_names.push((installation.path().as_ref(), names.clone()));
+        }
+    }
+
+    if let Some(Value::Object(packages)) = package_lock.get_mut("packages") {
+        packages.retain(|path, _| kept.contains(path.as_str()));
+        for (path, names) in dev_names {
+            let Some(Value::Object(dev_dependencies)) = packages
+                .get_mut(path)
+                .and_then(|entry| entry.get_mut("devDependencies"))
+            else {
+                continue;
+            };
+            dev_dependencies.retain(|name, _| names.contains(name.as_str()));
+            if dev_dependencies.is_empty() {
+                if let Some(Value::Object(entry)) = packages.get_mut(path) {
+                    entry.shift_remove("devDependencies");
+                }
+            }
+        }
+    }
+    if let Some(Value::Object(dependencies)) = package_lock.get_mut("dependencies") {
+        retain_legacy_dependencies(dependencies, "", &kept);
+    }
+
+    // npm indents the lockfile like the package.json, so the indentation is kept.
+    let indent = lockfile_contents
+        .lines()
+        .nth(1)
+        .map(|l| &l[..l.len() - l.trim_start().len()])
+        .filter(|i| !i.is_empty())
+        .unwrap_or("  ");
+    let mut contents = Vec::with_capacity(lockfile_contents.len());
+    let formatter = PrettyFormatter::with_indent(indent.as_bytes());
+    package_lock.serialize(&mut Serializer::with_formatter(&mut contents, formatter))?;
+    contents.push(b'\n');
+    Ok(String::from_utf8(contents).expect("serde_json writes UTF-8"))
+}
@@ -1,12 +1,16 @@
 // SPDX-FileCopyrightText: 2024 The Chaste Authors
 // SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
 
+use std::fs;
 use std::path::PathBuf;
 use std::sync::LazyLock;
 
-use chaste_types::{Chastefile, Package, PackageID, PackageSourceType};
+use chaste_types::{
+    Chastefile, ErrorKind, Locate as _, MemoryFileSource, Package, PackageID, PackageSourceType,
+    Warnings,
+};
 
-use super::{parse, Error, Meta, Result};
+use super::{parse, parse_str, write_pruned, Error, Meta, Result};
 
 static TEST_WORKSPACES: LazyLock<PathBuf> = LazyLock::new(|| PathBuf::from("test_workspaces"));
 
@@ -14,12 +18,22 @@ fn test_workspace(name: &str) -> Result<Chastefile<Meta>> {
     parse(TEST_WORKSPACES.join(name))
 }
 
+/// Parse only the lockfile, with no package.json.
+fn from_str(lockfile_contents: &str) -> Result<Chastefile<Meta>> {
+    let (chastefile, _) = parse_str(
+        lockfile_contents,
+        "",
+        &MemoryFileSource::new(),
+        Warnings::strict(),
+    )?;
+    Ok(chastefile)
+}
+
 #[test]
 fn v1_basic() -> Result<()> {
-    assert!(matches!(
-        test_workspace("v1_basic").unwrap_err(),
-        Error::UnknownLockVersion(1)
-    ));
+    let err = test_workspace("v1_basic").unwrap_err();
+    assert!(matches!(err, Error::UnknownLockVersion(1)));
+    assert_eq!(err.kind(), ErrorKind::UnsupportedVersion);
 
     Ok(())
 }
@@ -125,6 +139,27 @@ fn v3_hoist_partial() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn v3_infinite_recursion() -> Result<()> {
+    let chastefile = test_workspace("v3_infinite_recursion")?;
+    let [cycle] = &chastefile.cycles(false)[..] else {
+        panic!();
+    };
+    let mut names: Vec<&str> = cycle
+        .iter()
+        .map(|pid| chastefile.package(*pid).name().unwrap().as_ref())
+        .collect();
+    names.sort_unstable();
+    assert_eq!(
+        names,
+        ["@chastelock/recursion-a", "@chastelock/recursion-b"]
+    );
+    assert_eq!(chastefile.cycles(true).len(), 1);
+    assert!(chastefile.workspace_cycles(false).is_empty());
+
+    Ok(())
+}
+
 #[test]
 fn v3_link_nowhere() -> Result<()> {
     assert!(matches!(
@@ -223,6 +258,27 @@ fn v3_overrides() -> Result<()> {
         .collect::<Vec<&str>>();
     assert_eq!(scwm_svss, ["^1.0.2"]);
 
+    let [(scwm_oid, scwm_override), (ms_oid, ms_override), (path_oid, _)] =
+        *chastefile.overrides_with_ids()
+    else {
+        panic!();
+    };
+    assert_eq!(scwm_override.selector().name(), "side-channel-weakmap");
+    let scwm_ancestor = scwm_override.parent().unwrap();
+    assert_eq!(scwm_ancestor.name(), "side-channel");
+    assert_eq!(scwm_ancestor.svs(), Some("1.1.0"));
+    assert!(!scwm_override.is_parent_direct());
+    assert_eq!(ms_override.replacement(), "$ms");
+    assert_eq!(chastefile.overridden_dependencies(ms_oid).len(), 3);
+    let [path_dep] = *chastefile.overridden_dependencies(path_oid) else {
+        panic!();
+    };
+    assert_eq!(path_dep.on, path_pid);
+    let [scwm_dep] = *chastefile.overridden_dependencies(scwm_oid) else {
+        panic!();
+    };
+    assert_eq!(scwm_dep.on, scwm_pid);
+
     Ok(())
 }
 
@@ -351,6 +407,150 @@ fn v3_workspace_basic() -> Result<()> {
         balls_install_paths,
         ["balls", "node_modules/@chastelock/balls"]
     );
+    assert_eq!(chastefile.workspace_member_containing("balls"), balls_pid);
+    assert_eq!(
+        chastefile.workspace_member_containing("ligma-api/src/"),
+        ligma_pid
+    );
+    assert_eq!(
+        chastefile.workspace_member_containing("ballsy"),
+        chastefile.root_package_id()
+    );
+    assert_eq!(
+        chastefile.workspace_member_containing(""),
+        chastefile.root_package_id()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn v3_workspace_basic_prune() -> Result<()> {
+    let lockfile_contents =
+        fs::read_to_string(TEST_WORKSPACES.join("v3_workspace_basic/package-lock.json"))?;
+    let chastefile = from_str(&lockfile_contents)?;
+    let [ligma_pid] = *chastefile
+        .workspace_member_ids()
+        .iter()
+        .copied()
+        .filter(|pid| chastefile.package(*pid).name().unwrap() == "ligma-api")
+        .collect::<Vec<PackageID>>()
+    else {
+        panic!();
+    };
+    let pruned = chastefile.prune(&[ligma_pid], true);
+    assert_eq!(
+        write_pruned(&lockfile_contents, &pruned)?,
+        r#"{
+    "name": "v3_workspace_basic",
+    "lockfileVersion": 3,
+    "requires": true,
+    "packages": {
+        "": {
+            "workspaces": [
+                "ligma-api",
+                "balls"
+            ]
+        },
+        "ligma-api": {
+            "version": "1.0.0",
+            "dependencies": {
+                "long": "^5.0.0"
+            }
+        },
+        "node_modules/ligma-api": {
+            "resolved": "ligma-api",
+            "link": true
+        },
+        "node_modules/long": {
+            "version": "5.2.3",
+            "resolved": "https://registry.npmjs.org/long/-/long-5.2.3.tgz",
+            "integrity": "sha512-lcHwpNoggQTObv5apGNCTdJrO69eHOZMi4BNC+rTLER8iHAqGrUVeLh/irVIM7zTw2bOXA8T6uNPeujwOLg/2Q==",
+            "license": "Apache-2.0"
+        }
+    }
+}
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn v3_workspace_cycle() -> Result<()> {
+    let chastefile = test_workspace("v3_workspace_cycle")?;
+    // "a" depends on "b", which has "a" as a devDependency.
+    let [cycle] = &chastefile.workspace_cycles(false)[..] else {
+        panic!();
+    };
+    let mut names: Vec<&str> = cycle
+        .iter()
+        .map(|pid| chastefile.package(*pid).name().unwrap().as_ref())
+        .collect();
+    names.sort_unstable();
+    assert_eq!(names, ["a", "b"]);
+    assert_eq!(chastefile.cycles(false).len(), 1);
+    assert!(chastefile.workspace_cycles(true).is_empty());
+    assert!(chastefile.cycles(true).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn v3_basic_from_str() -> Result<()> {
+    let contents = fs::read_to_string(TEST_WORKSPACES.join("v3_basic/package-lock.json"))?;
+    let chastefile = from_str(&contents)?;
+    assert_eq!(
+        chastefile.root_package().name().unwrap(),
+        "@chastelock/test__v3_basic"
+    );
+    assert_eq!(chastefile.packages().len(), 9);
+
+    Ok(())
+}
+
+#[test]
+fn v3_dangling_dependency_key_path() -> Result<()> {
+    let contents = r#"{
+        "name": "@chastelock/testcase",
+        "lockfileVersion": 3,
+        "packages": {
+            "": { "name": "@chastelock/testcase", "dependencies": { "debug": "4.3.4" } },
+            "node_modules/debug": { "version": "4.3.4", "dependencies": { "ms": "2.1.2" } }
+        }
+    }"#;
+    let err = from_str(contents).unwrap_err();
+    assert!(matches!(err.inner(), Error::DependencyNotFound(name) if name == "ms"));
+    assert_eq!(err.kind(), ErrorKind::DanglingReference);
+    assert_eq!(
+        err.key_path().unwrap().keys(),
+        ["packages", "node_modules/debug", "dependencies", "ms"]
+    );
+
+    let (chastefile, warnings) =
+        parse_str(contents, "", &MemoryFileSource::new(), Warnings::lenient())?;
+    assert_eq!(chastefile.packages().len(), 2);
+    assert_eq!(chastefile.root_package_dependencies().len(), 1);
+    assert!(matches!(warnings.as_slice(), [w] if w.key_path() == err.key_path()));
+
+    Ok(())
+}
+
+#[test]
+fn v3_overrides_malformed_package_json() -> Result<()> {
+    let contents = fs::read_to_string(TEST_WORKSPACES.join("v3_overrides/package-lock.json"))?;
+    let files = MemoryFileSource::from_iter([("package.json", "{ \"overrides\": ".to_string())]);
+    let err = parse_str(&contents, "", &files, Warnings::strict()).unwrap_err();
+    assert!(
+        matches!(err.inner(), Error::SerdeInWorkspace(_, path) if path.ends_with("package.json"))
+    );
+    assert_eq!(err.kind(), ErrorKind::MalformedSyntax);
+    // The syntax error is not in the lockfile.
+    assert_eq!(err.position(), None);
+
+    let (chastefile, warnings) = parse_str(&contents, "", &files, Warnings::lenient())?;
+    assert!(chastefile.overrides_with_ids().is_empty());
+    assert!(matches!(warnings.as_slice(), [Error::SerdeInWorkspace(..)]));
 
     Ok(())
 }
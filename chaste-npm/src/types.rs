@@ -43,3 +43,9 @@ pub struct PackageLock<'a> {
     #[serde(default)]
     pub(crate) packages: HashMap<Cow<'a, str>, DependencyTreePackage<'a>>,
 }
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct PackageJson {
+    #[serde(default)]
+    pub(crate) overrides: serde_json::Map<String, serde_json::Value>,
+}
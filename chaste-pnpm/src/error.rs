@@ -1,8 +1,9 @@
 // SPDX-FileCopyrightText: 2024 The Chaste Authors
 // SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
 
-use std::io;
+use std::{io, path};
 
+use chaste_types::{ErrorKind, Locate, Located, Position};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -35,6 +36,9 @@ pub enum Error {
     #[error("I/O error: {0:?}")]
     IoError(#[from] io::Error),
 
+    #[error("I/O error trying to read {1:?}: {0:?}")]
+    IoInWorkspace(io::Error, path::PathBuf),
+
     #[error("Serde JSON error: {0:?}")]
     JSONError(#[from] serde_json::Error),
 
@@ -43,6 +47,62 @@ pub enum Error {
 
     #[error("SSRI error: {0:?}")]
     SSRIError(#[from] chaste_types::SSRIError),
+
+    #[error(transparent)]
+    At(#[from] Located<Box<Error>>),
+}
+
+impl Error {
+    /// What went wrong, as an [`ErrorKind`] shared with the other implementations.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::UnknownLockfileVersion(_) => ErrorKind::UnsupportedVersion,
+            Error::MissingRootImporter => ErrorKind::Inconsistent,
+            Error::DependencyPackageNotFound(_) | Error::InvalidSnapshotDescriptor(_) => {
+                ErrorKind::DanglingReference
+            }
+            Error::InvalidPackageDescriptor(_)
+            | Error::InvalidPatchedPackageSpecifier(_)
+            | Error::NorwayError(_) => ErrorKind::MalformedSyntax,
+            Error::InvalidPatchHash(_) | Error::SSRIError(_) => ErrorKind::InvalidChecksum,
+            Error::ChasteError(e) => e.kind(),
+            Error::IoError(_) => ErrorKind::Io,
+            Error::IoInWorkspace(e, _) if e.kind() == io::ErrorKind::NotFound => {
+                ErrorKind::MissingWorkspaceFile
+            }
+            Error::IoInWorkspace(..) => ErrorKind::Io,
+            Error::JSONError(e) if e.is_io() => ErrorKind::Io,
+            Error::JSONError(_) => ErrorKind::MalformedSyntax,
+            Error::At(located) => located.source.kind(),
+        }
+    }
+
+    /// Where the syntax error is, if the lockfile could not be deserialized.
+    pub fn position(&self) -> Option<Position> {
+        match self.inner() {
+            Error::NorwayError(e) => e.location().map(|l| Position {
+                line: l.line(),
+                column: l.column(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Locate for Error {
+    fn located(&self) -> Option<&Located<Box<Error>>> {
+        match self {
+            Error::At(located) => Some(located),
+            _ => None,
+        }
+    }
+
+    fn into_located(self) -> Result<Located<Box<Error>>, Error> {
+        match self {
+            Error::At(located) => Ok(located),
+            e => Err(e),
+        }
+    }
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -3,14 +3,14 @@
 
 use std::borrow::Cow;
 use std::collections::{BTreeMap, HashMap, VecDeque};
-use std::path::{Path, PathBuf};
-use std::{fs, io};
+use std::path::Path;
 
 use chaste_types::{
     package_name_str, ssri, Chastefile, ChastefileBuilder, Checksums, DependencyBuilder,
-    DependencyKind, InstallationBuilder, Integrity, LockfileVersion, ModulePath, PackageBuilder,
-    PackageDerivation, PackageDerivationMetaBuilder, PackageID, PackageName, PackagePatchBuilder,
-    PackageSource, ProviderMeta, SourceVersionSpecifier, PACKAGE_JSON_FILENAME,
+    DependencyKind, FileSource, InstallationBuilder, Integrity, Locate as _, LockfileVersion,
+    ModulePath, Override, OverrideBuilder, OverrideSelector, PackageBuilder, PackageDerivation,
+    PackageDerivationMetaBuilder, PackageID, PackageName, PackagePatchBuilder, PackageSource,
+    ProviderMeta, RealFileSource, SourceVersionSpecifier, Warnings, PACKAGE_JSON_FILENAME,
 };
 use nom::branch::alt;
 use nom::bytes::complete::{tag, take};
@@ -20,8 +20,10 @@ use nom::Parser;
 
 pub use crate::error::Error;
 use crate::error::Result;
+pub use crate::prune::write_pruned;
 
 mod error;
+mod prune;
 #[cfg(test)]
 mod tests;
 #[cfg(feature = "fuzzing")]
@@ -50,14 +52,7 @@ impl ProviderMeta for Meta {
 #[allow(clippy::type_complexity)]
 fn snapshot_key_rest<'a>(
     snap_pid: &BTreeMap<&'a str, PackageID>,
-    desc_pid: &BTreeMap<
-        (&'a str, &'a str),
-        (
-            PackageID,
-            &HashMap<Cow<'a, str>, Cow<'a, str>>,
-            &HashMap<Cow<'a, str>, types::lock::PeerDependencyMeta>,
-        ),
-    >,
+    desc_pid: &BTreeMap<(&'a str, &'a str), (PackageID, &HashMap<Cow<'a, str>, Cow<'a, str>>)>,
     rest: &'a str,
 ) -> Option<Vec<&'a str>> {
     let Ok((_, snap_pkg_name)) = delimited(tag("("), package_name_str, tag("@")).parse(rest) else {
@@ -120,22 +115,108 @@ fn snapshot_key_rest<'a>(
 pub fn parse<P>(root_dir: P) -> Result<Chastefile<Meta>>
 where
     P: AsRef<Path>,
+{
+    parse_with_source(root_dir, &RealFileSource)
+}
+
+/// Like [`parse`], but with all files read from `file_source` instead of the file system.
+pub fn parse_with_source<P, S>(root_dir: P, file_source: &S) -> Result<Chastefile<Meta>>
+where
+    P: AsRef<Path>,
+    S: FileSource + ?Sized,
 {
     let root_dir = root_dir.as_ref();
+    let lockfile_contents = file_source.read_to_string(&root_dir.join(LOCKFILE_NAME))?;
+    parse_str(&lockfile_contents, root_dir, file_source)
+}
+
+/// Parse the contents of a pnpm-lock.yaml. The package.json files of importers
+/// are read from `file_source`, relative to `root_dir`.
+pub fn parse_str<P, S>(
+    lockfile_contents: &str,
+    root_dir: P,
+    file_source: &S,
+) -> Result<Chastefile<Meta>>
+where
+    P: AsRef<Path>,
+    S: FileSource + ?Sized,
+{
+    let lockfile: types::Lockfile = serde_norway::from_str(lockfile_contents)?;
+    parse_real(
+        root_dir.as_ref(),
+        lockfile,
+        file_source,
+        &mut Warnings::strict(),
+    )
+}
 
-    let lockfile_contents = fs::read_to_string(root_dir.join(LOCKFILE_NAME))?;
-    let lockfile: types::Lockfile = serde_norway::from_str(&lockfile_contents)?;
+/// Like [`parse_str`], but problems with single packages or dependencies are skipped
+/// and returned alongside, instead of failing the whole parse.
+pub fn parse_str_lenient<P, S>(
+    lockfile_contents: &str,
+    root_dir: P,
+    file_source: &S,
+) -> Result<(Chastefile<Meta>, Vec<Error>)>
+where
+    P: AsRef<Path>,
+    S: FileSource + ?Sized,
+{
+    let lockfile: types::Lockfile = serde_norway::from_str(lockfile_contents)?;
+    let mut warnings = Warnings::lenient();
+    let chastefile = parse_real(root_dir.as_ref(), lockfile, file_source, &mut warnings)?;
+    Ok((chastefile, warnings.into_vec()))
+}
+
+/// Path of the importer that `link` points to, from the importer at `importer_path`,
+/// e.g. "../ligma-api" from "balls" is "ligma-api".
+fn join_importer_path(importer_path: &str, link: &str) -> String {
+    let mut components: Vec<&str> = importer_path
+        .split('/')
+        .filter(|c| !c.is_empty() && *c != ".")
+        .collect();
+    for component in link.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            c => components.push(c),
+        }
+    }
+    if components.is_empty() {
+        ".".to_string()
+    } else {
+        components.join("/")
+    }
+}
 
-    parse_real(root_dir, lockfile, &fs::read_to_string)
+/// Parse an entry of `overrides:`, where the key is like "bar", "bar@^1",
+/// or "foo@1>bar" for only dependencies of foo.
+fn parse_override(key: &str, value: &str) -> Result<Override> {
+    // ">" is also in ranges, like "foo@>=1 <2>bar".
+    let separator = key.char_indices().skip(1).find(|&(i, c)| {
+        c == '>' && !key[..i].ends_with(['@', ' ', '|']) && !key[i + 1..].starts_with('=')
+    });
+    let override_ = match separator {
+        Some((i, _)) => {
+            let mut override_ =
+                OverrideBuilder::new(OverrideSelector::parse(&key[i + 1..])?, value.to_string());
+            override_.parent(OverrideSelector::parse(&key[..i])?);
+            override_
+        }
+        None => OverrideBuilder::new(OverrideSelector::parse(key)?, value.to_string()),
+    };
+    Ok(override_.build())
 }
 
-fn parse_real<FG>(
+fn parse_real<S>(
     root_dir: &Path,
     lockfile: types::Lockfile,
-    file_getter: &FG,
+    file_source: &S,
+    warnings: &mut Warnings<Error>,
 ) -> Result<Chastefile<Meta>>
 where
-    FG: Fn(PathBuf) -> Result<String, io::Error>,
+    S: FileSource + ?Sized,
 {
     if lockfile.lockfile_version != "9.0" {
         return Err(Error::UnknownLockfileVersion(
@@ -149,11 +230,14 @@ where
 
     let mut importer_to_pid = HashMap::with_capacity(lockfile.importers.len());
     for importer_path in lockfile.importers.keys() {
-        let package_json_contents = file_getter(if *importer_path == "." {
+        let package_json_path = if *importer_path == "." {
             root_dir.join(PACKAGE_JSON_FILENAME)
         } else {
             root_dir.join(importer_path).join(PACKAGE_JSON_FILENAME)
-        })?;
+        };
+        let package_json_contents = file_source
+            .read_to_string(&package_json_path)
+            .map_err(|e| Error::IoInWorkspace(e, package_json_path.clone()))?;
         let package_json: types::PackageJson = serde_json::from_str(&package_json_contents)?;
         let importer_pkg = PackageBuilder::new(
             package_json
@@ -181,11 +265,25 @@ where
         chastefile.add_package_installation(installation);
     }
 
+    for (key, value) in &lockfile.overrides {
+        match parse_override(key, value) {
+            Ok(override_) => {
+                chastefile.add_override(override_);
+            }
+            Err(e) => warnings.skip(e.at(["overrides", key.as_ref()]))?,
+        }
+    }
+
     let mut desc_pid = BTreeMap::new();
     for (pkg_desc, pkg) in &lockfile.packages {
-        let (_, (package_name, _, package_svd)) = (package_name_str, tag("@"), rest)
-            .parse(pkg_desc)
-            .map_err(|_| Error::InvalidPackageDescriptor(pkg_desc.to_string()))?;
+        let Ok((_, (package_name, _, package_svd))) =
+            (package_name_str, tag("@"), rest).parse(pkg_desc)
+        else {
+            warnings.skip(
+                Error::InvalidPackageDescriptor(pkg_desc.to_string()).at(["packages", pkg_desc]),
+            )?;
+            continue;
+        };
         let version = pkg
             .version
             .as_deref()
@@ -216,20 +314,29 @@ where
         {
             package.source(PackageSource::Npm);
         }
-        let pkg_pid = chastefile.add_package(package.build()?)?;
+        let package = warnings.build_package(package, |e| e.at(["packages", pkg_desc]))?;
+        let pkg_pid = chastefile.add_package(package)?;
         desc_pid.insert(
             (package_name, package_svd),
-            (pkg_pid, &pkg.peer_dependencies, &pkg.peer_dependencies_meta),
+            (pkg_pid, &pkg.peer_dependencies),
         );
     }
 
     let mut patch_store = HashMap::with_capacity(lockfile.patched_dependencies.len());
     for (k, v) in &lockfile.patched_dependencies {
         let Ok((_, (pn, _))) = (package_name_str, alt((eof, (tag("@"))))).parse(k) else {
-            return Err(Error::InvalidPatchedPackageSpecifier(k.to_string()));
+            warnings.skip(
+                Error::InvalidPatchedPackageSpecifier(k.to_string()).at(["patchedDependencies", k]),
+            )?;
+            continue;
         };
         if v.hash.len() != 64 {
-            return Err(Error::InvalidPatchHash(v.hash.to_string()));
+            warnings.skip(Error::InvalidPatchHash(v.hash.to_string()).at([
+                "patchedDependencies",
+                k,
+                "hash",
+            ]))?;
+            continue;
         }
         let integrity = Integrity::from_hex(v.hash, ssri::Algorithm::Sha256)?;
         let mut patch = PackagePatchBuilder::new(v.path.to_string());
@@ -245,19 +352,20 @@ where
         let Some((snap_rest, pkg_name)) =
             terminated(package_name_str, tag("@")).parse(pkg_desc).ok()
         else {
-            return Err(Error::InvalidPackageDescriptor(pkg_desc.to_string()));
+            warnings.skip(
+                Error::InvalidPackageDescriptor(pkg_desc.to_string()).at(["snapshots", pkg_desc]),
+            )?;
+            continue 'queue;
         };
         // Not a peer dep: "@chastelock/package@1.0.0" snapshot for the ("@chastelock/package", "1.0.0") package.
-        if let Some(&(pid, peer_deps, peers_meta)) = desc_pid.get(&(pkg_name, snap_rest)) {
+        if let Some(&(pid, peer_deps)) = desc_pid.get(&(pkg_name, snap_rest)) {
             snap_pid.insert(pkg_desc.as_ref(), pid);
-            pid_peers.insert(pid, (peer_deps, peers_meta));
+            pid_peers.insert(pid, peer_deps);
             lap_i = 0;
             continue 'queue;
         }
         // Looking through descriptors to find a matching package.
-        for ((d_pkg_name, d_pkg_svd), (mut pid, peer_deps, peers_meta)) in
-            desc_pid.range((pkg_name, "")..)
-        {
+        for ((d_pkg_name, d_pkg_svd), (mut pid, peer_deps)) in desc_pid.range((pkg_name, "")..) {
             // List is sorted alphabetically.
             if *d_pkg_name != pkg_name {
                 break;
@@ -277,7 +385,10 @@ where
             .parse(peers_suffix)
             {
                 let Some(patch) = patch_store.get(&(pkg_name, patch_hash)) else {
-                    return Err(Error::InvalidPatchHash(patch_hash.to_string()));
+                    warnings.skip(
+                        Error::InvalidPatchHash(patch_hash.to_string()).at(["snapshots", pkg_desc]),
+                    )?;
+                    continue 'queue;
                 };
                 let patch_deriv_meta =
                     PackageDerivationMetaBuilder::new(PackageDerivation::Patch(patch.clone()), pid)
@@ -287,7 +398,9 @@ where
                     Some(d_pkg_svd.to_string()),
                 );
                 patched_pkg.derived(patch_deriv_meta);
-                pid = chastefile.add_package(patched_pkg.build()?)?;
+                pid = chastefile.add_package(
+                    warnings.build_package(patched_pkg, |e| e.at(["snapshots", pkg_desc]))?,
+                )?;
 
                 if suff.is_empty() {
                     snap_pid.insert(pkg_desc, pid);
@@ -296,7 +409,7 @@ where
                 }
                 peers_suffix = suff;
             }
-            if let Ok(_) = terminated(
+            if terminated(
                 delimited(
                     tag::<_, _, ()>("("),
                     verify(take(32usize), |hash: &str| {
@@ -309,11 +422,12 @@ where
                 eof,
             )
             .parse(peers_suffix)
+            .is_ok()
             {
                 // When the key suffix is longer than peersSuffixMaxLength, it's replaced with a hash
 
                 snap_pid.insert(pkg_desc, pid);
-                pid_peers.insert(pid, (peer_deps, peers_meta));
+                pid_peers.insert(pid, peer_deps);
                 lap_i = 0;
                 continue 'queue;
             }
@@ -323,12 +437,15 @@ where
                 continue;
             };
             snap_pid.insert(pkg_desc, pid);
-            pid_peers.insert(pid, (peer_deps, peers_meta));
+            pid_peers.insert(pid, peer_deps);
             lap_i = 0;
             continue 'queue;
         }
         if lap_i > snap_queue.len() {
-            return Err(Error::InvalidSnapshotDescriptor(pkg_desc.to_string()));
+            warnings.skip(
+                Error::InvalidSnapshotDescriptor(pkg_desc.to_string()).at(["snapshots", pkg_desc]),
+            )?;
+            continue 'queue;
         }
         lap_i += 1;
         snap_queue.push_back(pkg_desc);
@@ -345,8 +462,15 @@ where
             ),
         ] {
             for (dep_name, d) in dependencies {
-                if d.version.starts_with("link:") {
-                    // TODO:
+                if let Some(link) = d.version.strip_prefix("link:") {
+                    // Links to other workspace members. Links elsewhere are not in the lockfile.
+                    let link_path = join_importer_path(importer_path, link);
+                    let Some(&member_pid) = importer_to_pid.get(&link_path.as_str()) else {
+                        continue;
+                    };
+                    let mut dep = DependencyBuilder::new(kind, importer_pid, member_pid);
+                    dep.svs(SourceVersionSpecifier::new(d.specifier.to_string())?);
+                    chastefile.add_dependency(dep.build());
                     continue;
                 }
                 let mut is_aliased = false;
@@ -371,10 +495,16 @@ where
                         is_aliased = true;
                         *dep_pid
                     } else {
-                        return Err(Error::DependencyPackageNotFound(format!(
-                            "{dep_name}@{}",
-                            d.version
-                        )));
+                        warnings.skip(
+                            Error::DependencyPackageNotFound(format!("{dep_name}@{}", d.version))
+                                .at([
+                                    "importers",
+                                    importer_path,
+                                    kind.package_json_field(),
+                                    dep_name.as_ref(),
+                                ]),
+                        )?;
+                        continue;
                     }
                 };
                 let mut dep = DependencyBuilder::new(kind, importer_pid, dep_pid);
@@ -387,11 +517,11 @@ where
         }
     }
     for (pkg_desc, snap) in &lockfile.snapshots {
-        let pkg_pid = *snap_pid.get(pkg_desc.as_ref()).unwrap();
-        let (pkg_peers, _) = match pid_peers.get(&pkg_pid) {
-            Some((pd, pm)) => (Some(pd), Some(pm)),
-            None => (None, None),
+        // Snapshots that could not be matched to a package were skipped.
+        let Some(&pkg_pid) = snap_pid.get(pkg_desc.as_ref()) else {
+            continue;
         };
+        let pkg_peers = pid_peers.get(&pkg_pid);
         for (dependencies, kind_) in [
             (&snap.dependencies, DependencyKind::Dependency),
             (
@@ -400,9 +530,7 @@ where
             ),
         ] {
             for (dep_name, dep_svd) in dependencies {
-                let (kind, svs) = if let Some(svs) =
-                    pkg_peers.as_ref().and_then(|p| p.get(dep_name))
-                {
+                let (kind, svs) = if let Some(svs) = pkg_peers.and_then(|p| p.get(dep_name)) {
                     match kind_ {
                         DependencyKind::Dependency => (DependencyKind::PeerDependency, Some(svs)),
                         DependencyKind::OptionalDependency => {
@@ -433,9 +561,15 @@ where
                         // If the dependency is aliased
                         *dep_pid
                     } else {
-                        return Err(Error::DependencyPackageNotFound(format!(
-                            "{dep_name}@{dep_svd}"
-                        )));
+                        warnings.skip(
+                            Error::DependencyPackageNotFound(format!("{dep_name}@{dep_svd}")).at([
+                                "snapshots",
+                                pkg_desc.as_ref(),
+                                kind_.package_json_field(),
+                                dep_name.as_ref(),
+                            ]),
+                        )?;
+                        continue;
                     }
                 });
                 if let Some(svs) = svs {
@@ -446,17 +580,18 @@ where
         }
     }
 
+    chastefile.match_overrides();
     Ok(chastefile.build()?)
 }
 
 #[cfg(feature = "fuzzing")]
-pub fn parse_arbitrary<FG>(
+pub fn parse_arbitrary<S>(
     lockfile: types::Lockfile,
     root_dir: &Path,
-    file_getter: &FG,
+    file_source: &S,
 ) -> Result<Chastefile<Meta>>
 where
-    FG: Fn(PathBuf) -> Result<String, io::Error>,
+    S: FileSource + ?Sized,
 {
-    parse_real(root_dir, lockfile, file_getter)
+    parse_real(root_dir, lockfile, file_source, &mut Warnings::strict())
 }
@@ -0,0 +1,153 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use chaste_types::indented::{edit_entries, entry_key, lines};
+use chaste_types::{Chastefile, ProviderMeta};
+
+use crate::error::Result;
+use crate::types;
+
+/// Fields of an importer with dependencies, as in package.json.
+const DEPENDENCY_FIELDS: [&str; 4] = [
+    "dependencies",
+    "devDependencies",
+    "peerDependencies",
+    "optionalDependencies",
+];
+
+/// Key in `snapshots:` of a dependency on `name` at `version`, as written in an importer
+/// or a snapshot. Links to other importers are not snapshots.
+fn snapshot_key<'l>(lockfile: &'l types::Lockfile, name: &str, version: &str) -> Option<&'l str> {
+    lockfile
+        .snapshots
+        .get_key_value(format!("{name}@{version}").as_str())
+        // If the dependency is aliased, the version is the whole key.
+        .or_else(|| lockfile.snapshots.get_key_value(version))
+        .map(|(key, _)| key.as_ref())
+}
+
+/// Key in `packages:` of a snapshot, which is the same without peer dependencies and patches.
+fn package_key<'l>(lockfile: &'l types::Lockfile, snapshot_key: &str) -> Option<&'l str> {
+    let without_suffix = snapshot_key
+        .find('(')
+        .map_or(snapshot_key, |i| &snapshot_key[..i]);
+    lockfile
+        .packages
+        .get_key_value(without_suffix)
+        .map(|(key, _)| key.as_ref())
+}
+
+/// Write the pnpm-lock.yaml with contents `lockfile_contents` again, with only
+/// the importers in `pruned`, as made with [`Chastefile::prune`] or [`Chastefile::subgraph`]
+/// from the result of parsing the same contents, and what they need.
+///
+/// Importers keep only the dependencies that were not pruned away. Everything else
+/// is kept as it is, with the same formatting.
+pub fn write_pruned<P>(lockfile_contents: &str, pruned: &Chastefile<P>) -> Result<String>
+where
+    P: ProviderMeta,
+{
+    let lockfile: types::Lockfile = serde_norway::from_str(lockfile_contents)?;
+
+    // Dependencies that are kept, by the path of the importer.
+    let mut importers: HashMap<&str, HashSet<(&str, &str)>> = HashMap::new();
+    for pid in [pruned.root_package_id()]
+        .iter()
+        .chain(pruned.workspace_member_ids())
+    {
+        for installation in pruned.package_installations(*pid) {
+            let path = match installation.path().as_ref() {
+                "" => ".",
+                path => path,
+            };
+            importers.insert(path, pruned.declared_dependencies(*pid));
+        }
+    }
+
+    let mut snapshots: HashSet<&str> = HashSet::new();
+    let mut queue: VecDeque<&str> = VecDeque::new();
+    for (path, importer) in &lockfile.importers {
+        let Some(declared) = importers.get(path) else {
+            continue;
+        };
+        for (field, dependencies) in DEPENDENCY_FIELDS.into_iter().zip([
+            &importer.dependencies,
+            &importer.dev_dependencies,
+            &importer.peer_dependencies,
+            &importer.optional_dependencies,
+        ]) {
+            for (name, dependency) in dependencies {
+                if declared.contains(&(field, name.as_ref())) {
+                    queue.extend(snapshot_key(&lockfile, name, &dependency.version));
+                }
+            }
+        }
+    }
+    while let Some(key) = queue.pop_front() {
+        if !snapshots.insert(key) {
+            continue;
+        }
+        let snapshot = &lockfile.snapshots[key];
+        for (name, version) in snapshot
+            .dependencies
+            .iter()
+            .chain(&snapshot.optional_dependencies)
+        {
+            queue.extend(snapshot_key(&lockfile, name, version));
+        }
+    }
+    let packages: HashSet<&str> = snapshots
+        .iter()
+        .filter_map(|key| package_key(&lockfile, key))
+        .collect();
+
+    let (edited, _) = edit_entries(&lines(lockfile_contents), 0, |section| {
+        let name = entry_key(section[0]);
+        let (body, kept) = match name.as_ref() {
+            "importers" => edit_entries(&section[1..], 2, |importer| {
+                let declared = importers.get(entry_key(importer[0]).as_ref())?;
+                Some(edit_importer(importer, declared))
+            }),
+            "packages" => edit_entries(&section[1..], 2, |package| {
+                packages
+                    .contains(entry_key(package[0]).as_ref())
+                    .then(|| package.to_vec())
+            }),
+            "snapshots" => edit_entries(&section[1..], 2, |snapshot| {
+                snapshots
+                    .contains(entry_key(snapshot[0]).as_ref())
+                    .then(|| snapshot.to_vec())
+            }),
+            _ => return Some(section.to_vec()),
+        };
+        if kept == 0 {
+            return None;
+        }
+        Some([&section[..1], &body[..]].concat())
+    });
+    Ok(edited.concat())
+}
+
+/// Lines of the entry of an importer in `importers:`, with only the dependencies in `declared`.
+fn edit_importer<'c>(importer: &[&'c str], declared: &HashSet<(&str, &str)>) -> Vec<&'c str> {
+    let (body, kept) = edit_entries(&importer[1..], 4, |field| {
+        let field_name = entry_key(field[0]);
+        let Some(field_name) = DEPENDENCY_FIELDS.iter().find(|f| **f == field_name) else {
+            return Some(field.to_vec());
+        };
+        let (dependencies, kept) = edit_entries(&field[1..], 6, |dependency| {
+            declared
+                .contains(&(*field_name, entry_key(dependency[0]).as_ref()))
+                .then(|| dependency.to_vec())
+        });
+        (kept > 0).then(|| [&field[..1], &dependencies[..]].concat())
+    });
+    if kept == 0 && importer.len() > 1 {
+        // All dependencies were pruned away, which pnpm writes like "  .: {}".
+        let header = importer[0].trim_end();
+        return vec![header, " {}\n"];
+    }
+    [&importer[..1], &body[..]].concat()
+}
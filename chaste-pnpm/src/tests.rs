@@ -1,15 +1,17 @@
 // SPDX-FileCopyrightText: 2024 The Chaste Authors
 // SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
 
+use std::fs;
 use std::path::PathBuf;
 use std::sync::LazyLock;
 
 use chaste_types::{
-    Chastefile, DependencyKind, Package, PackageDerivation, PackageID, PackageSourceType,
+    Chastefile, DependencyKind, ErrorKind, MemoryFileSource, Package, PackageDerivation, PackageID,
+    PackageSourceType,
 };
 
 use crate::error::Result;
-use crate::{parse, Meta};
+use crate::{parse, parse_str, write_pruned, Meta};
 
 static TEST_WORKSPACES: LazyLock<PathBuf> = LazyLock::new(|| PathBuf::from("test_workspaces"));
 
@@ -138,7 +140,7 @@ fn v9_npm_tag() -> Result<()> {
 #[test]
 fn v9_overrides() -> Result<()> {
     let chastefile = test_workspace("v9_overrides")?;
-    let [(_ms_pid, ms_pkg)] = *chastefile
+    let [(ms_pid, ms_pkg)] = *chastefile
         .packages_with_ids()
         .into_iter()
         .filter(|(_pid, p)| p.name().is_some_and(|n| n == "ms"))
@@ -149,7 +151,7 @@ fn v9_overrides() -> Result<()> {
     assert_eq!(ms_pkg.version().unwrap().to_string(), "2.1.3");
     assert_eq!(ms_pkg.source_type(), Some(PackageSourceType::Npm));
 
-    let [(_path_pid, path_pkg)] = *chastefile
+    let [(path_pid, path_pkg)] = *chastefile
         .packages_with_ids()
         .into_iter()
         .filter(|(_pid, p)| p.name().is_some_and(|n| n == "path-to-regexp"))
@@ -160,7 +162,7 @@ fn v9_overrides() -> Result<()> {
     assert_eq!(path_pkg.version().unwrap().to_string(), "0.1.12");
     assert_eq!(path_pkg.source_type(), Some(PackageSourceType::Npm));
 
-    let [(_scwm_pid, scwm_pkg)] = *chastefile
+    let [(scwm_pid, scwm_pkg)] = *chastefile
         .packages_with_ids()
         .into_iter()
         .filter(|(_pid, p)| p.name().is_some_and(|n| n == "side-channel-weakmap"))
@@ -171,6 +173,37 @@ fn v9_overrides() -> Result<()> {
     assert_eq!(scwm_pkg.version().unwrap().to_string(), "1.0.1");
     assert_eq!(scwm_pkg.source_type(), Some(PackageSourceType::TarballURL));
 
+    let overrides = chastefile.overrides_with_ids();
+    let [(ms_oid, ms_override), (path_oid, _), (scwm_oid, scwm_override)] = *overrides else {
+        panic!();
+    };
+    assert_eq!(ms_override.selector().name(), "ms");
+    assert_eq!(ms_override.replacement(), "^2.1");
+    assert_eq!(scwm_override.selector().name(), "side-channel-weakmap");
+    let scwm_parent = scwm_override.parent().unwrap();
+    assert_eq!(scwm_parent.name(), "side-channel");
+    assert_eq!(scwm_parent.svs(), Some("^1.0.6"));
+    assert!(scwm_override.is_parent_direct());
+
+    // Every dependency on ms is overridden.
+    let ms_deps = chastefile.overridden_dependencies(ms_oid);
+    assert_eq!(ms_deps.len(), chastefile.package_dependents(ms_pid).len());
+    assert!(ms_deps
+        .iter()
+        .any(|d| d.from == chastefile.root_package_id()));
+    let [path_dep] = *chastefile.overridden_dependencies(path_oid) else {
+        panic!();
+    };
+    assert_eq!(path_dep.on, path_pid);
+    let [scwm_dep] = *chastefile.overridden_dependencies(scwm_oid) else {
+        panic!();
+    };
+    assert_eq!(scwm_dep.on, scwm_pid);
+    assert_eq!(
+        chastefile.package(scwm_dep.from).name().unwrap(),
+        "side-channel"
+    );
+
     Ok(())
 }
 
@@ -398,6 +431,164 @@ fn v9_workspace_basic() -> Result<()> {
         .map(|i| i.path().as_ref())
         .collect::<Vec<&str>>();
     assert_eq!(balls_install_paths, ["balls"]);
+    // Linked with "link:../ligma-api".
+    let balls_dependencies = chastefile.package_dependencies(balls_pid);
+    let [balls_dep] = *balls_dependencies else {
+        panic!("{balls_dependencies:?}");
+    };
+    assert_eq!(balls_dep.on, ligma_pid);
+    assert_eq!(balls_dep.svs().unwrap(), "workspace:");
+
+    Ok(())
+}
+
+#[test]
+fn v9_workspace_basic_in_memory() -> Result<()> {
+    let dir = TEST_WORKSPACES.join("v9_workspace_basic");
+    let files: MemoryFileSource = [
+        "package.json",
+        "balls/package.json",
+        "ligma-api/package.json",
+    ]
+    .into_iter()
+    .map(|p| (p, fs::read_to_string(dir.join(p)).unwrap()))
+    .collect();
+    let lockfile_contents = fs::read_to_string(dir.join("pnpm-lock.yaml"))?;
+    let chastefile = parse_str(&lockfile_contents, "", &files)?;
+    assert_eq!(chastefile.packages().len(), 4);
+    assert_eq!(chastefile.workspace_member_ids().len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn v9_workspace_basic_missing_member() -> Result<()> {
+    let dir = TEST_WORKSPACES.join("v9_workspace_basic");
+    let files: MemoryFileSource = ["package.json", "balls/package.json"]
+        .into_iter()
+        .map(|p| (p, fs::read_to_string(dir.join(p)).unwrap()))
+        .collect();
+    let lockfile_contents = fs::read_to_string(dir.join("pnpm-lock.yaml"))?;
+    let err = parse_str(&lockfile_contents, "", &files).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::MissingWorkspaceFile);
+
+    Ok(())
+}
+
+#[test]
+fn v9_basic_prune_prod() -> Result<()> {
+    let dir = TEST_WORKSPACES.join("v9_basic");
+    let lockfile_contents = fs::read_to_string(dir.join("pnpm-lock.yaml"))?;
+    let chastefile = parse_str(&lockfile_contents, &dir, &chaste_types::RealFileSource)?;
+    let pruned = chastefile.prune(&[], true);
+    let pruned_contents = write_pruned(&lockfile_contents, &pruned)?;
+    assert_eq!(
+        pruned_contents,
+        "lockfileVersion: '9.0'
+
+settings:
+  autoInstallPeers: true
+  excludeLinksFromLockfile: false
+
+importers:
+
+  .:
+    dependencies:
+      is-even:
+        specifier: 1.0.0
+        version: 1.0.0
+
+packages:
+
+  is-buffer@1.1.6:
+    resolution: {integrity: sha512-NcdALwpXkTm5Zvvbk7owOUSvVvBKDgKP5/ewfXEznmQFfs4ZRmanOeKBTjRVjka3QFoN6XJ+9F3USqfHqTaU5w==}
+
+  is-even@1.0.0:
+    resolution: {integrity: sha512-LEhnkAdJqic4Dbqn58A0y52IXoHWlsueqQkKfMfdEnIYG8A1sm/GHidKkS6yvXlMoRrkM34csHnXQtOqcb+Jzg==}
+    engines: {node: '>=0.10.0'}
+
+  is-number@3.0.0:
+    resolution: {integrity: sha512-4cboCqIpliH+mAvFNegjZQ4kgKc3ZUhQVr3HvWbSh5q3WH2v82ct+T2Y1hdU5Gdtorx/cLifQjqCbL7bpznLTg==}
+    engines: {node: '>=0.10.0'}
+
+  is-odd@0.1.2:
+    resolution: {integrity: sha512-Ri7C2K7o5IrUU9UEI8losXJCCD/UtsaIrkR5sxIcFg4xQ9cRJXlWA5DQvTE0yDc0krvSNLsRGXN11UPS6KyfBw==}
+    engines: {node: '>=0.10.0'}
+
+  kind-of@3.2.2:
+    resolution: {integrity: sha512-NOW9QQXMoZGg/oqnVNoNTTIFEIid1627WCffUBJEdMxYApq7mNE7CpzucIPc+ZQg25Phej7IJSmX3hO+oblOtQ==}
+    engines: {node: '>=0.10.0'}
+
+snapshots:
+
+  is-buffer@1.1.6: {}
+
+  is-even@1.0.0:
+    dependencies:
+      is-odd: 0.1.2
+
+  is-number@3.0.0:
+    dependencies:
+      kind-of: 3.2.2
+
+  is-odd@0.1.2:
+    dependencies:
+      is-number: 3.0.0
+
+  kind-of@3.2.2:
+    dependencies:
+      is-buffer: 1.1.6
+"
+    );
+    let reparsed = parse_str(&pruned_contents, &dir, &chaste_types::RealFileSource)?;
+    assert_eq!(reparsed.packages().len(), pruned.packages().len());
+
+    Ok(())
+}
+
+#[test]
+fn v9_workspace_basic_prune() -> Result<()> {
+    let dir = TEST_WORKSPACES.join("v9_workspace_basic");
+    let lockfile_contents = fs::read_to_string(dir.join("pnpm-lock.yaml"))?;
+    let chastefile = parse_str(&lockfile_contents, &dir, &chaste_types::RealFileSource)?;
+    let [ligma_pid] = *chastefile
+        .workspace_member_ids()
+        .iter()
+        .copied()
+        .filter(|pid| chastefile.package(*pid).name().unwrap() == "ligma-api")
+        .collect::<Vec<PackageID>>()
+    else {
+        panic!();
+    };
+    let pruned = chastefile.prune(&[ligma_pid], false);
+    assert_eq!(
+        write_pruned(&lockfile_contents, &pruned)?,
+        "lockfileVersion: '9.0'
+
+settings:
+  autoInstallPeers: true
+  excludeLinksFromLockfile: false
+
+importers:
+
+  .: {}
+
+  ligma-api:
+    dependencies:
+      long:
+        specifier: ^5.0.0
+        version: 5.2.3
+
+packages:
+
+  long@5.2.3:
+    resolution: {integrity: sha512-lcHwpNoggQTObv5apGNCTdJrO69eHOZMi4BNC+rTLER8iHAqGrUVeLh/irVIM7zTw2bOXA8T6uNPeujwOLg/2Q==}
+
+snapshots:
+
+  long@5.2.3: {}
+"
+    );
 
     Ok(())
 }
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
 
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::Deserialize;
 
@@ -18,7 +18,8 @@ pub(crate) struct PackageJson<'a> {
 #[serde(rename_all = "camelCase")]
 pub struct Lockfile<'a> {
     pub(crate) lockfile_version: &'a str,
-    pub(crate) settings: lock::Settings,
+    #[serde(default)]
+    pub(crate) overrides: BTreeMap<Cow<'a, str>, Cow<'a, str>>,
     #[serde(default)]
     pub(crate) patched_dependencies: HashMap<Cow<'a, str>, lock::Patch<'a>>,
     pub(crate) importers: HashMap<&'a str, lock::Importer<'a>>,
@@ -45,8 +46,6 @@ pub(crate) mod lock {
         #[serde(borrow, default)]
         pub(crate) peer_dependencies: HashMap<Cow<'a, str>, ImporterDependency<'a>>,
         #[serde(borrow, default)]
-        pub(crate) peer_dependencies_meta: HashMap<Cow<'a, str>, PeerDependencyMeta>,
-        #[serde(borrow, default)]
         pub(crate) optional_dependencies: HashMap<Cow<'a, str>, ImporterDependency<'a>>,
     }
 
@@ -58,13 +57,6 @@ pub(crate) mod lock {
         pub(crate) version: Cow<'a, str>,
     }
 
-    #[derive(Deserialize, Debug)]
-    #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
-    #[serde(rename_all = "camelCase")]
-    pub(crate) struct PeerDependencyMeta {
-        pub(crate) optional: Option<bool>,
-    }
-
     #[derive(Debug, Deserialize)]
     #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
     #[serde(rename_all = "camelCase")]
@@ -82,8 +74,6 @@ pub(crate) mod lock {
         pub(crate) version: Option<Cow<'a, str>>,
         #[serde(borrow, default)]
         pub(crate) peer_dependencies: HashMap<Cow<'a, str>, Cow<'a, str>>,
-        #[serde(borrow, default)]
-        pub(crate) peer_dependencies_meta: HashMap<Cow<'a, str>, PeerDependencyMeta>,
     }
 
     #[derive(Debug, Deserialize)]
@@ -102,14 +92,5 @@ pub(crate) mod lock {
         pub(crate) dependencies: HashMap<Cow<'a, str>, Cow<'a, str>>,
         #[serde(borrow, default)]
         pub(crate) optional_dependencies: HashMap<Cow<'a, str>, Cow<'a, str>>,
-        #[serde(borrow, default)]
-        pub(crate) transitive_peer_dependencies: Vec<Cow<'a, str>>,
-    }
-
-    #[derive(Debug, Deserialize)]
-    #[cfg_attr(feature = "fuzzing", derive(arbitrary::Arbitrary))]
-    #[serde(rename_all = "camelCase")]
-    pub(crate) struct Settings {
-        pub(crate) auto_install_peers: Option<bool>,
     }
 }
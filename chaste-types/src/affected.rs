@@ -0,0 +1,171 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::collections::HashMap;
+
+use crate::chastefile::Chastefile;
+use crate::checksums::Checksums;
+use crate::derivation::PackageDerivation;
+use crate::package::PackageID;
+use crate::source::PackageSource;
+use crate::ProviderMeta;
+
+/// How a package was resolved, as compared between lockfiles.
+#[derive(PartialEq)]
+struct Resolved<'a> {
+    source: Option<&'a PackageSource>,
+    checksums: Option<&'a Checksums>,
+    derivation: Option<&'a PackageDerivation>,
+}
+
+/// Packages by name and version, with how they were resolved.
+type Closure<'a> = HashMap<(Option<&'a str>, Option<String>), Vec<Resolved<'a>>>;
+
+fn same_closure(a: &Closure, b: &Closure) -> bool {
+    a.len() == b.len()
+        && a.iter().all(|(key, a)| {
+            b.get(key)
+                .is_some_and(|b| a.len() == b.len() && a.iter().all(|r| b.contains(r)))
+        })
+}
+
+impl<P: ProviderMeta> Chastefile<P> {
+    fn member_path(&self, member: PackageID) -> Option<&str> {
+        self.package_installations(member)
+            .first()
+            .map(|i| i.path().as_ref())
+    }
+
+    /// The member and the packages it depends on, directly or transitively.
+    fn closure(&self, member: PackageID, prod_only: bool) -> Closure<'_> {
+        let dependencies = match prod_only {
+            true => self.recursive_prod_package_dependencies(member),
+            false => self.recursive_package_dependencies(member),
+        };
+        let mut closure = Closure::new();
+        for pid in [member]
+            .into_iter()
+            .chain(dependencies.iter().map(|d| d.on))
+        {
+            let package = self.package(pid);
+            let resolved = Resolved {
+                source: package.source(),
+                checksums: package.checksums(),
+                derivation: package.derivation(),
+            };
+            let key = (
+                package.name().map(|n| n.as_ref()),
+                package.version().map(|v| v.to_string()),
+            );
+            let resolutions = closure.entry(key).or_default();
+            if !resolutions.contains(&resolved) {
+                resolutions.push(resolved);
+            }
+        }
+        closure
+    }
+
+    /// Workspace members whose packages could differ from those in `base`, e.g. the same
+    /// project before a change to the lockfile, so that only those have to be tested again.
+    ///
+    /// A member is affected if it, or a package it depends on, directly or transitively,
+    /// was not in its dependencies in `base` with the same version, source, checksums and
+    /// patches. Members are matched with those in `base` by path, and members that were not
+    /// in `base` are affected. With `prod_only`, dependencies of kind
+    /// [`crate::DependencyKind::DevDependency`] are not followed.
+    pub fn affected_workspace_members<Q>(
+        &self,
+        base: &Chastefile<Q>,
+        prod_only: bool,
+    ) -> Vec<PackageID>
+    where
+        Q: ProviderMeta,
+    {
+        let base_members: HashMap<&str, PackageID> = base
+            .workspace_member_ids()
+            .iter()
+            .filter_map(|&pid| Some((base.member_path(pid)?, pid)))
+            .collect();
+        let mut affected: Vec<PackageID> = self
+            .workspace_member_ids()
+            .iter()
+            .copied()
+            .filter(|&member| {
+                let Some(&base_member) = self
+                    .member_path(member)
+                    .and_then(|path| base_members.get(path))
+                else {
+                    return true;
+                };
+                !same_closure(
+                    &self.closure(member, prod_only),
+                    &base.closure(base_member, prod_only),
+                )
+            })
+            .collect();
+        affected.sort_unstable_by_key(|pid| pid.0);
+        affected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chastefile::graph_builder;
+    use crate::error::Result;
+    use crate::{
+        Chastefile, DependencyKind, InstallationBuilder, ModulePath, PackageID, PackageSource,
+    };
+
+    /// Members "app", with a devDependency on "long", "lib", depending on "long",
+    /// and "web", depending on "lib".
+    fn project(long_version: &str) -> Result<(Chastefile<()>, Vec<PackageID>)> {
+        let (mut chastefile, pids) = graph_builder(
+            &["root", "app", "lib", "web", "long"],
+            &["app", "lib", "web"],
+            &[
+                ("app", DependencyKind::DevDependency, "long"),
+                ("lib", DependencyKind::Dependency, "long"),
+                ("web", DependencyKind::Dependency, "lib"),
+            ],
+            |name, package| match name {
+                "root" => package.name(None),
+                "long" => {
+                    package.version(Some(long_version.to_string()));
+                    package.source(PackageSource::Npm);
+                }
+                _ => package.version(Some("1.0.0".to_string())),
+            },
+        );
+        let members = vec![pids["app"], pids["lib"], pids["web"]];
+        for name in ["app", "lib", "web"] {
+            let path = ModulePath::new(name.to_string())?;
+            chastefile
+                .add_package_installation(InstallationBuilder::new(pids[name], path).build()?);
+        }
+        Ok((chastefile.build()?, members))
+    }
+
+    #[test]
+    fn affected_workspace_members() -> Result<()> {
+        let (base, _) = project("5.2.2")?;
+        let (chastefile, members) = project("5.2.3")?;
+        let [app, lib, web] = members[..] else {
+            unreachable!();
+        };
+
+        assert_eq!(
+            chastefile.affected_workspace_members(&base, true),
+            [lib, web]
+        );
+        assert_eq!(
+            chastefile.affected_workspace_members(&base, false),
+            [app, lib, web]
+        );
+        let (same, _) = project("5.2.3")?;
+        assert!(chastefile
+            .affected_workspace_members(&same, false)
+            .is_empty());
+
+        Ok(())
+    }
+}
@@ -7,6 +7,7 @@ use std::rc::Rc;
 use crate::dependency::Dependency;
 use crate::error::{Error, Result};
 use crate::installation::Installation;
+use crate::overrides::{Override, OverrideID};
 use crate::package::{Package, PackageID};
 use crate::{PackageName, ProviderMeta};
 
@@ -15,6 +16,7 @@ pub struct Chastefile<P> {
     packages: HashMap<PackageID, Package>,
     installations: Vec<Installation>,
     dependencies: Vec<Dependency>,
+    overrides: Vec<Override>,
     root_package_id: PackageID,
     workspace_members: Vec<PackageID>,
     provider_meta: P,
@@ -51,6 +53,10 @@ impl<'a, P: ProviderMeta> Chastefile<P> {
             .filter(move |d| d.kind.is_prod() && d.from == package_id)
     }
 
+    pub(crate) fn all_dependencies(&'a self) -> &'a [Dependency] {
+        &self.dependencies
+    }
+
     /// Direct dependencies of any kind from specified package
     pub fn package_dependencies(&'a self, package_id: PackageID) -> Vec<&'a Dependency> {
         self.package_dependencies_iter(package_id).collect()
@@ -117,6 +123,23 @@ impl<'a, P: ProviderMeta> Chastefile<P> {
         self.package_dependents_iter(package_id).collect()
     }
 
+    /// Direct dependencies of the package as declared in its package.json: the field they are in,
+    /// like "devDependencies", and the name they are under, which is the alias if there is one.
+    pub fn declared_dependencies(
+        &'a self,
+        package_id: PackageID,
+    ) -> HashSet<(&'static str, &'a str)> {
+        self.package_dependencies_iter(package_id)
+            .filter_map(|d| {
+                let name = match d.alias_name() {
+                    Some(alias) => alias.inner,
+                    None => self.package(d.on).name()?.as_ref(),
+                };
+                Some((d.kind.package_json_field(), name))
+            })
+            .collect()
+    }
+
     pub fn root_package_id(&'a self) -> PackageID {
         self.root_package_id
     }
@@ -144,6 +167,26 @@ impl<'a, P: ProviderMeta> Chastefile<P> {
             .collect()
     }
 
+    /// The workspace member whose directory contains `path`, relative to the root
+    /// (e.g. "packages/foo/src"), or the root package if it is not inside any member.
+    pub fn workspace_member_containing(&'a self, path: &str) -> PackageID {
+        let path = path.trim_matches('/');
+        self.workspace_members
+            .iter()
+            .flat_map(|pid| {
+                self.package_installations(*pid)
+                    .into_iter()
+                    .map(move |i| (*pid, i.path().as_ref()))
+            })
+            .filter(|(_, member_path)| {
+                path.strip_prefix(member_path)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            .max_by_key(|(_, member_path)| member_path.len())
+            .map(|(pid, _)| pid)
+            .unwrap_or(self.root_package_id)
+    }
+
     pub fn package_installations(&'a self, package_id: PackageID) -> Vec<&'a Installation> {
         self.installations
             .iter()
@@ -151,6 +194,32 @@ impl<'a, P: ProviderMeta> Chastefile<P> {
             .collect()
     }
 
+    pub fn get_override(&'a self, override_id: OverrideID) -> &'a Override {
+        &self.overrides[override_id.0]
+    }
+
+    /// Overrides of dependency resolutions, declared in the lockfile or the root package.json.
+    pub fn overrides(&'a self) -> Vec<&'a Override> {
+        self.overrides.iter().collect()
+    }
+
+    pub fn overrides_with_ids(&'a self) -> Vec<(OverrideID, &'a Override)> {
+        self.overrides
+            .iter()
+            .enumerate()
+            .map(|(i, o)| (OverrideID(i), o))
+            .collect()
+    }
+
+    /// Dependencies whose resolution was changed by the override.
+    /// If there are none, the override is not in effect.
+    pub fn overridden_dependencies(&'a self, override_id: OverrideID) -> Vec<&'a Dependency> {
+        self.dependencies
+            .iter()
+            .filter(|d| d.overridden_by == Some(override_id))
+            .collect()
+    }
+
     /// Provider-specific metadata
     pub fn meta(&'a self) -> &'a P {
         &self.provider_meta
@@ -164,6 +233,7 @@ impl<'a, P: ProviderMeta> Chastefile<P> {
             packages: self.packages,
             installations: self.installations,
             dependencies: self.dependencies,
+            overrides: self.overrides,
             root_package_id: self.root_package_id,
             workspace_members: self.workspace_members,
             provider_meta: func(self.provider_meta),
@@ -171,12 +241,126 @@ impl<'a, P: ProviderMeta> Chastefile<P> {
     }
 }
 
+impl<P: ProviderMeta + Clone> Chastefile<P> {
+    /// Packages reachable from `from`, added to `kept`, without going into `excluded` ones.
+    /// Packages that a kept one is derived from are kept too.
+    fn mark_reachable<I>(
+        &self,
+        from: I,
+        prod_only: bool,
+        excluded: &HashSet<PackageID>,
+        kept: &mut HashSet<PackageID>,
+    ) where
+        I: IntoIterator<Item = PackageID>,
+    {
+        let mut q: VecDeque<PackageID> = from.into_iter().collect();
+        kept.extend(q.iter().copied());
+        while let Some(pid) = q.pop_front() {
+            let derived_from = self.package(pid).derived_from();
+            let dependencies = self
+                .package_dependencies_iter(pid)
+                .filter(|d| !prod_only || d.kind.is_prod())
+                .map(|d| d.on);
+            for on in dependencies.chain(derived_from) {
+                if !excluded.contains(&on) && kept.insert(on) {
+                    q.push_back(on);
+                }
+            }
+        }
+    }
+
+    fn with_packages(&self, kept: &HashSet<PackageID>, prod_only: bool) -> Chastefile<P> {
+        // Installations inside the directory of a package that is dropped go away with it.
+        let dropped_paths: HashSet<&str> = self
+            .installations
+            .iter()
+            .filter(|i| !kept.contains(&i.package_id()))
+            .map(|i| i.path().as_ref())
+            .collect();
+        let is_in_dropped = |path: &str| {
+            path.match_indices("/node_modules/")
+                .any(|(i, _)| dropped_paths.contains(&path[..i]))
+        };
+        Chastefile {
+            packages: self
+                .packages
+                .iter()
+                .filter(|(pid, _)| kept.contains(pid))
+                .map(|(pid, package)| (*pid, package.clone()))
+                .collect(),
+            installations: self
+                .installations
+                .iter()
+                .filter(|i| kept.contains(&i.package_id()) && !is_in_dropped(i.path().as_ref()))
+                .cloned()
+                .collect(),
+            dependencies: self
+                .dependencies
+                .iter()
+                .filter(|d| kept.contains(&d.from) && kept.contains(&d.on))
+                .filter(|d| !prod_only || d.kind.is_prod())
+                .cloned()
+                .collect(),
+            overrides: self.overrides.clone(),
+            root_package_id: self.root_package_id,
+            workspace_members: self
+                .workspace_members
+                .iter()
+                .filter(|pid| kept.contains(pid))
+                .copied()
+                .collect(),
+            provider_meta: self.provider_meta.clone(),
+        }
+    }
+
+    /// A Chastefile of only the root package, `roots`, and the packages they depend on,
+    /// directly or transitively. With `prod_only`, dependencies of kind
+    /// [`crate::DependencyKind::DevDependency`] are neither followed nor kept.
+    ///
+    /// Package IDs stay the same, and so do override IDs.
+    /// Installations of dropped packages are dropped, and so are ones inside their directories.
+    pub fn subgraph(&self, roots: &[PackageID], prod_only: bool) -> Chastefile<P> {
+        let mut kept = HashSet::from([self.root_package_id]);
+        self.mark_reachable(roots.iter().copied(), prod_only, &HashSet::new(), &mut kept);
+        self.with_packages(&kept, prod_only)
+    }
+
+    /// A Chastefile with what is needed to install only the workspace members `members`,
+    /// e.g. for building one app of a monorepo.
+    ///
+    /// Like [`Chastefile::subgraph`] of `members`, but the root package keeps its dependencies
+    /// too, except for the workspace members that `members` do not depend on.
+    pub fn prune(&self, members: &[PackageID], prod_only: bool) -> Chastefile<P> {
+        let mut kept = HashSet::new();
+        self.mark_reachable(
+            members.iter().copied(),
+            prod_only,
+            &HashSet::new(),
+            &mut kept,
+        );
+        let unneeded_members: HashSet<PackageID> = self
+            .workspace_members
+            .iter()
+            .filter(|pid| !kept.contains(pid))
+            .copied()
+            .collect();
+        self.mark_reachable(
+            [self.root_package_id],
+            prod_only,
+            &unneeded_members,
+            &mut kept,
+        );
+        self.with_packages(&kept, prod_only)
+    }
+}
+
 #[derive(Debug)]
 pub struct ChastefileBuilder<P> {
     packages: HashMap<PackageID, Package>,
     package_by_name: HashMap<Rc<PackageName>, Vec<PackageID>>,
     dependencies: Vec<Dependency>,
     installations: Vec<Installation>,
+    overrides: Vec<Override>,
     next_pid: u64,
     root_package_id: Option<PackageID>,
     workspace_members: Vec<PackageID>,
@@ -191,6 +375,7 @@ impl<P> ChastefileBuilder<P> {
             package_by_name: HashMap::new(),
             dependencies: Vec::new(),
             installations: Vec::new(),
+            overrides: Vec::new(),
             next_pid: 0,
             root_package_id: None,
             workspace_members: Vec::new(),
@@ -211,7 +396,7 @@ impl<P> ChastefileBuilder<P> {
             .and_then(|n| self.package_by_name.get(n))
         {
             if let Some((original_pid, _)) = list
-                .into_iter()
+                .iter()
                 .map(|pid| (*pid, self.packages.get(pid).unwrap()))
                 .find(|(_, p)| p.is_duplicate_of(&package))
             {
@@ -242,6 +427,81 @@ impl<P> ChastefileBuilder<P> {
         self.dependencies.extend(dependencies);
     }
 
+    pub fn add_override(&mut self, override_: Override) -> OverrideID {
+        self.overrides.push(override_);
+        OverrideID(self.overrides.len() - 1)
+    }
+
+    /// Find which override changed the resolution of each dependency that doesn't know yet,
+    /// going by the selectors. For implementations whose lockfiles don't say it themselves.
+    ///
+    /// More specific overrides, with a parent or a specifier, are preferred.
+    pub fn match_overrides(&mut self) {
+        if self.overrides.is_empty() {
+            return;
+        }
+        let mut order: Vec<usize> = (0..self.overrides.len()).collect();
+        order.sort_by_key(|&i| {
+            let o = &self.overrides[i];
+            (o.parent().is_none(), o.selector().svs().is_none())
+        });
+        let mut dependents: HashMap<PackageID, Vec<&Dependency>> = HashMap::new();
+        for dependency in &self.dependencies {
+            dependents
+                .entry(dependency.on)
+                .or_default()
+                .push(dependency);
+        }
+        let selects_parent = |parent: &crate::OverrideSelector, pid: PackageID| {
+            let incoming = dependents.get(&pid).into_iter().flatten();
+            parent.selects_package(
+                self.packages.get(&pid).unwrap(),
+                incoming.filter_map(|d| d.svs()),
+            )
+        };
+
+        let mut matched = Vec::new();
+        for (i, dependency) in self.dependencies.iter().enumerate() {
+            if dependency.overridden_by.is_some() {
+                continue;
+            }
+            let Some(package) = self.packages.get(&dependency.on) else {
+                continue;
+            };
+            let found = order.iter().find(|&&o| {
+                let override_ = &self.overrides[o];
+                if !override_.selects_dependency(dependency, package) {
+                    return false;
+                }
+                let Some(parent) = override_.parent() else {
+                    return true;
+                };
+                if override_.is_parent_direct() {
+                    return selects_parent(parent, dependency.from);
+                }
+                let mut seen = HashSet::from([dependency.from]);
+                let mut queue = VecDeque::from([dependency.from]);
+                while let Some(pid) = queue.pop_front() {
+                    if selects_parent(parent, pid) {
+                        return true;
+                    }
+                    for d in dependents.get(&pid).into_iter().flatten() {
+                        if seen.insert(d.from) {
+                            queue.push_back(d.from);
+                        }
+                    }
+                }
+                false
+            });
+            if let Some(&o) = found {
+                matched.push((i, OverrideID(o)));
+            }
+        }
+        for (i, override_id) in matched {
+            self.dependencies[i].overridden_by = Some(override_id);
+        }
+    }
+
     pub fn set_root_package_id(&mut self, root_pid: PackageID) -> Result<()> {
         self.root_package_id = Some(root_pid);
         Ok(())
@@ -257,9 +517,154 @@ impl<P> ChastefileBuilder<P> {
             packages: self.packages,
             dependencies: self.dependencies,
             installations: self.installations,
+            overrides: self.overrides,
             root_package_id: self.root_package_id.ok_or(Error::MissingRootPackageID)?,
             workspace_members: self.workspace_members,
             provider_meta: self.provider_meta,
         })
     }
 }
+
+/// Chastefile for tests, with a package for each of `names`, the first one being the root package,
+/// and a dependency for each of `edges`. The packages in `members` are workspace members.
+#[cfg(test)]
+pub(crate) fn graph<'n>(
+    names: &[&'n str],
+    members: &[&str],
+    edges: &[(&str, crate::DependencyKind, &str)],
+) -> (Chastefile<()>, HashMap<&'n str, PackageID>) {
+    let (chastefile, pids) = graph_builder(names, members, edges, |_, _| {});
+    (chastefile.build().unwrap(), pids)
+}
+
+/// Like [`graph`], with `package` called to set more on each package before it is added,
+/// and the chastefile not built yet, so that more can be added to it.
+#[cfg(test)]
+pub(crate) fn graph_builder<'n, F>(
+    names: &[&'n str],
+    members: &[&str],
+    edges: &[(&str, crate::DependencyKind, &str)],
+    mut package: F,
+) -> (ChastefileBuilder<()>, HashMap<&'n str, PackageID>)
+where
+    F: FnMut(&str, &mut crate::PackageBuilder),
+{
+    let mut chastefile = ChastefileBuilder::new(());
+    let mut pids = HashMap::new();
+    for &name in names {
+        let mut builder =
+            crate::PackageBuilder::new(Some(PackageName::new(name.to_string()).unwrap()), None);
+        package(name, &mut builder);
+        let pid = chastefile.add_package(builder.build().unwrap()).unwrap();
+        pids.insert(name, pid);
+    }
+    chastefile.set_root_package_id(pids[names[0]]).unwrap();
+    for member in members {
+        chastefile.set_as_workspace_member(pids[member]).unwrap();
+    }
+    for &(from, kind, on) in edges {
+        chastefile
+            .add_dependency(crate::DependencyBuilder::new(kind, pids[from], pids[on]).build());
+    }
+    (chastefile, pids)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::graph_builder;
+    use crate::error::Result;
+    use crate::{DependencyKind, InstallationBuilder, ModulePath, PackageID};
+
+    #[test]
+    fn prune() -> Result<()> {
+        let (mut chastefile, pids) = graph_builder(
+            &[
+                "root", "app", "web", "lodash", "vitest", "eslint", "react", "tslib",
+            ],
+            &["app", "web"],
+            &[
+                ("root", DependencyKind::DevDependency, "eslint"),
+                ("root", DependencyKind::Dependency, "web"),
+                ("app", DependencyKind::Dependency, "lodash"),
+                ("app", DependencyKind::DevDependency, "vitest"),
+                ("web", DependencyKind::Dependency, "react"),
+                ("lodash", DependencyKind::Dependency, "tslib"),
+                ("react", DependencyKind::Dependency, "tslib"),
+            ],
+            |_, _| {},
+        );
+        for (name, paths) in [
+            ("root", &[""][..]),
+            ("app", &["apps/app", "node_modules/app"]),
+            ("web", &["apps/web", "node_modules/web"]),
+            ("lodash", &["node_modules/lodash"]),
+            ("vitest", &["node_modules/vitest"]),
+            ("eslint", &["node_modules/eslint"]),
+            ("react", &["node_modules/react"]),
+            (
+                "tslib",
+                &[
+                    "node_modules/tslib",
+                    "node_modules/react/node_modules/tslib",
+                ],
+            ),
+        ] {
+            for path in paths {
+                let path = ModulePath::new(path.to_string())?;
+                chastefile
+                    .add_package_installation(InstallationBuilder::new(pids[name], path).build()?);
+            }
+        }
+        let chastefile = chastefile.build()?;
+        let names = |pruned: &super::Chastefile<()>| {
+            let mut names: Vec<String> = pruned
+                .packages()
+                .into_iter()
+                .map(|p| p.name().unwrap().to_string())
+                .collect();
+            names.sort_unstable();
+            names
+        };
+        let paths = |pruned: &super::Chastefile<()>, pid: PackageID| {
+            let mut paths: Vec<String> = pruned
+                .package_installations(pid)
+                .into_iter()
+                .map(|i| i.path().as_ref().to_string())
+                .collect();
+            paths.sort_unstable();
+            paths
+        };
+
+        let pruned = chastefile.prune(&[pids["app"]], false);
+        assert_eq!(
+            names(&pruned),
+            ["app", "eslint", "lodash", "root", "tslib", "vitest"]
+        );
+        assert_eq!(pruned.workspace_member_ids(), [pids["app"]]);
+        assert_eq!(pruned.root_package_dependencies().len(), 1);
+        assert_eq!(paths(&pruned, pids["tslib"]), ["node_modules/tslib"]);
+
+        let pruned = chastefile.prune(&[pids["app"]], true);
+        assert_eq!(names(&pruned), ["app", "lodash", "root", "tslib"]);
+        assert_eq!(pruned.package_dependencies(pids["app"]).len(), 1);
+        assert_eq!(
+            pruned.declared_dependencies(pids["app"]),
+            HashSet::from([("dependencies", "lodash")])
+        );
+
+        let pruned = chastefile.subgraph(&[pids["web"]], false);
+        assert_eq!(names(&pruned), ["react", "root", "tslib", "web"]);
+        assert_eq!(pruned.root_package_dependencies().len(), 1);
+        assert_eq!(
+            paths(&pruned, pids["tslib"]),
+            [
+                "node_modules/react/node_modules/tslib",
+                "node_modules/tslib"
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use crate::chastefile::Chastefile;
+use crate::dependency::Dependency;
+use crate::package::PackageID;
+use crate::ProviderMeta;
+
+impl<P: ProviderMeta> Chastefile<P> {
+    /// Strongly connected components, with Tarjan's algorithm, of the packages `include`d
+    /// and the dependencies between them that are `follow`ed.
+    pub(crate) fn components<N, E>(&self, include: N, follow: E) -> Vec<Vec<PackageID>>
+    where
+        N: Fn(PackageID) -> bool,
+        E: Fn(&Dependency) -> bool,
+    {
+        let mut nodes: Vec<PackageID> = self
+            .packages_with_ids()
+            .into_iter()
+            .map(|(pid, _)| pid)
+            .filter(|pid| include(*pid))
+            .collect();
+        nodes.sort_unstable_by_key(|pid| pid.0);
+        let mut edges: HashMap<PackageID, Vec<PackageID>> = HashMap::new();
+        for dependency in self.all_dependencies() {
+            if follow(dependency) && include(dependency.from) && include(dependency.on) {
+                edges
+                    .entry(dependency.from)
+                    .or_default()
+                    .push(dependency.on);
+            }
+        }
+        let successors = |pid: PackageID| edges.get(&pid).map(Vec::as_slice).unwrap_or_default();
+
+        let mut next_index = 0;
+        let mut index: HashMap<PackageID, usize> = HashMap::with_capacity(nodes.len());
+        let mut lowlink: HashMap<PackageID, usize> = HashMap::with_capacity(nodes.len());
+        let mut stack: Vec<PackageID> = Vec::new();
+        let mut on_stack: HashSet<PackageID> = HashSet::new();
+        let mut components = Vec::new();
+        for &start in &nodes {
+            if index.contains_key(&start) {
+                continue;
+            }
+            // Packages being visited, with how many of their successors were.
+            let mut frames: Vec<(PackageID, usize)> = vec![(start, 0)];
+            index.insert(start, next_index);
+            lowlink.insert(start, next_index);
+            next_index += 1;
+            stack.push(start);
+            on_stack.insert(start);
+            while let Some((pid, visited)) = frames.last_mut() {
+                let pid = *pid;
+                if let Some(&on) = successors(pid).get(*visited) {
+                    *visited += 1;
+                    if let Entry::Vacant(entry) = index.entry(on) {
+                        entry.insert(next_index);
+                        lowlink.insert(on, next_index);
+                        next_index += 1;
+                        stack.push(on);
+                        on_stack.insert(on);
+                        frames.push((on, 0));
+                    } else if on_stack.contains(&on) {
+                        let low = lowlink[&pid].min(index[&on]);
+                        lowlink.insert(pid, low);
+                    }
+                    continue;
+                }
+                frames.pop();
+                if let Some(&(parent, _)) = frames.last() {
+                    let low = lowlink[&parent].min(lowlink[&pid]);
+                    lowlink.insert(parent, low);
+                }
+                if lowlink[&pid] == index[&pid] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack.remove(&member);
+                        component.push(member);
+                        if member == pid {
+                            break;
+                        }
+                    }
+                    component.sort_unstable_by_key(|pid| pid.0);
+                    components.push(component);
+                }
+            }
+        }
+        components.sort_unstable_by_key(|c| c[0].0);
+        components.retain(|c| c.len() > 1 || successors(c[0]).contains(&c[0]));
+        components
+    }
+
+    /// Dependency cycles: groups of packages that all depend on each other, directly or
+    /// transitively, i.e. strongly connected components of more than one package,
+    /// or of one that depends on itself.
+    ///
+    /// With `prod_only`, dependencies of kind [`crate::DependencyKind::DevDependency`]
+    /// are not followed.
+    pub fn cycles(&self, prod_only: bool) -> Vec<Vec<PackageID>> {
+        self.components(|_| true, |d| !prod_only || d.kind.is_prod())
+    }
+
+    /// Like [`Chastefile::cycles`], but only of the root package and workspace members,
+    /// through dependencies on each other.
+    pub fn workspace_cycles(&self, prod_only: bool) -> Vec<Vec<PackageID>> {
+        let importers = self.workspace_member_ids();
+        self.components(
+            |pid| pid == self.root_package_id() || importers.contains(&pid),
+            |d| !prod_only || d.kind.is_prod(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chastefile::graph;
+    use crate::DependencyKind;
+
+    #[test]
+    fn cycles() {
+        let (chastefile, _) = graph(
+            &["root", "app", "lib", "a", "b", "c", "d", "self"],
+            &["app", "lib"],
+            &[
+                ("root", DependencyKind::Dependency, "app"),
+                ("app", DependencyKind::Dependency, "lib"),
+                ("lib", DependencyKind::DevDependency, "app"),
+                ("app", DependencyKind::Dependency, "a"),
+                ("a", DependencyKind::Dependency, "b"),
+                ("b", DependencyKind::Dependency, "c"),
+                ("c", DependencyKind::Dependency, "a"),
+                ("c", DependencyKind::Dependency, "d"),
+                ("self", DependencyKind::PeerDependency, "self"),
+            ],
+        );
+        let names = |cycles: Vec<Vec<crate::PackageID>>| {
+            let mut names: Vec<Vec<String>> = cycles
+                .into_iter()
+                .map(|cycle| {
+                    let mut names: Vec<String> = cycle
+                        .into_iter()
+                        .map(|pid| chastefile.package(pid).name().unwrap().to_string())
+                        .collect();
+                    names.sort_unstable();
+                    names
+                })
+                .collect();
+            names.sort_unstable();
+            names
+        };
+
+        assert_eq!(
+            names(chastefile.cycles(false)),
+            [vec!["a", "b", "c"], vec!["app", "lib"], vec!["self"]]
+        );
+        assert_eq!(
+            names(chastefile.cycles(true)),
+            [vec!["a", "b", "c"], vec!["self"]]
+        );
+        assert_eq!(
+            names(chastefile.workspace_cycles(false)),
+            [vec!["app", "lib"]]
+        );
+        assert!(chastefile.workspace_cycles(true).is_empty());
+    }
+}
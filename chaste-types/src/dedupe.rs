@@ -0,0 +1,339 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::chastefile::Chastefile;
+use crate::dependency::Dependency;
+use crate::installation::Installation;
+use crate::name::PackageName;
+use crate::package::{Package, PackageID};
+use crate::satisfaction::npm_range;
+use crate::source::PackageSourceType;
+use crate::svs::VersionRange;
+use crate::ProviderMeta;
+
+/// Above this many versions of a package, the plan is made greedily instead of searching
+/// for the smallest set, which takes time exponential in the number of versions.
+const EXHAUSTIVE_MAX_VERSIONS: usize = 12;
+
+/// One of the packages of a [`Duplicate`].
+#[derive(Debug, Clone)]
+pub struct DuplicateVersion<'a> {
+    pub package_id: PackageID,
+    pub package: &'a Package,
+    pub installations: Vec<&'a Installation>,
+    /// Dependencies on the package, with the specifiers they ask for.
+    pub dependents: Vec<&'a Dependency>,
+}
+
+/// A package name with more than one package locked, e.g. of different versions,
+/// or with a package installed at several paths.
+#[derive(Debug, Clone)]
+pub struct Duplicate<'a> {
+    pub name: &'a PackageName,
+    /// Sorted by version, the ones without a version first.
+    pub versions: Vec<DuplicateVersion<'a>>,
+}
+
+/// A dependency to be resolved to another package of the same name.
+#[derive(Debug, Clone)]
+pub struct DedupeMove<'a> {
+    pub dependency: &'a Dependency,
+    pub to: PackageID,
+}
+
+/// What to change to have as few packages of a [`Duplicate`] as possible,
+/// with every dependency still resolved to a version in the range it asks for.
+#[derive(Debug, Clone)]
+pub struct DedupePlan<'a> {
+    /// Packages still depended on after the changes.
+    pub keep: Vec<PackageID>,
+    /// Packages no longer depended on after the changes.
+    pub remove: Vec<PackageID>,
+    pub moves: Vec<DedupeMove<'a>>,
+}
+
+impl<P: ProviderMeta> Chastefile<P> {
+    /// Package names with more than one package locked, and packages installed at more than
+    /// one path, sorted by name. The root package and workspace members are left out.
+    pub fn duplicates(&self) -> Vec<Duplicate<'_>> {
+        let importers = self.workspace_member_ids();
+        let mut by_name: BTreeMap<&PackageName, Vec<(PackageID, &Package)>> = BTreeMap::new();
+        for (pid, package) in self.packages_with_ids() {
+            if pid == self.root_package_id() || importers.contains(&pid) {
+                continue;
+            }
+            if let Some(name) = package.name() {
+                by_name.entry(name).or_default().push((pid, package));
+            }
+        }
+        by_name
+            .into_iter()
+            .filter_map(|(name, packages)| {
+                let mut versions: Vec<DuplicateVersion> = packages
+                    .into_iter()
+                    .map(|(package_id, package)| DuplicateVersion {
+                        package_id,
+                        package,
+                        installations: self.package_installations(package_id),
+                        dependents: self.package_dependents(package_id),
+                    })
+                    .collect();
+                if versions.len() < 2 && versions.iter().all(|v| v.installations.len() < 2) {
+                    return None;
+                }
+                versions.sort_by(|a, b| a.package.version().cmp(&b.package.version()));
+                Some(Duplicate { name, versions })
+            })
+            .collect()
+    }
+}
+
+/// Whether dependents of the package could be moved to another, or others to it.
+fn is_candidate(package: &Package) -> bool {
+    package.version().is_some()
+        && !package.is_derived()
+        && package
+            .source_type()
+            .is_none_or(|s| s == PackageSourceType::Npm)
+}
+
+impl<'a> Duplicate<'a> {
+    /// Plan the smallest set of versions that satisfies the range of every dependency,
+    /// preferring higher versions, and move every dependency to the highest version it allows.
+    ///
+    /// Dependencies stay with the package they are on if it is not from an npm registry,
+    /// if it is patched, if they don't ask for an npm range, or if they were overridden.
+    pub fn plan(&self) -> DedupePlan<'a> {
+        let mut pinned: HashSet<PackageID> = HashSet::new();
+        // Dependencies to be resolved, with their ranges.
+        let mut flexible: Vec<(&'a Dependency, VersionRange)> = Vec::new();
+        for version in &self.versions {
+            if !is_candidate(version.package) {
+                pinned.insert(version.package_id);
+                continue;
+            }
+            for &dependency in &version.dependents {
+                let range = dependency
+                    .svs()
+                    .filter(|_| dependency.overridden_by().is_none())
+                    .and_then(npm_range)
+                    .and_then(|r| VersionRange::parse(r.trim()).ok());
+                match range {
+                    Some(range) if range.satisfies(version.package.version().unwrap()) => {
+                        flexible.push((dependency, range))
+                    }
+                    _ => {
+                        pinned.insert(version.package_id);
+                    }
+                }
+            }
+        }
+
+        // Highest versions first, so that they are preferred.
+        let candidates: Vec<&DuplicateVersion> = self
+            .versions
+            .iter()
+            .rev()
+            .filter(|v| is_candidate(v.package) && !pinned.contains(&v.package_id))
+            .collect();
+        let satisfies = |v: &DuplicateVersion, range: &VersionRange| {
+            v.package.version().is_some_and(|ver| range.satisfies(ver))
+        };
+        let pinned_versions: Vec<&DuplicateVersion> = self
+            .versions
+            .iter()
+            .filter(|v| pinned.contains(&v.package_id) && is_candidate(v.package))
+            .collect();
+        let uncovered: Vec<&VersionRange> = flexible
+            .iter()
+            .map(|(_, range)| range)
+            .filter(|range| !pinned_versions.iter().any(|v| satisfies(v, range)))
+            .collect();
+        let covers = |chosen: &[&DuplicateVersion]| {
+            uncovered
+                .iter()
+                .all(|range| chosen.iter().any(|v| satisfies(v, range)))
+        };
+
+        let chosen: Vec<&DuplicateVersion> = if candidates.len() <= EXHAUSTIVE_MAX_VERSIONS {
+            // Subsets by size, and within a size, those of higher versions first.
+            let mut subsets: Vec<u32> = (0..1u32 << candidates.len()).collect();
+            subsets.sort_by_key(|s| (s.count_ones(), Reverse(s.reverse_bits())));
+            subsets
+                .into_iter()
+                .map(|s| {
+                    candidates
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| s & (1 << i) != 0)
+                        .map(|(_, v)| *v)
+                        .collect::<Vec<_>>()
+                })
+                .find(|chosen| covers(chosen))
+                .unwrap_or_else(|| candidates.clone())
+        } else {
+            let mut chosen = Vec::new();
+            let mut left = uncovered.clone();
+            while !left.is_empty() {
+                let Some(best) = candidates
+                    .iter()
+                    .max_by_key(|v| {
+                        let covered = left.iter().filter(|r| satisfies(v, r)).count();
+                        // Ties go to the higher version.
+                        (covered, v.package.version())
+                    })
+                    .filter(|v| left.iter().any(|r| satisfies(v, r)))
+                else {
+                    break;
+                };
+                left.retain(|r| !satisfies(best, r));
+                chosen.push(*best);
+            }
+            chosen
+        };
+
+        let mut kept: Vec<&DuplicateVersion> = chosen;
+        kept.extend(pinned_versions);
+        kept.sort_by(|a, b| b.package.version().cmp(&a.package.version()));
+        let mut moves = Vec::new();
+        let mut depended: HashSet<PackageID> = pinned.clone();
+        for (dependency, range) in flexible {
+            let to = kept
+                .iter()
+                .find(|v| satisfies(v, &range))
+                .map(|v| v.package_id)
+                .unwrap_or(dependency.on);
+            depended.insert(to);
+            if to != dependency.on {
+                moves.push(DedupeMove { dependency, to });
+            }
+        }
+        let (keep, remove) = self
+            .versions
+            .iter()
+            .map(|v| v.package_id)
+            .partition(|pid| depended.contains(pid));
+        DedupePlan {
+            keep,
+            remove,
+            moves,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chastefile::graph_builder;
+    use crate::error::Result;
+    use crate::{
+        DependencyBuilder, DependencyKind, InstallationBuilder, ModulePath, PackageName,
+        PackageSource, SourceVersionSpecifier,
+    };
+
+    #[test]
+    fn duplicates_and_plan() -> Result<()> {
+        let (mut chastefile, pids) = graph_builder(
+            &[
+                "root", "a", "b", "c", "ms-2.0.0", "ms-2.1.2", "ms", "ms-3.0.0", "tslib",
+            ],
+            &[],
+            &[
+                ("root", DependencyKind::Dependency, "a"),
+                ("root", DependencyKind::Dependency, "b"),
+                ("root", DependencyKind::Dependency, "c"),
+            ],
+            |name, package| {
+                let version = match name {
+                    "root" => {
+                        package.name(None);
+                        return;
+                    }
+                    "ms" => "2.1.3",
+                    "tslib" => "2.6.0",
+                    _ => match name.strip_prefix("ms-") {
+                        Some(version) => {
+                            package.name(Some(PackageName::new("ms".to_string()).unwrap()));
+                            version
+                        }
+                        None => "1.0.0",
+                    },
+                };
+                package.version(Some(version.to_string()));
+                package.source(PackageSource::Npm);
+            },
+        );
+        for (name, paths) in [
+            ("a", &["node_modules/a"][..]),
+            ("b", &["node_modules/b"]),
+            ("c", &["node_modules/c"]),
+            ("ms-2.0.0", &["node_modules/a/node_modules/ms"]),
+            ("ms-2.1.2", &["node_modules/b/node_modules/ms"]),
+            ("ms", &["node_modules/ms"]),
+            ("ms-3.0.0", &["node_modules/c/node_modules/ms"]),
+            (
+                "tslib",
+                &[
+                    "node_modules/a/node_modules/tslib",
+                    "node_modules/b/node_modules/tslib",
+                ],
+            ),
+        ] {
+            for path in paths {
+                let path = ModulePath::new(path.to_string())?;
+                chastefile
+                    .add_package_installation(InstallationBuilder::new(pids[name], path).build()?);
+            }
+        }
+        for (from, svs, on) in [
+            ("a", "^2.0.0", "ms-2.0.0"),
+            ("b", "~2.1.0", "ms-2.1.2"),
+            ("c", "^3.0.0", "ms-3.0.0"),
+            ("c", "^2.1.0", "ms"),
+            ("a", "^2.0.0", "tslib"),
+            ("b", "^2.0.0", "tslib"),
+        ] {
+            let mut dependency =
+                DependencyBuilder::new(DependencyKind::Dependency, pids[from], pids[on]);
+            dependency.svs(SourceVersionSpecifier::new(svs.to_string())?);
+            chastefile.add_dependency(dependency.build());
+        }
+        let chastefile = chastefile.build()?;
+
+        let duplicates = chastefile.duplicates();
+        let names: Vec<&str> = duplicates.iter().map(|d| d.name.as_ref()).collect();
+        assert_eq!(names, ["ms", "tslib"]);
+        let versions: Vec<String> = duplicates[0]
+            .versions
+            .iter()
+            .map(|v| v.package.version().unwrap().to_string())
+            .collect();
+        assert_eq!(versions, ["2.0.0", "2.1.2", "2.1.3", "3.0.0"]);
+
+        let plan = duplicates[0].plan();
+        let mut keep = plan.keep.clone();
+        keep.sort_unstable_by_key(|pid| pid.0);
+        let mut expected = vec![pids["ms"], pids["ms-3.0.0"]];
+        expected.sort_unstable_by_key(|pid| pid.0);
+        assert_eq!(keep, expected);
+        assert_eq!(plan.remove.len(), 2);
+        let mut moves: Vec<_> = plan
+            .moves
+            .iter()
+            .map(|m| (m.dependency.from, m.to))
+            .collect();
+        moves.sort_unstable_by_key(|(from, _)| from.0);
+        let mut expected = vec![(pids["a"], pids["ms"]), (pids["b"], pids["ms"])];
+        expected.sort_unstable_by_key(|(from, _)| from.0);
+        assert_eq!(moves, expected);
+
+        // Installed twice, but nothing to change in the lockfile.
+        let plan = duplicates[1].plan();
+        assert_eq!(plan.keep, [pids["tslib"]]);
+        assert!(plan.remove.is_empty() && plan.moves.is_empty());
+
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
 
 use crate::name::{PackageName, PackageNameBorrowed};
+use crate::overrides::OverrideID;
 use crate::package::PackageID;
 use crate::svs::SourceVersionSpecifier;
 
@@ -46,6 +47,17 @@ impl DependencyKind {
             DependencyKind::PeerDependency | DependencyKind::OptionalPeerDependency
         )
     }
+    /// The field of package.json that dependencies of this kind are defined in.
+    pub fn package_json_field(self) -> &'static str {
+        match self {
+            DependencyKind::Dependency => "dependencies",
+            DependencyKind::DevDependency => "devDependencies",
+            DependencyKind::PeerDependency | DependencyKind::OptionalPeerDependency => {
+                "peerDependencies"
+            }
+            DependencyKind::OptionalDependency => "optionalDependencies",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -59,6 +71,7 @@ pub struct Dependency {
     pub on: PackageID,
     alias_name: Option<PackageName>,
     svs: Option<SourceVersionSpecifier>,
+    pub(crate) overridden_by: Option<OverrideID>,
 }
 
 impl Dependency {
@@ -124,6 +137,12 @@ impl Dependency {
     pub fn alias_name<'a>(&'a self) -> Option<PackageNameBorrowed<'a>> {
         self.alias_name.as_ref().map(|a| a.as_borrowed())
     }
+
+    /// The override that changed what this dependency resolves to, if any.
+    /// See [`crate::Chastefile::get_override`].
+    pub fn overridden_by(&self) -> Option<OverrideID> {
+        self.overridden_by
+    }
 }
 
 pub struct DependencyBuilder {
@@ -132,6 +151,7 @@ pub struct DependencyBuilder {
     on: PackageID,
     alias_name: Option<PackageName>,
     svs: Option<SourceVersionSpecifier>,
+    overridden_by: Option<OverrideID>,
 }
 
 impl DependencyBuilder {
@@ -142,6 +162,7 @@ impl DependencyBuilder {
             on,
             alias_name: None,
             svs: None,
+            overridden_by: None,
         }
     }
 
@@ -153,6 +174,10 @@ impl DependencyBuilder {
         self.svs = Some(svs);
     }
 
+    pub fn overridden_by(&mut self, override_id: OverrideID) {
+        self.overridden_by = Some(override_id);
+    }
+
     pub fn build(self) -> Dependency {
         Dependency {
             kind: self.kind,
@@ -160,6 +185,7 @@ impl DependencyBuilder {
             on: self.on,
             alias_name: self.alias_name,
             svs: self.svs,
+            overridden_by: self.overridden_by,
         }
     }
 }
@@ -0,0 +1,281 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::fmt;
+use std::path::PathBuf;
+
+/// Chain of keys in a lockfile leading to where something was found,
+/// e.g. `importers` → `.` → `dependencies` → `lodash`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyPath(Vec<String>);
+
+impl KeyPath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn keys(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Put keys of outer levels in front of the ones already known.
+    pub fn prepend<I, K>(&mut self, keys: I)
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<String>,
+    {
+        let mut keys: Vec<String> = keys.into_iter().map(Into::into).collect();
+        keys.append(&mut self.0);
+        self.0 = keys;
+    }
+}
+
+impl<K: Into<String>> FromIterator<K> for KeyPath {
+    fn from_iter<T: IntoIterator<Item = K>>(iter: T) -> Self {
+        KeyPath(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+impl fmt::Display for KeyPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, key) in self.0.iter().enumerate() {
+            if i != 0 {
+                f.write_str(" → ")?;
+            }
+            write!(f, "{key:?}")?;
+        }
+        Ok(())
+    }
+}
+
+/// An error, with the keys in the lockfile leading to where it was found.
+#[derive(Debug)]
+pub struct Located<E> {
+    pub key_path: KeyPath,
+    pub source: E,
+}
+
+impl<E: fmt::Display> fmt::Display for Located<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at {})", self.source, self.key_path)
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for Located<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Errors of an implementation that have a variant for being [`Located`].
+pub trait Locate: Sized + From<Located<Box<Self>>> {
+    /// The error with where it was found, if it is that variant.
+    fn located(&self) -> Option<&Located<Box<Self>>>;
+
+    /// Like [`Locate::located`], but taking the error.
+    fn into_located(self) -> Result<Located<Box<Self>>, Self>;
+
+    /// Record the keys in the lockfile leading to where the error was found,
+    /// in front of the ones that are already known.
+    fn at<I, K>(self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<String>,
+    {
+        match self.into_located() {
+            Ok(mut located) => {
+                located.key_path.prepend(keys);
+                located.into()
+            }
+            Err(e) => Located {
+                key_path: keys.into_iter().collect(),
+                source: Box::new(e),
+            }
+            .into(),
+        }
+    }
+
+    /// The error, without the keys leading to where it was found.
+    fn inner(&self) -> &Self {
+        match self.located() {
+            Some(located) => located.source.inner(),
+            None => self,
+        }
+    }
+
+    /// Keys in the lockfile leading to where the error was found, if known.
+    fn key_path(&self) -> Option<&KeyPath> {
+        self.located().map(|located| &located.key_path)
+    }
+}
+
+/// Line and column in a file, both starting at 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn from_offset(contents: &str, offset: usize) -> Position {
+        let before = &contents[..offset.min(contents.len())];
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        Position {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// Find where the keys appear in the lockfile, each one after the previous one.
+    ///
+    /// This works on the text, so it's the same for JSON, YAML and the yarn.lock formats.
+    /// A key counts only where it starts a line, or follows another key in a list
+    /// (as in `"a@^1", "a@^1.1":`), optionally quoted.
+    pub fn of_key_path(contents: &str, key_path: &KeyPath) -> Option<Position> {
+        let mut offset = 0;
+        let mut found = None;
+        for key in key_path.keys() {
+            let (idx, _) = contents[offset..]
+                .match_indices(key.as_str())
+                .find(|(i, _)| {
+                    let start = offset + i;
+                    let line_start = contents[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+                    let before = contents[line_start..start].trim_end_matches(['"', '\'']);
+                    let after = contents[start + key.len()..]
+                        .trim_start_matches(['"', '\''])
+                        .chars()
+                        .next();
+                    (before.trim().is_empty() || before.ends_with(", "))
+                        && matches!(after, Some(':' | ',' | ' ' | '\n' | '\r') | None)
+                })?;
+            offset += idx;
+            found = Some(offset);
+            offset += key.len();
+        }
+        found.map(|o| Position::from_offset(contents, o))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The lockfile could not be parsed.
+    Error,
+    /// The lockfile was parsed leniently, with something skipped.
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        })
+    }
+}
+
+/// A problem found in a lockfile, with where it was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub file: Option<PathBuf>,
+    pub position: Option<Position>,
+    pub key_path: Option<KeyPath>,
+}
+
+impl Diagnostic {
+    /// Format the diagnostic with the lines of the lockfile around it, like:
+    ///
+    /// ```text
+    /// error: Dependency "ms" not found
+    ///  --> package-lock.json:12:9
+    ///    |
+    /// 11 |         "node_modules/debug": {
+    /// 12 |             "dependencies": {
+    ///    |             ^ "packages" → "node_modules/debug" → "dependencies" → "ms"
+    /// ```
+    pub fn render(&self, contents: &str) -> String {
+        let mut out = format!("{}: {}\n", self.severity, self.message);
+        let file = self
+            .file
+            .as_ref()
+            .map(|f| f.display().to_string())
+            .unwrap_or_else(|| "<lockfile>".to_string());
+        let Some(Position { line, column }) = self.position else {
+            out.push_str(&format!(" --> {file}\n"));
+            if let Some(key_path) = &self.key_path {
+                out.push_str(&format!("  = at {key_path}\n"));
+            }
+            return out;
+        };
+        let lines: Vec<&str> = contents.lines().collect();
+        let width = line.to_string().len();
+        let pad = " ".repeat(width);
+        out.push_str(&format!("{pad}--> {file}:{line}:{column}\n{pad} |\n"));
+        for n in line.saturating_sub(1).max(1)..=line {
+            if let Some(text) = lines.get(n - 1) {
+                out.push_str(&format!("{n:>width$} | {text}\n"));
+            }
+        }
+        let marker_indent = " ".repeat(column.saturating_sub(1));
+        out.push_str(&format!("{pad} | {marker_indent}^"));
+        if let Some(key_path) = &self.key_path {
+            out.push_str(&format!(" {key_path}"));
+        }
+        out.push('\n');
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Diagnostic, KeyPath, Position, Severity};
+
+    static YARN_LOCK: &str = r#"# yarn lockfile v1
+
+"a@^1.0.0", "a@^1.1.0":
+  version "1.1.0"
+  dependencies:
+    b "^2.0.0"
+
+b@^2.0.0:
+  version "2.0.0"
+"#;
+
+    #[test]
+    fn key_path_position() {
+        let kp: KeyPath = ["a@^1.1.0", "dependencies", "b"].into_iter().collect();
+        assert_eq!(
+            Position::of_key_path(YARN_LOCK, &kp),
+            Some(Position { line: 6, column: 5 })
+        );
+        let kp: KeyPath = ["b@^2.0.0"].into_iter().collect();
+        assert_eq!(
+            Position::of_key_path(YARN_LOCK, &kp),
+            Some(Position { line: 8, column: 1 })
+        );
+        let kp: KeyPath = ["c"].into_iter().collect();
+        assert_eq!(Position::of_key_path(YARN_LOCK, &kp), None);
+    }
+
+    #[test]
+    fn render_snippet() {
+        let diagnostic = Diagnostic {
+            severity: Severity::Error,
+            message: "Dependency \"b\" not found".to_string(),
+            file: Some("yarn.lock".into()),
+            position: Some(Position { line: 6, column: 5 }),
+            key_path: Some(["a@^1.1.0", "dependencies", "b"].into_iter().collect()),
+        };
+        assert_eq!(
+            diagnostic.render(YARN_LOCK),
+            r#"error: Dependency "b" not found
+ --> yarn.lock:6:5
+  |
+5 |   dependencies:
+6 |     b "^2.0.0"
+  |     ^ "a@^1.1.0" → "dependencies" → "b"
+"#
+        );
+    }
+}
@@ -24,8 +24,68 @@ pub enum Error {
     #[error("Invalid module path: {0:?}")]
     InvalidModulePath(String),
 
+    #[error("Invalid override selector: {0:?}")]
+    InvalidOverrideSelector(String),
+
+    #[error("Invalid query {query:?}: unexpected {} at offset {offset}", unexpected(&.query[*.offset..]))]
+    InvalidQuery { query: String, offset: usize },
+
     #[error("Semver error: {0:?}")]
     SemverError(#[from] SemverError),
 }
 
+/// What is left of the input where parsing failed.
+fn unexpected(rest: &str) -> String {
+    match rest {
+        "" => "end".to_string(),
+        rest => format!("{rest:?}"),
+    }
+}
+
+impl Error {
+    /// What went wrong, as an [`ErrorKind`] shared with the implementations.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::MissingRootPackageID | Error::DuplicatePackage(_) => ErrorKind::Inconsistent,
+            Error::InvalidPackageName(_)
+            | Error::InvalidSVS(_)
+            | Error::InvalidModulePath(_)
+            | Error::InvalidOverrideSelector(_)
+            | Error::InvalidQuery { .. }
+            | Error::SemverError(_) => ErrorKind::MalformedSyntax,
+        }
+    }
+}
+
+/// What went wrong, in terms shared by all implementations,
+/// so that errors can be handled without matching each implementation's error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// No lockfile was found, or nothing that could be recognized as one.
+    NoLockfile,
+    /// Lockfiles of multiple implementations were found, and it's not known which one is in use.
+    AmbiguousLockfile,
+    /// The lockfile is of a version or format that is not supported.
+    UnsupportedVersion,
+    /// The operation is not supported for the lockfile, e.g. because its provider
+    /// does not implement it.
+    Unsupported,
+    /// A lockfile provider was asked for by a name that no provider has.
+    UnknownProvider,
+    /// The lockfile or a package.json could not be deserialized,
+    /// or a value in it, like a package name or version, is not valid.
+    MalformedSyntax,
+    /// Something refers to a package, entry or workspace member that is not there.
+    DanglingReference,
+    /// The lockfile contradicts itself, e.g. one specifier resolves to multiple packages.
+    Inconsistent,
+    /// A package.json of the root package or a workspace member is missing.
+    MissingWorkspaceFile,
+    /// A checksum or a patch hash is not valid.
+    InvalidChecksum,
+    /// Reading a file failed, for reasons that may go away on retry.
+    Io,
+}
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,131 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::{fs, io};
+
+/// Where the lockfile, and the package.json files needed by some implementations, are read from.
+pub trait FileSource {
+    fn read_to_string(&self, path: &Path) -> io::Result<String>;
+
+    /// All files in the directory and its subdirectories.
+    /// Used to discover workspace members by their package.json files.
+    fn walk_files(&self, dir: &Path) -> io::Result<Vec<PathBuf>>;
+}
+
+impl<S: FileSource + ?Sized> FileSource for &S {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        (**self).read_to_string(path)
+    }
+
+    fn walk_files(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        (**self).walk_files(dir)
+    }
+}
+
+/// Files read from the real file system.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RealFileSource;
+
+impl FileSource for RealFileSource {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        fs::read_to_string(path)
+    }
+
+    fn walk_files(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        let mut dirs = vec![dir.to_path_buf()];
+        while let Some(current) = dirs.pop() {
+            for entry in fs::read_dir(current)? {
+                let entry = entry?;
+                // Symlinks are not followed.
+                let file_type = entry.file_type()?;
+                if file_type.is_dir() {
+                    dirs.push(entry.path());
+                } else if file_type.is_file() {
+                    files.push(entry.path());
+                }
+            }
+        }
+        Ok(files)
+    }
+}
+
+/// Files kept in memory, e.g. pulled out of an artifact store.
+///
+/// # Example
+/// ```
+/// # use std::path::Path;
+/// # use chaste_types::{FileSource, MemoryFileSource};
+/// let mut files = MemoryFileSource::new();
+/// files.insert("package.json", "{}".to_string());
+/// files.insert("packages/a/package.json", "{}".to_string());
+/// assert_eq!(files.read_to_string(Path::new("./package.json")).unwrap(), "{}");
+/// assert_eq!(files.walk_files(Path::new("packages")).unwrap().len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFileSource {
+    files: BTreeMap<PathBuf, String>,
+}
+
+/// "./a/../b/./c" and "b/c" should be the same key.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push(component);
+                }
+            }
+            c => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+impl MemoryFileSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert<P>(&mut self, path: P, contents: String)
+    where
+        P: AsRef<Path>,
+    {
+        self.files.insert(normalize(path.as_ref()), contents);
+    }
+}
+
+impl<P: AsRef<Path>> FromIterator<(P, String)> for MemoryFileSource {
+    fn from_iter<T: IntoIterator<Item = (P, String)>>(iter: T) -> Self {
+        let mut source = MemoryFileSource::new();
+        for (path, contents) in iter {
+            source.insert(path, contents);
+        }
+        source
+    }
+}
+
+impl FileSource for MemoryFileSource {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        self.files
+            .get(&normalize(path))
+            .cloned()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    }
+
+    fn walk_files(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let normalized_dir = normalize(dir);
+        // Returned paths are prefixed with the directory as it was passed,
+        // so that callers can strip it back, same as with the real file system.
+        Ok(self
+            .files
+            .keys()
+            .filter_map(|p| p.strip_prefix(&normalized_dir).ok())
+            .map(|rel| dir.join(rel))
+            .collect())
+    }
+}
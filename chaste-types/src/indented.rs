@@ -0,0 +1,180 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+//! Editing of lockfiles written as indented lines, like pnpm-lock.yaml, yarn.lock and bun.lock,
+//! that keeps the formatting of what is not edited.
+
+use std::borrow::Cow;
+
+/// Lines of `contents`, each with its line break, to be edited with [`edit_entries`].
+pub fn lines(contents: &str) -> Vec<&str> {
+    contents.split_inclusive('\n').collect()
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Whether the line starts an entry at `indent`: it is indented by exactly that many spaces,
+/// and is not a comment, or the closing bracket of an entry before it.
+fn starts_entry(line: &str, indent: usize) -> bool {
+    indentation(line) == indent
+        && !matches!(
+            line.as_bytes().get(indent),
+            None | Some(b'\t' | b'\r' | b'\n' | b'#' | b'}' | b']')
+        )
+}
+
+/// Edit the entries at `indent` in `lines`. Each one is given to `edit`, with the lines
+/// indented more that follow it, and replaced with what that returns, or dropped for `None`.
+/// Lines before the first entry, and from the first line indented less after it, are kept.
+///
+/// Blank lines between the entries stay as they were.
+/// Returns the edited lines, and the number of entries that were kept.
+pub fn edit_entries<'c, F>(lines: &[&'c str], indent: usize, mut edit: F) -> (Vec<&'c str>, usize)
+where
+    F: FnMut(&[&'c str]) -> Option<Vec<&'c str>>,
+{
+    let start = lines
+        .iter()
+        .position(|l| starts_entry(l, indent))
+        .unwrap_or(lines.len());
+    let end = lines[start..]
+        .iter()
+        .position(|l| !is_blank(l) && indentation(l) < indent)
+        .map_or(lines.len(), |i| start + i);
+
+    // Each entry, and the blank lines after it.
+    let mut entries: Vec<(&[&'c str], &[&'c str])> = Vec::new();
+    let mut i = start;
+    while i < end {
+        let next = lines[i + 1..end]
+            .iter()
+            .position(|l| starts_entry(l, indent))
+            .map_or(end, |j| i + 1 + j);
+        let blank = lines[i + 1..next]
+            .iter()
+            .rev()
+            .take_while(|l| is_blank(l))
+            .count();
+        entries.push((&lines[i..next - blank], &lines[next - blank..next]));
+        i = next;
+    }
+
+    let mut edited = lines[..start].to_vec();
+    let mut kept = 0;
+    let mut separator: &[&str] = &[];
+    for (entry, blank) in &entries {
+        if let Some(entry) = edit(entry) {
+            edited.extend_from_slice(separator);
+            edited.extend(entry);
+            separator = blank;
+            kept += 1;
+        }
+    }
+    if kept > 0 {
+        if let Some((_, blank)) = entries.last() {
+            edited.extend_from_slice(blank);
+        }
+    }
+    edited.extend_from_slice(&lines[end..]);
+    (edited, kept)
+}
+
+/// Key of the entry that starts on `line`, unquoted. If the key is quoted, that is
+/// the first quoted string, otherwise what is before the `:` that ends the key.
+pub fn entry_key(line: &str) -> Cow<'_, str> {
+    let line = line.trim();
+    let mut chars = line.char_indices();
+    match chars.next() {
+        Some((_, quote @ ('"' | '\''))) => {
+            let mut key = String::new();
+            while let Some((_, c)) = chars.next() {
+                match c {
+                    '\\' if quote == '"' => key.extend(chars.next().map(|(_, c)| c)),
+                    c if c == quote => {
+                        // Single quotes are escaped by doubling them.
+                        if quote == '\'' && chars.clone().next().is_some_and(|(_, c)| c == '\'') {
+                            chars.next();
+                            key.push('\'');
+                        } else {
+                            break;
+                        }
+                    }
+                    c => key.push(c),
+                }
+            }
+            Cow::Owned(key)
+        }
+        _ => Cow::Borrowed(match line.strip_suffix(':') {
+            Some(key) => key,
+            None => line.split_once(": ").map_or(line, |(key, _)| key),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{edit_entries, entry_key, lines};
+
+    #[test]
+    fn keys() {
+        assert_eq!(entry_key("  long@5.2.3: {}\n"), "long@5.2.3");
+        assert_eq!(
+            entry_key("  '@chastelock/a@1.0.0':\n"),
+            "@chastelock/a@1.0.0"
+        );
+        assert_eq!(entry_key("'it''s':"), "it's");
+        assert_eq!(entry_key("long@npm:^5.0.0:\n"), "long@npm:^5.0.0");
+        assert_eq!(entry_key("\"a@^1\", \"a@^2\":\n"), "a@^1");
+        assert_eq!(entry_key("    \"a\\\"b\": [\"a@1.0.0\"],\n"), "a\"b");
+    }
+
+    #[test]
+    fn drop_entries() {
+        let contents = "packages:\n\n  a@1:\n    x: 1\n\n  b@1:\n    x: 2\n\n  c@1: {}\n\nend: 1\n";
+        let (edited, kept) = edit_entries(&lines(contents), 0, |entry| {
+            if entry_key(entry[0]) != "packages" {
+                return Some(entry.to_vec());
+            }
+            let (body, _) = edit_entries(&entry[1..], 2, |e| {
+                (entry_key(e[0]) == "b@1").then(|| e.to_vec())
+            });
+            Some([&entry[..1], &body[..]].concat())
+        });
+        assert_eq!(kept, 2);
+        assert_eq!(edited.concat(), "packages:\n\n  b@1:\n    x: 2\n\nend: 1\n");
+
+        let (edited, kept) = edit_entries(&lines(contents), 0, |entry| {
+            if entry_key(entry[0]) != "packages" {
+                return Some(entry.to_vec());
+            }
+            let (body, _) = edit_entries(&entry[1..], 2, |e| {
+                (entry_key(e[0]) != "c@1").then(|| e.to_vec())
+            });
+            Some([&entry[..1], &body[..]].concat())
+        });
+        assert_eq!(kept, 2);
+        assert_eq!(
+            edited.concat(),
+            "packages:\n\n  a@1:\n    x: 1\n\n  b@1:\n    x: 2\n\nend: 1\n"
+        );
+    }
+
+    #[test]
+    fn closing_brackets() {
+        let contents = "  \"packages\": {\n    \"a\": [\"a@1\"],\n\n    \"b\": {\n      \"x\": 1,\n    },\n  }\n}";
+        let (edited, kept) = edit_entries(&lines(contents)[1..], 4, |entry| {
+            (entry_key(entry[0]) == "b").then(|| entry.to_vec())
+        });
+        assert_eq!(kept, 1);
+        assert_eq!(
+            edited.concat(),
+            "    \"b\": {\n      \"x\": 1,\n    },\n  }\n}"
+        );
+    }
+}
@@ -5,29 +5,52 @@ pub static PACKAGE_JSON_FILENAME: &str = "package.json";
 
 pub use crate::chastefile::*;
 pub use crate::checksums::*;
+pub use crate::dedupe::*;
 pub use crate::dependency::*;
 pub use crate::derivation::*;
-pub use crate::error::{Error, Result};
+pub use crate::diagnostic::*;
+pub use crate::error::{Error, ErrorKind, Result};
+pub use crate::file_source::*;
 pub use crate::installation::*;
 pub use crate::meta::*;
 pub use crate::module_path::*;
 pub use crate::name::*;
+pub use crate::overrides::*;
 pub use crate::package::*;
+pub use crate::query::Query;
 pub use crate::quirks::*;
+pub use crate::reachability::*;
+pub use crate::satisfaction::*;
 pub use crate::source::*;
+pub use crate::stats::*;
 pub use crate::svs::*;
+pub use crate::warnings::*;
 
+mod affected;
 mod chastefile;
 mod checksums;
+mod cycles;
+mod dedupe;
 mod dependency;
 mod derivation;
+mod diagnostic;
 pub mod error;
+mod file_source;
+pub mod indented;
 mod installation;
 mod meta;
 mod misc;
 mod module_path;
 mod name;
+mod overrides;
 mod package;
+mod paths;
+mod query;
 mod quirks;
+mod reachability;
+mod satisfaction;
 mod source;
+mod stats;
 mod svs;
+mod warnings;
+mod workspace_order;
@@ -225,12 +225,12 @@ impl AsRef<str> for PackageNameBorrowed<'_> {
 }
 impl PartialOrd for PackageName {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        Some(self.inner.cmp(&other.inner))
+        Some(self.cmp(other))
     }
 }
 impl PartialOrd for PackageNameBorrowed<'_> {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        Some(self.inner.cmp(other.inner))
+        Some(self.cmp(other))
     }
 }
 impl Ord for PackageName {
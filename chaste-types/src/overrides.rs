@@ -0,0 +1,223 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use nom::combinator::{opt, rest};
+use nom::sequence::preceded;
+use nom::{bytes::complete::tag, Parser as _};
+
+use crate::dependency::Dependency;
+use crate::error::{Error, Result};
+use crate::name::{package_name_str, PackageName, PackageNameBorrowed};
+use crate::package::Package;
+use crate::svs::{SourceVersionSpecifier, VersionRange};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct OverrideID(pub(crate) usize);
+
+/// Which packages an [`Override`] is about: those of a name,
+/// and, if there is a source/version specifier, only those matching it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverrideSelector {
+    name: PackageName,
+    svs: Option<String>,
+}
+
+impl OverrideSelector {
+    pub fn new(name: PackageName, svs: Option<String>) -> Self {
+        Self { name, svs }
+    }
+
+    /// Parse a selector of a name, optionally followed by `@` and a specifier.
+    ///
+    /// # Example
+    /// ```
+    /// # use chaste_types::OverrideSelector;
+    /// let selector = OverrideSelector::parse("@types/node@npm:^20").unwrap();
+    /// assert_eq!(selector.name(), "@types/node");
+    /// assert_eq!(selector.svs(), Some("npm:^20"));
+    /// ```
+    pub fn parse(input: &str) -> Result<Self> {
+        match (package_name_str, opt(preceded(tag("@"), rest))).parse(input) {
+            Ok(("", (name, svs))) => Ok(Self {
+                name: PackageName::new(name.to_string())?,
+                svs: svs.filter(|s| !s.is_empty()).map(str::to_string),
+            }),
+            Ok(_) | Err(_) => Err(Error::InvalidOverrideSelector(input.to_string())),
+        }
+    }
+
+    pub fn name(&self) -> PackageNameBorrowed<'_> {
+        self.name.as_borrowed()
+    }
+
+    /// The source/version specifier, e.g. "^1" in "lodash@^1".
+    pub fn svs(&self) -> Option<&str> {
+        self.svs.as_deref()
+    }
+
+    fn range(&self) -> Option<VersionRange> {
+        let svs = self.svs.as_deref()?;
+        VersionRange::parse(svs.strip_prefix("npm:").unwrap_or(svs)).ok()
+    }
+
+    fn is_same_svs(&self, svs: &SourceVersionSpecifier) -> bool {
+        let (Some(own), svs) = (self.svs.as_deref(), svs.as_ref()) else {
+            return false;
+        };
+        own == svs || svs.strip_prefix("npm:") == Some(own) || own.strip_prefix("npm:") == Some(svs)
+    }
+
+    /// Whether the package is one of those selected, given specifiers it is depended on with.
+    pub(crate) fn selects_package<'a, I>(&self, package: &Package, incoming: I) -> bool
+    where
+        I: IntoIterator<Item = &'a SourceVersionSpecifier>,
+    {
+        if package
+            .name()
+            .is_none_or(|n| n.as_ref() != self.name.as_ref())
+        {
+            return false;
+        }
+        self.svs.is_none()
+            || package
+                .version()
+                .zip(self.range())
+                .is_some_and(|(version, range)| range.satisfies(version))
+            || incoming.into_iter().any(|svs| self.is_same_svs(svs))
+    }
+
+    /// Whether the dependency, on `package`, is one of those selected.
+    pub(crate) fn selects_dependency(&self, dependency: &Dependency, package: &Package) -> bool {
+        let is_same_name = match dependency.alias_name() {
+            Some(alias) => alias.as_ref() == self.name.as_ref(),
+            None => package
+                .name()
+                .is_some_and(|n| n.as_ref() == self.name.as_ref()),
+        };
+        if !is_same_name {
+            return false;
+        }
+        let Some(range) = self.range() else {
+            return self.svs.is_none() || dependency.svs().is_some_and(|s| self.is_same_svs(s));
+        };
+        dependency
+            .svs()
+            .and_then(|s| s.npm_range())
+            .is_some_and(|requested| range.allows_any(&requested))
+            || package.version().is_some_and(|v| range.satisfies(v))
+    }
+}
+
+/// A replacement, declared in package.json or the lockfile, of what some dependencies resolve to,
+/// like `"overrides"` of npm, pnpm and bun, or `"resolutions"` of yarn.
+#[derive(Debug, Clone)]
+pub struct Override {
+    selector: OverrideSelector,
+    parent: Option<OverrideSelector>,
+    parent_is_direct: bool,
+    replacement: String,
+}
+
+impl Override {
+    /// The dependencies this override is for.
+    pub fn selector(&self) -> &OverrideSelector {
+        &self.selector
+    }
+
+    /// If set, the override is only for dependencies of packages selected by this,
+    /// or, if not [`Override::is_parent_direct`], dependencies anywhere under them.
+    pub fn parent(&self) -> Option<&OverrideSelector> {
+        self.parent.as_ref()
+    }
+
+    pub fn is_parent_direct(&self) -> bool {
+        self.parent_is_direct
+    }
+
+    /// The specifier used instead of the one the dependency asks for, as declared,
+    /// e.g. "^2.1", "npm:nop@1.0.0", or, in npm, "$ms" for the one the root package asks for.
+    pub fn replacement(&self) -> &str {
+        &self.replacement
+    }
+
+    /// Whether the override applies to a dependency on `package`, not regarding the parent.
+    pub(crate) fn selects_dependency(&self, dependency: &Dependency, package: &Package) -> bool {
+        if self.selector.selects_dependency(dependency, package) {
+            return true;
+        }
+        // Replacements of other names are locked under those names,
+        // e.g. "is-odd" replaced with "npm:nop@1.0.0" is locked as "nop".
+        SourceVersionSpecifier::new(self.replacement.clone())
+            .ok()
+            .and_then(|svs| svs.aliased_package_name().map(|n| n.to_string()))
+            .is_some_and(|aliased| {
+                package.name().is_some_and(|n| n == aliased.as_str())
+                    && dependency
+                        .svs()
+                        .is_none_or(|s| s.aliased_package_name().is_none())
+            })
+    }
+}
+
+pub struct OverrideBuilder {
+    selector: OverrideSelector,
+    parent: Option<OverrideSelector>,
+    parent_is_direct: bool,
+    replacement: String,
+}
+
+impl OverrideBuilder {
+    pub fn new(selector: OverrideSelector, replacement: String) -> Self {
+        Self {
+            selector,
+            parent: None,
+            parent_is_direct: true,
+            replacement,
+        }
+    }
+
+    /// Only override dependencies of packages selected by `parent`.
+    pub fn parent(&mut self, parent: OverrideSelector) {
+        self.parent = Some(parent);
+        self.parent_is_direct = true;
+    }
+
+    /// Only override dependencies anywhere under packages selected by `ancestor`.
+    pub fn ancestor(&mut self, ancestor: OverrideSelector) {
+        self.parent = Some(ancestor);
+        self.parent_is_direct = false;
+    }
+
+    pub fn build(self) -> Override {
+        Override {
+            selector: self.selector,
+            parent: self.parent,
+            parent_is_direct: self.parent_is_direct,
+            replacement: self.replacement,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OverrideSelector;
+    use crate::error::Result;
+
+    #[test]
+    fn parse_selectors() -> Result<()> {
+        for (input, name, svs) in [
+            ("lodash", "lodash", None),
+            ("lodash@^4", "lodash", Some("^4")),
+            ("@types/node", "@types/node", None),
+            ("@types/node@>=20 <22", "@types/node", Some(">=20 <22")),
+            ("@yarnpkg/core@npm:^4.5", "@yarnpkg/core", Some("npm:^4.5")),
+        ] {
+            let selector = OverrideSelector::parse(input)?;
+            assert_eq!(selector.name(), name);
+            assert_eq!(selector.svs(), svs);
+        }
+        assert!(OverrideSelector::parse("a>b").is_err());
+
+        Ok(())
+    }
+}
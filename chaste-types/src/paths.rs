@@ -0,0 +1,189 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+use crate::chastefile::Chastefile;
+use crate::dependency::Dependency;
+use crate::package::{PackageID, PackageVersion};
+use crate::ProviderMeta;
+
+impl<P: ProviderMeta> Chastefile<P> {
+    /// The root package, and packages that nothing depends on,
+    /// like workspace members that are not dependencies of others.
+    pub fn top_package_ids(&self) -> Vec<PackageID> {
+        let depended: HashSet<PackageID> = self.all_dependencies().iter().map(|d| d.on).collect();
+        let mut tops: Vec<PackageID> = self
+            .packages_with_ids()
+            .into_iter()
+            .map(|(pid, _)| pid)
+            .filter(|pid| *pid == self.root_package_id() || !depended.contains(pid))
+            .collect();
+        tops.sort_unstable_by_key(|pid| pid.0);
+        tops
+    }
+
+    pub(crate) fn sort_key(&self, pid: PackageID) -> (Option<&str>, Option<&PackageVersion>) {
+        let package = self.package(pid);
+        (package.name().map(|n| n.as_ref()), package.version())
+    }
+
+    /// Fewest dependencies to go through to get to each package from any of `from`.
+    fn distances_from(&self, from: &[PackageID]) -> HashMap<PackageID, usize> {
+        let mut dependencies: HashMap<PackageID, Vec<PackageID>> = HashMap::new();
+        for dependency in self.all_dependencies() {
+            dependencies
+                .entry(dependency.from)
+                .or_default()
+                .push(dependency.on);
+        }
+        let mut distances: HashMap<PackageID, usize> = from.iter().map(|&pid| (pid, 0)).collect();
+        let mut q: VecDeque<PackageID> = from.iter().copied().collect();
+        while let Some(pid) = q.pop_front() {
+            let distance = distances[&pid] + 1;
+            for &on in dependencies
+                .get(&pid)
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+            {
+                distances.entry(on).or_insert_with(|| {
+                    q.push_back(on);
+                    distance
+                });
+            }
+        }
+        distances
+    }
+
+    /// Chains of dependencies from any of the packages `from` to any of the packages `to`,
+    /// shortest first, e.g. to find out why a package is installed. Chains of the same length
+    /// are in order of the names and versions of the packages they go through, from the end.
+    ///
+    /// Chains don't go through a package more than once, and end at the first package of
+    /// `from` found going back from `to`. There can be exponentially many of them, so ask for
+    /// at most `max_paths`, and of at most `max_depth` dependencies, unless the graph is small.
+    pub fn dependency_paths(
+        &self,
+        from: &[PackageID],
+        to: &[PackageID],
+        max_depth: Option<usize>,
+        max_paths: Option<usize>,
+    ) -> Vec<Vec<&Dependency>> {
+        let distances = self.distances_from(from);
+        let mut dependents: HashMap<PackageID, Vec<&Dependency>> = HashMap::new();
+        for dependency in self.all_dependencies() {
+            dependents
+                .entry(dependency.on)
+                .or_default()
+                .push(dependency);
+        }
+        for dependents in dependents.values_mut() {
+            dependents.sort_by_key(|d| self.sort_key(d.from));
+        }
+        let within_depth = |length: usize| max_depth.is_none_or(|max| length <= max);
+
+        // Chains found so far, from the package depending on the first dependency, in reverse.
+        // Those that can be the shortest when finished are continued first.
+        let mut queue: BinaryHeap<(Reverse<usize>, Reverse<usize>)> = BinaryHeap::new();
+        let mut chains: Vec<(PackageID, Vec<&Dependency>)> = Vec::new();
+        let mut to = to.to_vec();
+        to.sort_by_key(|&pid| self.sort_key(pid));
+        for pid in to {
+            if let Some(&distance) = distances.get(&pid) {
+                if within_depth(distance) {
+                    queue.push((Reverse(distance), Reverse(chains.len())));
+                    chains.push((pid, Vec::new()));
+                }
+            }
+        }
+        let mut paths = Vec::new();
+        while let Some((_, Reverse(index))) = queue.pop() {
+            if max_paths.is_some_and(|max| paths.len() >= max) {
+                break;
+            }
+            let head = chains[index].0;
+            let chain = std::mem::take(&mut chains[index].1);
+            if from.contains(&head) {
+                if !chain.is_empty() {
+                    paths.push(chain.into_iter().rev().collect());
+                }
+                continue;
+            }
+            for &dependency in dependents.get(&head).map(Vec::as_slice).unwrap_or_default() {
+                let Some(&distance) = distances.get(&dependency.from) else {
+                    continue;
+                };
+                let length = chain.len() + 1;
+                if !within_depth(length + distance)
+                    || chain.iter().any(|d| d.on == dependency.from)
+                    || dependency.from == head
+                {
+                    continue;
+                }
+                let mut continued = chain.clone();
+                continued.push(dependency);
+                queue.push((Reverse(length + distance), Reverse(chains.len())));
+                chains.push((dependency.from, continued));
+            }
+        }
+        paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chastefile::graph;
+    use crate::{Dependency, DependencyKind};
+
+    #[test]
+    fn dependency_paths() {
+        let (chastefile, pids) = graph(
+            &["root", "app", "a", "b", "c", "ms"],
+            &["app"],
+            &[
+                ("root", DependencyKind::DevDependency, "a"),
+                ("app", DependencyKind::Dependency, "b"),
+                ("a", DependencyKind::Dependency, "b"),
+                ("b", DependencyKind::Dependency, "c"),
+                ("c", DependencyKind::Dependency, "a"),
+                ("c", DependencyKind::Dependency, "ms"),
+                ("a", DependencyKind::Dependency, "ms"),
+            ],
+        );
+        let name = |pid| chastefile.package(pid).name().unwrap().as_ref();
+        let names = |paths: Vec<Vec<&Dependency>>| {
+            paths
+                .into_iter()
+                .map(|path| {
+                    let mut names = vec![name(path[0].from)];
+                    names.extend(path.iter().map(|d| name(d.on)));
+                    names.join(" ")
+                })
+                .collect::<Vec<String>>()
+        };
+        let from = [pids["root"], pids["app"]];
+        let (a, c, ms) = (pids["a"], pids["c"], pids["ms"]);
+
+        assert_eq!(
+            names(chastefile.dependency_paths(&from, &[ms], None, None)),
+            ["root a ms", "app b c ms", "root a b c ms", "app b c a ms"]
+        );
+        assert_eq!(
+            names(chastefile.dependency_paths(&from, &[ms], Some(3), None)),
+            ["root a ms", "app b c ms"]
+        );
+        assert_eq!(
+            names(chastefile.dependency_paths(&from, &[ms], None, Some(1))),
+            ["root a ms"]
+        );
+        assert_eq!(
+            names(chastefile.dependency_paths(&[pids["app"]], &[a, c], None, None)),
+            ["app b c", "app b c a"]
+        );
+        assert!(chastefile
+            .dependency_paths(&from, &[pids["app"]], None, None)
+            .is_empty());
+        assert_eq!(chastefile.top_package_ids(), from);
+    }
+}
@@ -0,0 +1,775 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use nom::branch::alt;
+use nom::bytes::complete::{is_not, tag, take_while, take_while1};
+use nom::character::complete::{char, multispace0, multispace1};
+use nom::combinator::{all_consuming, cut, map, map_res, opt, recognize, value};
+use nom::multi::{many0, many1, separated_list1};
+use nom::sequence::{delimited, preceded};
+use nom::{IResult, Parser as _};
+
+use crate::chastefile::Chastefile;
+use crate::error::{Error, Result};
+use crate::package::{Package, PackageID};
+use crate::reachability::Reachability;
+use crate::source::{PackageSource, PackageSourceType};
+use crate::svs::VersionRange;
+use crate::ProviderMeta;
+
+/// A query for packages of a [`Chastefile`] in a CSS-like selector language,
+/// modeled after [`npm query`](https://docs.npmjs.com/cli/v10/using-npm/dependency-selectors).
+///
+/// Packages are the elements, and dependencies go from parents to children.
+/// The root package is also the parent of the workspace members.
+///
+/// - `*` is any package, `#name` a package of the name, and `#name@range` also in the range.
+/// - `.prod`, `.dev`, `.optional`, `.peer` and `.workspace` are packages installed
+///   for production, only for development, only as optional or peer dependencies,
+///   and workspace members. A class right after `#name` is read as part of the name,
+///   so put it first, like in `.dev#ms`.
+/// - `[key]` is a package that has the attribute, and `[key=value]` one where it is the value.
+///   `^=`, `$=`, `*=`, `~=` and `|=` compare like in CSS, and `[key=value i]` ignores case.
+///   Attributes are `name`, `version`, `source` (`npm`, `tarball` or `git`), `url`,
+///   `integrity`, `patch` (the path to the patch file) and `path` (where it's installed).
+/// - `:root` is the root package, `:scope` the packages the query is from,
+///   `:empty` a package without dependencies, `:derived` one that was patched,
+///   `:semver(range)` one with a version in the range and `:attr(key=value)` is `[key=value]`.
+/// - `:not(selectors)`, `:is(selectors)` and `:has(selectors)`, where selectors of `:has`
+///   can start with a combinator, like `:has(> #ms)`.
+/// - `a > b` is b depended on by a, `a b` is b that a depends on transitively,
+///   and `a ~ b` is b with a parent in common with a.
+/// - `a, b` is packages matching any of the selectors.
+///
+/// `:outdated` and other pseudo-classes that need a registry are not supported.
+///
+/// # Example
+/// ```
+/// # use chaste_types::Query;
+/// let query = Query::parse(":root > .dev[name^=@types/], :attr(source=git)").unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Query {
+    selectors: Vec<Complex>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Combinator {
+    /// `a > b`
+    Child,
+    /// `a b`
+    Descendant,
+    /// `a ~ b`
+    Sibling,
+}
+
+/// Compound selectors joined by combinators.
+#[derive(Debug, Clone)]
+struct Complex {
+    first: Compound,
+    rest: Vec<(Combinator, Compound)>,
+}
+
+/// A selector in `:has()`, relative to the package it's about.
+#[derive(Debug, Clone)]
+struct Relative {
+    combinator: Combinator,
+    complex: Complex,
+}
+
+/// Simple selectors that all have to match. Empty for `*`.
+#[derive(Debug, Clone)]
+struct Compound(Vec<Simple>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Prod,
+    Dev,
+    Optional,
+    Peer,
+    Workspace,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Key {
+    Name,
+    Version,
+    Source,
+    Url,
+    Integrity,
+    Patch,
+    Path,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    /// `=`
+    Equals,
+    /// `^=`
+    Prefix,
+    /// `$=`
+    Suffix,
+    /// `*=`
+    Contains,
+    /// `~=`
+    Word,
+    /// `|=`
+    Dash,
+}
+
+#[derive(Debug, Clone)]
+struct Attribute {
+    key: Key,
+    /// The operator and value, if the attribute has to be a value, and whether to ignore case.
+    test: Option<(Operator, String, bool)>,
+}
+
+#[derive(Debug, Clone)]
+enum Simple {
+    Name(String, Option<VersionRange>),
+    Class(Class),
+    Attribute(Attribute),
+    Root,
+    Scope,
+    Empty,
+    Derived,
+    Semver(VersionRange),
+    Not(Vec<Complex>),
+    Is(Vec<Complex>),
+    Has(Vec<Relative>),
+}
+
+fn ws<'a, O, F>(
+    inner: F,
+) -> impl nom::Parser<&'a str, Output = O, Error = nom::error::Error<&'a str>>
+where
+    F: nom::Parser<&'a str, Output = O, Error = nom::error::Error<&'a str>>,
+{
+    delimited(multispace0, inner, multispace0)
+}
+
+fn ident(input: &str) -> IResult<&str, &str> {
+    take_while1(|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_').parse(input)
+}
+
+fn version_range(input: &str) -> IResult<&str, VersionRange> {
+    map_res(
+        take_while1(|c: char| !c.is_whitespace() && !",)[:".contains(c)),
+        VersionRange::parse,
+    )
+    .parse(input)
+}
+
+/// A package name, without characters that have a meaning in selectors,
+/// which are not allowed in new package names anyway.
+fn package_name(input: &str) -> IResult<&str, &str> {
+    let part = || take_while1(|c: char| c.is_ascii_alphanumeric() || "-._".contains(c));
+    recognize((opt((char('@'), part(), char('/'))), part())).parse(input)
+}
+
+fn name(input: &str) -> IResult<&str, Simple> {
+    map(
+        (package_name, opt(preceded(char('@'), version_range))),
+        |(name, range)| Simple::Name(name.to_string(), range),
+    )
+    .parse(input)
+}
+
+fn class(input: &str) -> IResult<&str, Class> {
+    map_res(ident, |class| match class {
+        "prod" => Ok(Class::Prod),
+        "dev" => Ok(Class::Dev),
+        "optional" => Ok(Class::Optional),
+        "peer" => Ok(Class::Peer),
+        "workspace" => Ok(Class::Workspace),
+        _ => Err(()),
+    })
+    .parse(input)
+}
+
+fn key(input: &str) -> IResult<&str, Key> {
+    map_res(ident, |key| match key {
+        "name" => Ok(Key::Name),
+        "version" => Ok(Key::Version),
+        "source" => Ok(Key::Source),
+        "url" => Ok(Key::Url),
+        "integrity" => Ok(Key::Integrity),
+        "patch" => Ok(Key::Patch),
+        "path" => Ok(Key::Path),
+        _ => Err(()),
+    })
+    .parse(input)
+}
+
+fn operator(input: &str) -> IResult<&str, Operator> {
+    alt((
+        value(Operator::Equals, tag("=")),
+        value(Operator::Prefix, tag("^=")),
+        value(Operator::Suffix, tag("$=")),
+        value(Operator::Contains, tag("*=")),
+        value(Operator::Word, tag("~=")),
+        value(Operator::Dash, tag("|=")),
+    ))
+    .parse(input)
+}
+
+fn attribute_value(input: &str) -> IResult<&str, &str> {
+    alt((
+        delimited(char('"'), take_while(|c| c != '"'), char('"')),
+        delimited(char('\''), take_while(|c| c != '\''), char('\'')),
+        take_while1(|c: char| !c.is_whitespace() && c != ']' && c != ')'),
+    ))
+    .parse(input)
+}
+
+/// What is between the brackets of `[key=value]`.
+fn attribute(input: &str) -> IResult<&str, Attribute> {
+    map(
+        (
+            ws(key),
+            opt((
+                operator,
+                ws(attribute_value),
+                opt(value(true, (char('i'), multispace0))),
+            )),
+        ),
+        |(key, test)| Attribute {
+            key,
+            test: test.map(|(op, value, insensitive)| {
+                (op, value.to_string(), insensitive.unwrap_or(false))
+            }),
+        },
+    )
+    .parse(input)
+}
+
+fn arguments<'a, O, F>(
+    inner: F,
+) -> impl nom::Parser<&'a str, Output = O, Error = nom::error::Error<&'a str>>
+where
+    F: nom::Parser<&'a str, Output = O, Error = nom::error::Error<&'a str>>,
+{
+    delimited(char('('), ws(inner), char(')'))
+}
+
+fn pseudo_class(input: &str) -> IResult<&str, Simple> {
+    let start = input;
+    let (input, name) = ident(input)?;
+    match name {
+        "root" => Ok((input, Simple::Root)),
+        "scope" => Ok((input, Simple::Scope)),
+        "empty" => Ok((input, Simple::Empty)),
+        "derived" => Ok((input, Simple::Derived)),
+        "semver" => map_res(arguments(is_not(")")), |range: &str| {
+            VersionRange::parse(range.trim()).map(Simple::Semver)
+        })
+        .parse(input),
+        "attr" => map(
+            arguments(alt((delimited(char('['), attribute, char(']')), attribute))),
+            Simple::Attribute,
+        )
+        .parse(input),
+        "not" => map(arguments(selector_list), Simple::Not).parse(input),
+        "is" => map(arguments(selector_list), Simple::Is).parse(input),
+        "has" => map(
+            arguments(separated_list1(ws(char(',')), relative)),
+            Simple::Has,
+        )
+        .parse(input),
+        _ => Err(nom::Err::Error(nom::error::Error::new(
+            start,
+            nom::error::ErrorKind::Tag,
+        ))),
+    }
+}
+
+fn simple(input: &str) -> IResult<&str, Simple> {
+    // Past the first character, nothing else can match, so errors are reported from there.
+    alt((
+        preceded(char('#'), cut(name)),
+        map(preceded(char('.'), cut(class)), Simple::Class),
+        map(
+            preceded(char('['), cut((attribute, char(']')))),
+            |(attribute, _)| Simple::Attribute(attribute),
+        ),
+        preceded(char(':'), cut(pseudo_class)),
+    ))
+    .parse(input)
+}
+
+fn compound(input: &str) -> IResult<&str, Compound> {
+    alt((
+        map(preceded(char('*'), many0(simple)), Compound),
+        map(many1(simple), Compound),
+    ))
+    .parse(input)
+}
+
+fn combinator(input: &str) -> IResult<&str, Combinator> {
+    alt((
+        ws(alt((
+            value(Combinator::Child, char('>')),
+            value(Combinator::Sibling, char('~')),
+        ))),
+        value(Combinator::Descendant, multispace1),
+    ))
+    .parse(input)
+}
+
+fn complex(input: &str) -> IResult<&str, Complex> {
+    map(
+        (compound, many0((combinator, compound))),
+        |(first, rest)| Complex { first, rest },
+    )
+    .parse(input)
+}
+
+fn relative(input: &str) -> IResult<&str, Relative> {
+    map(
+        (
+            opt(ws(alt((
+                value(Combinator::Child, char('>')),
+                value(Combinator::Sibling, char('~')),
+            )))),
+            complex,
+        ),
+        |(combinator, complex)| Relative {
+            combinator: combinator.unwrap_or(Combinator::Descendant),
+            complex,
+        },
+    )
+    .parse(input)
+}
+
+fn selector_list(input: &str) -> IResult<&str, Vec<Complex>> {
+    separated_list1(ws(char(',')), complex).parse(input)
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Self> {
+        let rest = match all_consuming(ws(selector_list)).parse(input) {
+            Ok((_, selectors)) => return Ok(Self { selectors }),
+            Err(nom::Err::Error(e) | nom::Err::Failure(e)) => e.input,
+            Err(nom::Err::Incomplete(_)) => "",
+        };
+        Err(Error::InvalidQuery {
+            query: input.to_string(),
+            offset: input.len() - rest.len(),
+        })
+    }
+}
+
+fn attribute_matches(attribute: &Attribute, values: &[String]) -> bool {
+    let Some((op, expected, insensitive)) = &attribute.test else {
+        return !values.is_empty();
+    };
+    let fold = |s: &str| match insensitive {
+        true => s.to_lowercase(),
+        false => s.to_string(),
+    };
+    let expected = fold(expected);
+    values.iter().map(|v| fold(v)).any(|actual| match op {
+        Operator::Equals => actual == expected,
+        Operator::Prefix => actual.starts_with(&expected),
+        Operator::Suffix => actual.ends_with(&expected),
+        Operator::Contains => actual.contains(&expected),
+        Operator::Word => actual.split_whitespace().any(|w| w == expected),
+        Operator::Dash => {
+            actual == expected
+                || actual
+                    .strip_prefix(&expected)
+                    .is_some_and(|r| r.starts_with('-'))
+        }
+    })
+}
+
+/// Where the query is evaluated, with dependencies indexed both ways.
+struct Evaluation<'a, P: ProviderMeta> {
+    chastefile: &'a Chastefile<P>,
+    scope: HashSet<PackageID>,
+    all: HashSet<PackageID>,
+    dependencies: HashMap<PackageID, HashSet<PackageID>>,
+    dependents: HashMap<PackageID, HashSet<PackageID>>,
+    reachability: HashMap<PackageID, Reachability>,
+}
+
+impl<'a, P: ProviderMeta> Evaluation<'a, P> {
+    fn new(chastefile: &'a Chastefile<P>, scope: &[PackageID]) -> Self {
+        let mut dependencies: HashMap<PackageID, HashSet<PackageID>> = HashMap::new();
+        let mut dependents: HashMap<PackageID, HashSet<PackageID>> = HashMap::new();
+        let root = chastefile.root_package_id();
+        let edges = chastefile
+            .all_dependencies()
+            .iter()
+            .map(|d| (d.from, d.on))
+            .chain(
+                chastefile
+                    .workspace_member_ids()
+                    .iter()
+                    .map(|&member| (root, member)),
+            );
+        for (from, on) in edges {
+            dependencies.entry(from).or_default().insert(on);
+            dependents.entry(on).or_default().insert(from);
+        }
+        let mut tops = vec![root];
+        tops.extend(chastefile.workspace_member_ids());
+        Self {
+            chastefile,
+            scope: scope.iter().copied().collect(),
+            all: chastefile
+                .packages_with_ids()
+                .into_iter()
+                .map(|(pid, _)| pid)
+                .collect(),
+            dependencies,
+            dependents,
+            reachability: chastefile.reachability(&tops),
+        }
+    }
+
+    fn values(&self, pid: PackageID, package: &Package, key: Key) -> Vec<String> {
+        match key {
+            Key::Name => package.name().map(|n| n.to_string()).into_iter().collect(),
+            Key::Version => package
+                .version()
+                .map(|v| v.to_string())
+                .into_iter()
+                .collect(),
+            Key::Source => package
+                .source_type()
+                .map(|t| match t {
+                    PackageSourceType::Npm => "npm",
+                    PackageSourceType::TarballURL => "tarball",
+                    PackageSourceType::Git => "git",
+                })
+                .map(str::to_string)
+                .into_iter()
+                .collect(),
+            Key::Url => match package.source() {
+                Some(PackageSource::TarballURL { url } | PackageSource::Git { url }) => {
+                    vec![url.clone()]
+                }
+                _ => Vec::new(),
+            },
+            Key::Integrity => package
+                .checksums()
+                .map(|c| c.integrity().to_string())
+                .into_iter()
+                .collect(),
+            Key::Patch => package
+                .derivation_meta()
+                .and_then(|d| d.patch())
+                .map(|p| p.path().to_string())
+                .into_iter()
+                .collect(),
+            Key::Path => self
+                .chastefile
+                .package_installations(pid)
+                .into_iter()
+                .map(|i| i.path().as_ref().to_string())
+                .collect(),
+        }
+    }
+
+    fn matches(&self, pid: PackageID, simple: &Simple) -> bool {
+        let package = self.chastefile.package(pid);
+        match simple {
+            Simple::Name(name, range) => {
+                package.name().is_some_and(|n| n.as_ref() == name)
+                    && match (range, package.version()) {
+                        (None, _) => true,
+                        (Some(range), Some(version)) => range.satisfies(version),
+                        (Some(_), None) => false,
+                    }
+            }
+            Simple::Class(Class::Workspace) => {
+                self.chastefile.workspace_member_ids().contains(&pid)
+            }
+            Simple::Class(class) => self.reachability.get(&pid).is_some_and(|r| match class {
+                Class::Prod => r.is_prod(),
+                Class::Dev => r.dev,
+                Class::Optional => r.optional,
+                Class::Peer => r.peer,
+                Class::Workspace => unreachable!(),
+            }),
+            Simple::Attribute(attribute) => {
+                attribute_matches(attribute, &self.values(pid, package, attribute.key))
+            }
+            Simple::Root => pid == self.chastefile.root_package_id(),
+            Simple::Scope => self.scope.contains(&pid),
+            Simple::Empty => self.dependencies.get(&pid).is_none_or(|d| d.is_empty()),
+            Simple::Derived => package.is_derived(),
+            Simple::Semver(range) => package.version().is_some_and(|v| range.satisfies(v)),
+            Simple::Not(_) | Simple::Is(_) | Simple::Has(_) => unreachable!(),
+        }
+    }
+
+    fn simple(&self, simple: &Simple) -> HashSet<PackageID> {
+        match simple {
+            Simple::Not(selectors) => {
+                let excluded = self.selectors(selectors);
+                self.all.difference(&excluded).copied().collect()
+            }
+            Simple::Is(selectors) => self.selectors(selectors),
+            Simple::Has(relatives) => relatives.iter().flat_map(|r| self.relative(r)).collect(),
+            _ => self
+                .all
+                .iter()
+                .copied()
+                .filter(|&pid| self.matches(pid, simple))
+                .collect(),
+        }
+    }
+
+    fn compound(&self, compound: &Compound) -> HashSet<PackageID> {
+        let mut matching = self.all.clone();
+        for simple in &compound.0 {
+            let matching_simple = self.simple(simple);
+            matching.retain(|pid| matching_simple.contains(pid));
+        }
+        matching
+    }
+
+    /// Packages related to `from` as described by the combinator, looking through
+    /// dependencies forwards from parents to children, or backwards.
+    fn step(
+        &self,
+        from: &HashSet<PackageID>,
+        combinator: Combinator,
+        forwards: bool,
+    ) -> HashSet<PackageID> {
+        let next = match forwards {
+            true => &self.dependencies,
+            false => &self.dependents,
+        };
+        let neighbours = |pid: &PackageID| next.get(pid).into_iter().flatten().copied();
+        let mut found = HashSet::new();
+        match combinator {
+            Combinator::Child => found.extend(from.iter().flat_map(neighbours)),
+            Combinator::Descendant => {
+                let mut q: VecDeque<PackageID> = from.iter().flat_map(neighbours).collect();
+                while let Some(pid) = q.pop_front() {
+                    if found.insert(pid) {
+                        q.extend(neighbours(&pid));
+                    }
+                }
+            }
+            // The same either way.
+            Combinator::Sibling => {
+                for pid in from {
+                    for parent in self.dependents.get(pid).into_iter().flatten() {
+                        let children = self.dependencies.get(parent).into_iter().flatten();
+                        found.extend(children.filter(|c| *c != pid).copied());
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    fn complex(&self, complex: &Complex) -> HashSet<PackageID> {
+        let mut matching = self.compound(&complex.first);
+        for (combinator, compound) in &complex.rest {
+            let next = self.step(&matching, *combinator, true);
+            let matching_compound = self.compound(compound);
+            matching = next.intersection(&matching_compound).copied().collect();
+        }
+        matching
+    }
+
+    /// Packages from which the relative selector matches something.
+    fn relative(&self, relative: &Relative) -> HashSet<PackageID> {
+        let parts = [(relative.combinator, &relative.complex.first)]
+            .into_iter()
+            .chain(
+                relative
+                    .complex
+                    .rest
+                    .iter()
+                    .map(|(c, compound)| (*c, compound)),
+            );
+        let mut matching: Option<HashSet<PackageID>> = None;
+        for (combinator, compound) in parts.collect::<Vec<_>>().into_iter().rev() {
+            let mut current = self.compound(compound);
+            if let Some(after) = matching {
+                current.retain(|pid| after.contains(pid));
+            }
+            matching = Some(self.step(&current, combinator, false));
+        }
+        matching.unwrap_or_default()
+    }
+
+    fn selectors(&self, selectors: &[Complex]) -> HashSet<PackageID> {
+        selectors.iter().flat_map(|c| self.complex(c)).collect()
+    }
+}
+
+impl<P: ProviderMeta> Chastefile<P> {
+    /// Packages matching the query, in order of names and versions.
+    /// `:scope` is the root package.
+    pub fn query(&self, query: &Query) -> Vec<PackageID> {
+        self.query_from(query, &[self.root_package_id()])
+    }
+
+    /// Packages matching the query, in order of names and versions,
+    /// where `:scope` is any of `scope`, like a workspace member.
+    pub fn query_from(&self, query: &Query, scope: &[PackageID]) -> Vec<PackageID> {
+        let evaluation = Evaluation::new(self, scope);
+        let mut matching: Vec<PackageID> =
+            evaluation.selectors(&query.selectors).into_iter().collect();
+        matching.sort_unstable_by_key(|&pid| (self.sort_key(pid), pid.0));
+        matching
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::chastefile::graph_builder;
+    use crate::error::Result;
+    use crate::{Chastefile, Checksums, DependencyKind, Error, PackageID, PackageSource};
+
+    use super::Query;
+
+    /// Root with a devDependency on "@types/node" and members "app" and "lib",
+    /// "app" depending on "ms" and "lib" from git, and "lib" optionally on "fsevents"
+    /// and as a peer on "react".
+    fn project() -> Result<(Chastefile<()>, HashMap<&'static str, PackageID>)> {
+        let (chastefile, pids) = graph_builder(
+            &[
+                "root",
+                "app",
+                "lib",
+                "@types/node",
+                "ms",
+                "fsevents",
+                "react",
+            ],
+            &["app", "lib"],
+            &[
+                ("root", DependencyKind::DevDependency, "@types/node"),
+                ("app", DependencyKind::Dependency, "ms"),
+                ("app", DependencyKind::Dependency, "lib"),
+                ("lib", DependencyKind::OptionalDependency, "fsevents"),
+                ("lib", DependencyKind::PeerDependency, "react"),
+            ],
+            |name, package| {
+                let version = match name {
+                    "root" => {
+                        package.name(None);
+                        return;
+                    }
+                    "app" | "lib" => "1.0.0",
+                    "@types/node" => "20.1.0",
+                    "ms" => "2.1.3",
+                    "fsevents" => "2.3.3",
+                    _ => "18.0.0",
+                };
+                package.version(Some(version.to_string()));
+                match name {
+                    "app" | "lib" => {}
+                    "fsevents" => package.source(PackageSource::Git {
+                        url: "https://github.com/fsevents/fsevents.git".to_string(),
+                    }),
+                    _ => {
+                        package.source(PackageSource::Npm);
+                        package.checksums(Checksums::Tarball("sha512-AAAA".parse().unwrap()));
+                    }
+                }
+            },
+        );
+        Ok((chastefile.build()?, pids))
+    }
+
+    fn names(chastefile: &Chastefile<()>, query: &str) -> Result<Vec<String>> {
+        Ok(chastefile
+            .query(&Query::parse(query)?)
+            .into_iter()
+            .map(|pid| match chastefile.package(pid).name() {
+                Some(name) => name.to_string(),
+                None => "[root]".to_string(),
+            })
+            .collect())
+    }
+
+    #[test]
+    fn query_selectors() -> Result<()> {
+        let (chastefile, _) = project()?;
+
+        assert_eq!(names(&chastefile, ":root")?, ["[root]"]);
+        assert_eq!(names(&chastefile, "#ms, #react@^18")?, ["ms", "react"]);
+        assert!(names(&chastefile, "#react@^17")?.is_empty());
+        assert_eq!(
+            names(&chastefile, ":root > *")?,
+            ["@types/node", "app", "lib"]
+        );
+        assert_eq!(names(&chastefile, ".workspace")?, ["app", "lib"]);
+        assert_eq!(names(&chastefile, ":root > .dev")?, ["@types/node"]);
+        assert_eq!(
+            names(&chastefile, ".prod")?,
+            ["[root]", "app", "fsevents", "lib", "ms", "react"]
+        );
+        assert_eq!(names(&chastefile, ".optional")?, ["fsevents"]);
+        assert_eq!(names(&chastefile, ".peer")?, ["react"]);
+        assert_eq!(names(&chastefile, "[name^=@types/]")?, ["@types/node"]);
+        assert_eq!(names(&chastefile, "[name*=EVENT i]")?, ["fsevents"]);
+        assert_eq!(names(&chastefile, ":attr(source=git)")?, ["fsevents"]);
+        assert_eq!(names(&chastefile, "[url$=\".git\"]")?, ["fsevents"]);
+        assert_eq!(
+            names(&chastefile, ":not([integrity])")?,
+            ["[root]", "app", "fsevents", "lib"]
+        );
+        assert_eq!(
+            names(&chastefile, ":semver(>=18)")?,
+            ["@types/node", "react"]
+        );
+        assert_eq!(names(&chastefile, ".workspace:has(> #ms)")?, ["app"]);
+        assert_eq!(names(&chastefile, ".workspace:has(.peer)")?, ["app", "lib"]);
+        assert_eq!(names(&chastefile, "*:has(> #app ~ #lib)")?, ["[root]"]);
+        assert_eq!(names(&chastefile, "#app #react")?, ["react"]);
+        assert_eq!(names(&chastefile, "#ms ~ *")?, ["lib"]);
+        assert_eq!(
+            names(&chastefile, ":is(#ms, #lib) > *")?,
+            ["fsevents", "react"]
+        );
+        assert_eq!(
+            names(&chastefile, ".workspace > :empty:not(.optional)")?,
+            ["ms", "react"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn query_invalid() {
+        for (query, offset) in [
+            ("", 0),
+            (":outdated", 1),
+            (".bundled", 1),
+            ("[color=red]", 1),
+            ("#ms >", 4),
+            (":has(", 5),
+            ("a,", 0),
+        ] {
+            assert_eq!(
+                Query::parse(query).unwrap_err(),
+                Error::InvalidQuery {
+                    query: query.to_string(),
+                    offset,
+                }
+            );
+        }
+        assert_eq!(
+            Query::parse(".prod > :semver(^1").unwrap_err().to_string(),
+            "Invalid query \".prod > :semver(^1\": unexpected end at offset 18"
+        );
+        assert_eq!(
+            Query::parse("#ms, .bundled").unwrap_err().to_string(),
+            "Invalid query \"#ms, .bundled\": unexpected \"bundled\" at offset 6"
+        );
+    }
+}
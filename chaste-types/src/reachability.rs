@@ -0,0 +1,137 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::chastefile::Chastefile;
+use crate::dependency::Dependency;
+use crate::package::PackageID;
+use crate::ProviderMeta;
+
+/// How a package is reachable from the packages it was looked for from, like the
+/// `"dev"`, `"optional"`, `"devOptional"` and `"peer"` flags of npm's package-lock.json.
+///
+/// A flag is set if every path of dependencies to the package goes through a dependency of
+/// that kind. None are set for the packages looked from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Reachability {
+    /// Only needed for development, and left out of production installs.
+    pub dev: bool,
+    /// Only needed if optional dependencies can be installed.
+    pub optional: bool,
+    /// Needed only for development or if optional dependencies can be installed.
+    /// Set whenever `dev` or `optional` is, unlike in npm, which then leaves it out.
+    pub dev_optional: bool,
+    /// Only there to satisfy peer dependencies.
+    pub peer: bool,
+}
+
+impl Reachability {
+    /// Whether the package is installed without devDependencies, like with `npm install --omit=dev`.
+    pub fn is_prod(&self) -> bool {
+        !self.dev
+    }
+}
+
+impl<P: ProviderMeta> Chastefile<P> {
+    /// Packages reachable from `from` without going through dependencies `through` says to avoid.
+    ///
+    /// devDependencies are only followed from `from`,
+    /// as implementations don't install devDependencies of dependencies.
+    fn reachable_avoiding<F>(&self, from: &[PackageID], avoid: F) -> HashSet<PackageID>
+    where
+        F: Fn(&Dependency) -> bool,
+    {
+        let mut seen: HashSet<PackageID> = from.iter().copied().collect();
+        let mut q: VecDeque<PackageID> = from.iter().copied().collect();
+        while let Some(pid) = q.pop_front() {
+            for dependency in self.package_dependencies(pid) {
+                if avoid(dependency) || (dependency.kind.is_dev() && !from.contains(&pid)) {
+                    continue;
+                }
+                if seen.insert(dependency.on) {
+                    q.push_back(dependency.on);
+                }
+            }
+        }
+        seen
+    }
+
+    /// How every package reachable from `from` is reachable.
+    ///
+    /// Look from the root package, a workspace member, or all of them together,
+    /// e.g. to leave out packages that are only for development of a workspace member.
+    pub fn reachability(&self, from: &[PackageID]) -> HashMap<PackageID, Reachability> {
+        let reachable = self.reachable_avoiding(from, |_| false);
+        let not_dev = self.reachable_avoiding(from, |d| d.kind.is_dev());
+        let not_optional = self.reachable_avoiding(from, |d| d.kind.is_optional());
+        let not_dev_optional =
+            self.reachable_avoiding(from, |d| d.kind.is_dev() || d.kind.is_optional());
+        let not_peer = self.reachable_avoiding(from, |d| d.kind.is_peer());
+        reachable
+            .into_iter()
+            .map(|pid| {
+                let reachability = Reachability {
+                    dev: !not_dev.contains(&pid),
+                    optional: !not_optional.contains(&pid),
+                    dev_optional: !not_dev_optional.contains(&pid),
+                    peer: !not_peer.contains(&pid),
+                };
+                (pid, reachability)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Reachability;
+    use crate::chastefile::graph;
+    use crate::DependencyKind;
+
+    #[test]
+    fn reachability() {
+        let (chastefile, pids) = graph(
+            &[
+                "root", "app", "react", "vitest", "fsevents", "chokidar", "tslib", "esbuild",
+            ],
+            &["app"],
+            &[
+                ("app", DependencyKind::PeerDependency, "react"),
+                ("app", DependencyKind::DevDependency, "vitest"),
+                ("app", DependencyKind::OptionalDependency, "chokidar"),
+                ("vitest", DependencyKind::Dependency, "fsevents"),
+                ("vitest", DependencyKind::Dependency, "esbuild"),
+                ("chokidar", DependencyKind::Dependency, "fsevents"),
+                ("chokidar", DependencyKind::Dependency, "tslib"),
+                ("root", DependencyKind::Dependency, "tslib"),
+                // Not installed, as a devDependency of a dependency.
+                ("esbuild", DependencyKind::DevDependency, "root"),
+            ],
+        );
+
+        let reachability = chastefile.reachability(&[pids["app"]]);
+        let flags = |name: &str| {
+            let Reachability {
+                dev,
+                optional,
+                dev_optional,
+                peer,
+            } = reachability[&pids[name]];
+            (dev, optional, dev_optional, peer)
+        };
+        assert_eq!(reachability.len(), 7);
+        assert_eq!(flags("app"), (false, false, false, false));
+        assert_eq!(flags("react"), (false, false, false, true));
+        assert_eq!(flags("vitest"), (true, false, true, false));
+        assert_eq!(flags("esbuild"), (true, false, true, false));
+        assert_eq!(flags("chokidar"), (false, true, true, false));
+        assert_eq!(flags("fsevents"), (false, false, true, false));
+        assert_eq!(flags("tslib"), (false, true, true, false));
+        assert!(!reachability[&pids["vitest"]].is_prod());
+
+        let reachability = chastefile.reachability(&[pids["root"], pids["app"]]);
+        assert_eq!(reachability.len(), 8);
+        assert_eq!(reachability[&pids["tslib"]], Reachability::default());
+    }
+}
@@ -0,0 +1,135 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use crate::chastefile::Chastefile;
+use crate::dependency::Dependency;
+use crate::package::PackageVersion;
+use crate::svs::{SourceVersionSpecifier, VersionRange};
+use crate::ProviderMeta;
+
+/// A dependency whose locked package is of a version outside of the range it asks for.
+#[derive(Debug, Clone)]
+pub struct UnsatisfiedRange<'a> {
+    pub dependency: &'a Dependency,
+    /// The version range asked for, e.g. "^4.2.0" of "npm:lodash@^4.2.0".
+    pub range: String,
+    pub version: &'a PackageVersion,
+}
+
+/// The npm version range of the specifier, looking into the original specifier of patches.
+pub(crate) fn npm_range(svs: &SourceVersionSpecifier) -> Option<String> {
+    if svs.is_patch() {
+        let patched = svs.patched_svs_decoded()?.decode_utf8().ok()?;
+        return npm_range(&SourceVersionSpecifier::new(patched.into_owned()).ok()?);
+    }
+    if !svs.is_npm() {
+        return None;
+    }
+    svs.npm_range_str().map(str::to_string)
+}
+
+impl<P: ProviderMeta> Chastefile<P> {
+    /// Dependencies whose locked version does not satisfy the range they ask for,
+    /// e.g. from a lockfile that was edited by hand, or badly merged.
+    ///
+    /// Only dependencies on npm ranges are checked. Tags, like "latest", and sources other than
+    /// npm registries can't be checked against. Patched packages are checked with the range
+    /// of the package they are patching. Not checked are also:
+    /// - peer dependencies, which implementations install even if unmet, with a warning,
+    /// - dependencies on workspace members, which are locked with their own specifiers,
+    /// - dependencies changed by an override, see [`Dependency::overridden_by`].
+    pub fn unsatisfied_ranges(&self) -> Vec<UnsatisfiedRange<'_>> {
+        let importers = self.workspace_member_ids();
+        let mut unsatisfied = Vec::new();
+        for (pid, _) in self.packages_with_ids() {
+            for dependency in self.package_dependencies(pid) {
+                if dependency.kind.is_peer()
+                    || dependency.overridden_by().is_some()
+                    || dependency.on == self.root_package_id()
+                    || importers.contains(&dependency.on)
+                {
+                    continue;
+                }
+                let Some(version) = self.package(dependency.on).version() else {
+                    continue;
+                };
+                let Some(range) = dependency.svs().and_then(npm_range) else {
+                    continue;
+                };
+                // "*" and "" take whatever is tagged as latest, even a prerelease.
+                if range.trim().is_empty() || range.trim() == "*" {
+                    continue;
+                }
+                let Ok(parsed) = VersionRange::parse(&range) else {
+                    continue;
+                };
+                if !parsed.satisfies(version) {
+                    unsatisfied.push(UnsatisfiedRange {
+                        dependency,
+                        range,
+                        version,
+                    });
+                }
+            }
+        }
+        unsatisfied
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chastefile::graph_builder;
+    use crate::error::Result;
+    use crate::{
+        DependencyBuilder, DependencyKind, OverrideBuilder, OverrideSelector,
+        SourceVersionSpecifier,
+    };
+
+    #[test]
+    fn unsatisfied_ranges() -> Result<()> {
+        let locked = [
+            ("ms", "2.1.3", "^2.1.0"),
+            ("debug", "3.2.7", "^4.3.0"),
+            ("lodash", "4.17.21", "npm:^4.0.0"),
+            ("tinyexec", "0.3.2", "npm:tinyexec@^1.0.0"),
+            ("typescript", "5.9.0-beta", "beta"),
+            ("react", "19.0.0-rc.1", "*"),
+            ("semver", "6.3.1", "^7.0.0"),
+        ];
+        let names: Vec<&str> = ["root"]
+            .into_iter()
+            .chain(locked.iter().map(|(name, _, _)| *name))
+            .collect();
+        let (mut chastefile, pids) = graph_builder(&names, &[], &[], |name, package| match locked
+            .iter()
+            .find(|(n, _, _)| *n == name)
+        {
+            Some((_, version, _)) => package.version(Some(version.to_string())),
+            None => package.name(None),
+        });
+        for (name, _, svs) in locked {
+            let mut dependency =
+                DependencyBuilder::new(DependencyKind::Dependency, pids["root"], pids[name]);
+            dependency.svs(SourceVersionSpecifier::new(svs.to_string())?);
+            chastefile.add_dependency(dependency.build());
+        }
+        chastefile.add_override(
+            OverrideBuilder::new(OverrideSelector::parse("semver")?, "6.3.1".to_string()).build(),
+        );
+        chastefile.match_overrides();
+        let chastefile = chastefile.build()?;
+
+        let unsatisfied = chastefile.unsatisfied_ranges();
+        let mut found: Vec<(&str, &str)> = unsatisfied
+            .iter()
+            .map(|u| {
+                let name = chastefile.package(u.dependency.on).name().unwrap();
+                (name.as_ref(), u.range.as_str())
+            })
+            .collect();
+        found.sort_unstable();
+        assert_eq!(found, [("debug", "^4.3.0"), ("tinyexec", "^1.0.0")]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,358 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use ssri::Algorithm;
+
+use crate::chastefile::Chastefile;
+use crate::package::PackageID;
+use crate::source::PackageSourceType;
+use crate::ProviderMeta;
+
+/// Numbers of packages of each [`PackageSourceType`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SourceCounts {
+    pub npm: usize,
+    pub tarball_url: usize,
+    pub git: usize,
+    /// Packages without a source, like the root package, workspace members and local links.
+    pub none: usize,
+}
+
+/// Numbers of packages reachable from the root package and the workspace members in each way,
+/// as told by [`crate::Reachability`]. A package can be counted in multiple of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ReachabilityCounts {
+    pub prod: usize,
+    pub dev: usize,
+    pub optional: usize,
+    pub dev_optional: usize,
+    pub peer: usize,
+    /// Not reachable at all, e.g. left in the lockfile after a dependency was removed.
+    pub unreachable: usize,
+}
+
+/// Totals for a workspace member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemberStats {
+    pub member: PackageID,
+    /// Packages the member depends on, directly or transitively.
+    pub dependencies: usize,
+    /// Of those, packages that are not only there through devDependencies.
+    pub prod_dependencies: usize,
+}
+
+/// A summary of a lockfile, e.g. to track how its size changes over time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub packages: usize,
+    pub dependencies: usize,
+    pub sources: SourceCounts,
+    pub reachability: ReachabilityCounts,
+    /// Packages by the strongest algorithm of their checksums, strongest first.
+    pub checksum_algorithms: Vec<(Algorithm, usize)>,
+    pub without_checksums: usize,
+    /// Most dependencies to go through from the root package to get to a package.
+    /// Workspace members are one dependency away from the root.
+    pub max_depth: usize,
+    /// Average of the fewest dependencies to go through from the root package,
+    /// for packages other than the root.
+    pub average_depth: f64,
+    /// Packages with the most dependencies, direct and transitive, with how many, most first.
+    pub largest_closures: Vec<(PackageID, usize)>,
+    /// Packages with the most packages depending directly on them, with how many, most first.
+    pub most_dependents: Vec<(PackageID, usize)>,
+    /// Names of packages that are in the lockfile in multiple versions, with how many.
+    pub duplicate_names: Vec<(String, usize)>,
+    pub patched: usize,
+    /// In order of names.
+    pub members: Vec<MemberStats>,
+}
+
+/// How many packages `pid` depends on, directly or transitively.
+fn closure_size(pid: PackageID, dependencies: &HashMap<PackageID, Vec<PackageID>>) -> usize {
+    let mut seen: HashSet<PackageID> = HashSet::from([pid]);
+    let mut q: VecDeque<PackageID> = VecDeque::from([pid]);
+    while let Some(pid) = q.pop_front() {
+        for &on in dependencies.get(&pid).into_iter().flatten() {
+            if seen.insert(on) {
+                q.push_back(on);
+            }
+        }
+    }
+    seen.len() - 1
+}
+
+impl<P: ProviderMeta> Chastefile<P> {
+    /// The `top` largest counts, most first, then in order of names and versions.
+    fn top_counts(
+        &self,
+        mut counts: Vec<(PackageID, usize)>,
+        top: usize,
+    ) -> Vec<(PackageID, usize)> {
+        counts.retain(|(_, count)| *count > 0);
+        counts.sort_unstable_by_key(|&(pid, count)| (Reverse(count), self.sort_key(pid), pid.0));
+        counts.truncate(top);
+        counts
+    }
+
+    /// Counts of packages, and the `top` packages with the largest closures and most dependents.
+    pub fn stats(&self, top: usize) -> Stats {
+        let packages = self.packages_with_ids();
+        let root = self.root_package_id();
+        let members = self.workspace_member_ids();
+
+        let mut dependencies: HashMap<PackageID, Vec<PackageID>> = HashMap::new();
+        let mut dependents: HashMap<PackageID, HashSet<PackageID>> = HashMap::new();
+        for dependency in self.all_dependencies() {
+            dependencies
+                .entry(dependency.from)
+                .or_default()
+                .push(dependency.on);
+            dependents
+                .entry(dependency.on)
+                .or_default()
+                .insert(dependency.from);
+        }
+
+        let mut sources = SourceCounts::default();
+        let mut algorithms: BTreeMap<Algorithm, usize> = BTreeMap::new();
+        let mut without_checksums = 0;
+        let mut patched = 0;
+        for (_, package) in &packages {
+            match package.source_type() {
+                Some(PackageSourceType::Npm) => sources.npm += 1,
+                Some(PackageSourceType::TarballURL) => sources.tarball_url += 1,
+                Some(PackageSourceType::Git) => sources.git += 1,
+                None => sources.none += 1,
+            }
+            match package.checksums() {
+                Some(checksums) => {
+                    *algorithms
+                        .entry(checksums.integrity().pick_algorithm())
+                        .or_default() += 1
+                }
+                None => without_checksums += 1,
+            }
+            if package.derivation_meta().and_then(|d| d.patch()).is_some() {
+                patched += 1;
+            }
+        }
+
+        let mut tops = vec![root];
+        tops.extend(members);
+        let reachabilities = self.reachability(&tops);
+        let mut reachability = ReachabilityCounts {
+            unreachable: packages.len() - reachabilities.len(),
+            ..Default::default()
+        };
+        for r in reachabilities.values() {
+            reachability.prod += usize::from(r.is_prod());
+            reachability.dev += usize::from(r.dev);
+            reachability.optional += usize::from(r.optional);
+            reachability.dev_optional += usize::from(r.dev_optional);
+            reachability.peer += usize::from(r.peer);
+        }
+
+        let mut depths: HashMap<PackageID, usize> = HashMap::from([(root, 0)]);
+        let mut q: VecDeque<PackageID> = VecDeque::from([root]);
+        while let Some(pid) = q.pop_front() {
+            let depth = depths[&pid] + 1;
+            let mut next: Vec<PackageID> = dependencies.get(&pid).cloned().unwrap_or_default();
+            if pid == root {
+                next.extend(members);
+            }
+            for on in next {
+                depths.entry(on).or_insert_with(|| {
+                    q.push_back(on);
+                    depth
+                });
+            }
+        }
+        let max_depth = depths.values().copied().max().unwrap_or(0);
+        let average_depth = match depths.len() {
+            0 | 1 => 0.0,
+            n => depths.values().sum::<usize>() as f64 / (n - 1) as f64,
+        };
+
+        let largest_closures = self.top_counts(
+            packages
+                .iter()
+                .map(|(pid, _)| (*pid, closure_size(*pid, &dependencies)))
+                .collect(),
+            top,
+        );
+        let most_dependents = self.top_counts(
+            dependents
+                .iter()
+                .map(|(pid, dependents)| (*pid, dependents.len()))
+                .collect(),
+            top,
+        );
+        let duplicate_names = self
+            .duplicates()
+            .into_iter()
+            .map(|d| (d.name.to_string(), d.versions.len()))
+            .collect();
+
+        let mut members: Vec<MemberStats> = members
+            .iter()
+            .map(|&member| {
+                let reachability = self.reachability(&[member]);
+                MemberStats {
+                    member,
+                    dependencies: reachability.len() - 1,
+                    prod_dependencies: reachability
+                        .iter()
+                        .filter(|(pid, r)| **pid != member && r.is_prod())
+                        .count(),
+                }
+            })
+            .collect();
+        members.sort_by_key(|m| (self.sort_key(m.member), m.member.0));
+
+        Stats {
+            packages: packages.len(),
+            dependencies: self.all_dependencies().len(),
+            sources,
+            reachability,
+            checksum_algorithms: algorithms.into_iter().collect(),
+            without_checksums,
+            max_depth,
+            average_depth,
+            largest_closures,
+            most_dependents,
+            duplicate_names,
+            patched,
+            members,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ssri::Algorithm;
+
+    use crate::chastefile::graph_builder;
+    use crate::error::Result;
+    use crate::{
+        Checksums, DependencyBuilder, DependencyKind, PackageBuilder, PackageDerivation,
+        PackageDerivationMetaBuilder, PackageName, PackagePatchBuilder, PackageSource,
+        ReachabilityCounts, SourceCounts,
+    };
+
+    #[test]
+    fn stats() -> Result<()> {
+        let (mut chastefile, pids) = graph_builder(
+            &["root", "app", "a", "b", "ms-2.0.0", "ms", "orphan"],
+            &["app"],
+            &[
+                ("app", DependencyKind::Dependency, "b"),
+                ("app", DependencyKind::OptionalDependency, "ms-2.0.0"),
+                ("b", DependencyKind::Dependency, "ms"),
+                ("orphan", DependencyKind::Dependency, "ms"),
+            ],
+            |name, package| {
+                let (version, source, sri) = match name {
+                    "root" => {
+                        package.name(None);
+                        return;
+                    }
+                    "app" => ("1.0.0", None, None),
+                    "a" => ("1.0.0", Some(PackageSource::Npm), Some("sha512-AAAA")),
+                    "b" => (
+                        "1.0.0",
+                        Some(PackageSource::Git {
+                            url: "https://example.com/b.git".to_string(),
+                        }),
+                        None,
+                    ),
+                    "ms-2.0.0" => {
+                        package.name(Some(PackageName::new("ms".to_string()).unwrap()));
+                        ("2.0.0", Some(PackageSource::Npm), Some("sha1-AAAA"))
+                    }
+                    "ms" => ("2.1.3", Some(PackageSource::Npm), Some("sha512-BBBB")),
+                    _ => ("1.0.0", Some(PackageSource::Npm), Some("sha512-CCCC")),
+                };
+                package.version(Some(version.to_string()));
+                if let Some(source) = source {
+                    package.source(source);
+                }
+                if let Some(sri) = sri {
+                    package.checksums(Checksums::Tarball(sri.parse().unwrap()));
+                }
+            },
+        );
+        let [root, app, ms] = [pids["root"], pids["app"], pids["ms"]];
+        // A patched "a", added once "a" has its ID.
+        let mut patched = PackageBuilder::new(
+            Some(PackageName::new("a".to_string())?),
+            Some("1.0.0".to_string()),
+        );
+        patched.derived(
+            PackageDerivationMetaBuilder::new(
+                PackageDerivation::Patch(
+                    PackagePatchBuilder::new("patches/a.patch".to_string()).build()?,
+                ),
+                pids["a"],
+            )
+            .build()?,
+        );
+        let a_patched = chastefile.add_package(patched.build()?)?;
+        for (from, kind, on) in [
+            (root, DependencyKind::DevDependency, a_patched),
+            (a_patched, DependencyKind::Dependency, ms),
+        ] {
+            chastefile.add_dependency(DependencyBuilder::new(kind, from, on).build());
+        }
+        let chastefile = chastefile.build()?;
+        let stats = chastefile.stats(2);
+
+        assert_eq!(stats.packages, 8);
+        assert_eq!(stats.dependencies, 6);
+        assert_eq!(
+            stats.sources,
+            SourceCounts {
+                npm: 4,
+                tarball_url: 0,
+                git: 1,
+                none: 3,
+            }
+        );
+        assert_eq!(
+            stats.reachability,
+            ReachabilityCounts {
+                prod: 5,
+                dev: 1,
+                optional: 1,
+                dev_optional: 2,
+                peer: 0,
+                unreachable: 2,
+            }
+        );
+        assert_eq!(
+            stats.checksum_algorithms,
+            [(Algorithm::Sha512, 3), (Algorithm::Sha1, 1)]
+        );
+        assert_eq!(stats.without_checksums, 4);
+        // root > app > b, root > a > ms
+        assert_eq!(stats.max_depth, 2);
+        // app 1, a 1, b 2, ms@2.0.0 2, ms 2, over 5 packages
+        assert_eq!(stats.average_depth, 8.0 / 5.0);
+        assert_eq!(stats.largest_closures, [(app, 3), (root, 2)]);
+        assert_eq!(stats.most_dependents, [(ms, 3), (a_patched, 1)]);
+        assert_eq!(
+            stats.duplicate_names,
+            [("a".to_string(), 2), ("ms".to_string(), 2)]
+        );
+        assert_eq!(stats.patched, 1);
+        assert_eq!(stats.members.len(), 1);
+        assert_eq!(stats.members[0].member, app);
+        assert_eq!(stats.members[0].dependencies, 3);
+        assert_eq!(stats.members[0].prod_dependencies, 3);
+
+        Ok(())
+    }
+}
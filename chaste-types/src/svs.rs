@@ -49,12 +49,9 @@ enum SourceVersionSpecifierPositions {
     NpmTag {},
     TarballURL {},
     Git {
-        type_prefix_end: usize,
         pre_path_sep_offset: Option<usize>,
     },
-    GitHub {
-        type_prefix_end: usize,
-    },
+    GitHub {},
     Patch {
         type_prefix_end: usize,
         package_name_end: usize,
@@ -96,7 +93,6 @@ fn url(input: &str) -> Option<SourceVersionSpecifierPositions> {
         .map(|(_, (git_prefix, url, _spec_suffix))| {
             if git_prefix.is_some() || url.ends_with(".git") {
                 SourceVersionSpecifierPositions::Git {
-                    type_prefix_end: git_prefix.map(|p| p.len()).unwrap_or(0),
                     pre_path_sep_offset: None,
                 }
             } else {
@@ -128,7 +124,6 @@ fn ssh(input: &str) -> Option<SourceVersionSpecifierPositions> {
                     })
                     .unwrap_or(0);
                 Some(SourceVersionSpecifierPositions::Git {
-                    type_prefix_end: prefix_len,
                     pre_path_sep_offset: Some(
                         prefix_len + host.len() + port.map(|p| p.len() + 1).unwrap_or(0),
                     ),
@@ -152,11 +147,7 @@ fn github(input: &str) -> Option<SourceVersionSpecifierPositions> {
     )
         .parse(input)
         .ok()
-        .map(
-            |(_, (gh_prefix, _, _, _, _))| SourceVersionSpecifierPositions::GitHub {
-                type_prefix_end: gh_prefix.map(|p| p.len()).unwrap_or(0),
-            },
-        )
+        .map(|_| SourceVersionSpecifierPositions::GitHub {})
 }
 
 fn patch(input: &str) -> Option<SourceVersionSpecifierPositions> {
@@ -0,0 +1,104 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use crate::error::Error;
+use crate::package::{Package, PackageBuilder};
+
+/// Problems skipped over while parsing a lockfile.
+///
+/// When strict, which is the default, every problem is returned back to fail the parse.
+/// When lenient, the problems that only affect one package or one dependency are kept here,
+/// and parsing goes on without the bad package or dependency.
+#[derive(Debug)]
+pub struct Warnings<E> {
+    lenient: bool,
+    warnings: Vec<E>,
+}
+
+impl<E> Default for Warnings<E> {
+    fn default() -> Self {
+        Self::strict()
+    }
+}
+
+impl<E> Warnings<E> {
+    pub fn strict() -> Self {
+        Warnings {
+            lenient: false,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn lenient() -> Self {
+        Warnings {
+            lenient: true,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Keep the error as a warning if lenient, otherwise give it back.
+    pub fn skip(&mut self, error: E) -> Result<(), E> {
+        if !self.lenient {
+            return Err(error);
+        }
+        self.warnings.push(error);
+        Ok(())
+    }
+
+    pub fn as_slice(&self) -> &[E] {
+        &self.warnings
+    }
+
+    pub fn into_vec(self) -> Vec<E> {
+        self.warnings
+    }
+}
+
+impl<E: From<Error>> Warnings<E> {
+    /// Build the package. If lenient, a version that is not valid semver is dropped
+    /// instead of failing. `context` is applied to the error, e.g. to tell where the package is.
+    pub fn build_package<F>(&mut self, package: PackageBuilder, context: F) -> Result<Package, E>
+    where
+        F: Fn(E) -> E,
+    {
+        match package.clone().build() {
+            Ok(p) => Ok(p),
+            Err(e @ Error::SemverError(_)) => {
+                self.skip(context(E::from(e)))?;
+                let mut package = package;
+                package.version(None);
+                package.build().map_err(|e| context(E::from(e)))
+            }
+            Err(e) => Err(context(E::from(e))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Warnings;
+    use crate::error::Error;
+    use crate::package::PackageBuilder;
+    use crate::PackageName;
+
+    #[test]
+    fn build_package_with_bad_version() {
+        let package = PackageBuilder::new(
+            Some(PackageName::new("ms".to_string()).unwrap()),
+            Some("not a version".to_string()),
+        );
+
+        let mut strict: Warnings<Error> = Warnings::strict();
+        assert!(strict.build_package(package.clone(), |e| e).is_err());
+
+        let mut lenient: Warnings<Error> = Warnings::lenient();
+        let built = lenient.build_package(package, |e| e).unwrap();
+        assert_eq!(built.name().unwrap(), "ms");
+        assert!(built.version().is_none());
+        assert!(matches!(lenient.as_slice(), [Error::SemverError(_)]));
+    }
+}
@@ -0,0 +1,136 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::collections::{HashMap, HashSet};
+
+use crate::chastefile::Chastefile;
+use crate::dependency::DependencyKind;
+use crate::package::PackageID;
+use crate::ProviderMeta;
+
+impl<P: ProviderMeta> Chastefile<P> {
+    /// Workspace members in an order they can be built or published in, as levels:
+    /// members of a level only depend on members of earlier levels,
+    /// so members of the same level can be built in parallel. Levels are sorted by package id.
+    ///
+    /// Dependencies count however they were specified, e.g. with `workspace:^` or as a pnpm
+    /// `link:`, as long as the lockfile resolves them to the member.
+    /// With `prod_only`, dependencies of kind [`crate::DependencyKind::DevDependency`]
+    /// are not followed, e.g. for publishing already built members.
+    ///
+    /// If members depend on each other in cycles, there is no such order,
+    /// and the cycles are returned instead, like from [`Chastefile::workspace_cycles`].
+    pub fn workspace_build_order(
+        &self,
+        prod_only: bool,
+    ) -> Result<Vec<Vec<PackageID>>, Vec<Vec<PackageID>>> {
+        let members = self.workspace_member_ids();
+        let follow = |kind: DependencyKind| !prod_only || kind.is_prod();
+        // Members each member depends on, and is waiting on to be placed in a level.
+        let mut waiting: HashMap<PackageID, HashSet<PackageID>> =
+            members.iter().map(|&pid| (pid, HashSet::new())).collect();
+        for &member in members {
+            for dependency in self.package_dependencies(member) {
+                if follow(dependency.kind) && members.contains(&dependency.on) {
+                    waiting.get_mut(&member).unwrap().insert(dependency.on);
+                }
+            }
+        }
+
+        let mut levels: Vec<Vec<PackageID>> = Vec::new();
+        while !waiting.is_empty() {
+            let mut level: Vec<PackageID> = waiting
+                .iter()
+                .filter(|(_, on)| on.is_empty())
+                .map(|(pid, _)| *pid)
+                .collect();
+            if level.is_empty() {
+                let left: HashSet<PackageID> = waiting.into_keys().collect();
+                return Err(self.components(|pid| left.contains(&pid), |d| follow(d.kind)));
+            }
+            level.sort_unstable_by_key(|pid| pid.0);
+            for pid in &level {
+                waiting.remove(pid);
+            }
+            for on in waiting.values_mut() {
+                on.retain(|pid| !level.contains(pid));
+            }
+            levels.push(level);
+        }
+        Ok(levels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chastefile::{graph, graph_builder};
+    use crate::error::Result;
+    use crate::{DependencyBuilder, DependencyKind, SourceVersionSpecifier};
+
+    #[test]
+    fn workspace_build_order() -> Result<()> {
+        let (mut chastefile, pids) = graph_builder(
+            &["root", "app", "ui", "utils", "docs", "tslib"],
+            &["app", "ui", "utils", "docs"],
+            &[],
+            |_, _| {},
+        );
+        for (from, kind, svs, on) in [
+            ("root", DependencyKind::DevDependency, "workspace:*", "app"),
+            ("app", DependencyKind::Dependency, "workspace:^", "ui"),
+            ("app", DependencyKind::Dependency, "workspace:^", "utils"),
+            (
+                "ui",
+                DependencyKind::PeerDependency,
+                "link:../utils",
+                "utils",
+            ),
+            ("utils", DependencyKind::Dependency, "^2.6.0", "tslib"),
+            ("docs", DependencyKind::DevDependency, "workspace:", "app"),
+        ] {
+            let mut dependency = DependencyBuilder::new(kind, pids[from], pids[on]);
+            dependency.svs(SourceVersionSpecifier::new(svs.to_string())?);
+            chastefile.add_dependency(dependency.build());
+        }
+        let chastefile = chastefile.build()?;
+
+        assert_eq!(
+            chastefile.workspace_build_order(false),
+            Ok(vec![
+                vec![pids["utils"]],
+                vec![pids["ui"]],
+                vec![pids["app"]],
+                vec![pids["docs"]],
+            ])
+        );
+        let mut first = vec![pids["utils"], pids["docs"]];
+        first.sort_unstable_by_key(|pid| pid.0);
+        assert_eq!(
+            chastefile.workspace_build_order(true),
+            Ok(vec![first, vec![pids["ui"]], vec![pids["app"]]])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn workspace_build_order_cycle() {
+        let (chastefile, pids) = graph(
+            &["root", "a", "b", "c"],
+            &["a", "b", "c"],
+            &[
+                ("a", DependencyKind::Dependency, "b"),
+                ("b", DependencyKind::DevDependency, "a"),
+                ("c", DependencyKind::Dependency, "a"),
+            ],
+        );
+
+        let mut cycle = vec![pids["a"], pids["b"]];
+        cycle.sort_unstable_by_key(|pid| pid.0);
+        assert_eq!(chastefile.workspace_build_order(false), Err(vec![cycle]));
+        assert_eq!(
+            chastefile.workspace_build_order(true),
+            Ok(vec![vec![pids["b"]], vec![pids["a"]], vec![pids["c"]]])
+        );
+    }
+}
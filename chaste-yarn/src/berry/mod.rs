@@ -3,14 +3,15 @@
 
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
+use chaste_types::indented;
 use chaste_types::{
     package_name_str, ssri, Chastefile, ChastefileBuilder, Checksums, DependencyBuilder,
-    DependencyKind, InstallationBuilder, Integrity, ModulePath, PackageBuilder, PackageDerivation,
-    PackageDerivationMetaBuilder, PackageID, PackageName, PackagePatchBuilder, PackageSource,
-    PackageSourceType, PackageVersion, SourceVersionSpecifier, PACKAGE_JSON_FILENAME,
-    ROOT_MODULE_PATH,
+    DependencyKind, FileSource, InstallationBuilder, Integrity, Locate as _, ModulePath,
+    PackageBuilder, PackageDerivation, PackageDerivationMetaBuilder, PackageID, PackageName,
+    PackagePatchBuilder, PackageSource, PackageSourceType, PackageVersion, ProviderMeta,
+    SourceVersionSpecifier, Warnings, PACKAGE_JSON_FILENAME, ROOT_MODULE_PATH,
 };
 
 use itertools::Itertools as _;
@@ -20,10 +21,10 @@ use nom::Parser as _;
 use yarn_lock_parser as yarn;
 
 use crate::berry::types::PackageJson;
-use crate::btree_candidates::Candidates;
+use crate::btree_candidates::{insert_specifiers, Candidates};
 use crate::error::{Error, Result};
-use crate::resolutions::{is_same_svs, Resolutions};
-use crate::{Implem, Meta};
+use crate::resolutions::{is_same_svs, resolution_override, Resolutions};
+use crate::{edit_entries, entry_key, package_versions, workspace_dependency_names, Implem, Meta};
 
 mod mjam;
 mod types;
@@ -76,7 +77,7 @@ where
         &from_entry.descriptors
     });
     let evaluated_svs = overridden_resolution.unwrap_or(descriptor_svs);
-    let mut candidate_entries = Candidates::new(descriptor_name, &descriptor_to_pid)
+    let mut candidate_entries = Candidates::new(descriptor_name, descriptor_to_pid)
         .filter(|((_, d_s), _)| is_same_svs(evaluated_svs, d_s));
     if let Some((_, pid)) = candidate_entries.next() {
         if candidate_entries.next().is_some() {
@@ -92,6 +93,7 @@ where
     )))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn find_peer_pid<'a, S>(
     descriptor: &'a (S, S),
     from_pid: PackageID,
@@ -110,15 +112,14 @@ where
         &from_entry.descriptors
     });
 
-    let candidate_entries =
-        Candidates::new(descriptor_name, &descriptor_to_pid).collect::<Vec<_>>();
+    let candidate_entries = Candidates::new(descriptor_name, descriptor_to_pid).collect::<Vec<_>>();
 
     // If there's just one candidate to consider, it's easy.
     if let [(_, pid)] = *candidate_entries {
         return Ok(Some(*pid));
     }
     // Peer dependencies can be optional or unfulfilled.
-    if candidate_entries.len() == 0 {
+    if candidate_entries.is_empty() {
         return Ok(None);
     }
     // If an SVS is overridden through package.json "resolutions" field,
@@ -205,26 +206,33 @@ where
     Ok(None)
 }
 
-pub(crate) fn resolve<'y, FG>(
+pub(crate) fn resolve<'y, S>(
     yarn_lock: yarn::Lockfile<'y>,
     root_dir: &Path,
-    file_getter: &FG,
+    file_source: &S,
+    warnings: &mut Warnings<Error>,
 ) -> Result<Chastefile<Meta>>
 where
-    FG: Fn(PathBuf) -> Result<String, io::Error>,
+    S: FileSource + ?Sized,
 {
-    let root_package_contents = file_getter(root_dir.join(PACKAGE_JSON_FILENAME))?;
+    let root_package_path = root_dir.join(PACKAGE_JSON_FILENAME);
+    let root_package_contents = file_source
+        .read_to_string(&root_package_path)
+        .map_err(|e| Error::IoInWorkspace(e, root_package_path.clone()))?;
     let root_package_json: PackageJson = serde_json::from_str(&root_package_contents)?;
 
-    let mut resolutions = Resolutions::new();
-    for (rk, rv) in &root_package_json.resolutions {
-        resolutions.insert(rk, rv.as_ref())?;
-    }
-
     let mut chastefile_builder = ChastefileBuilder::new(Meta {
         implem: Implem::Berry,
         lockfile_version: yarn_lock.version,
     });
+
+    let mut resolutions = Resolutions::new();
+    // Indexed the same as in `resolutions`.
+    let mut override_ids = Vec::with_capacity(root_package_json.resolutions.len());
+    for (rk, rv) in root_package_json.resolutions.iter().sorted() {
+        resolutions.insert(rk, rv.as_ref())?;
+        override_ids.push(chastefile_builder.add_override(resolution_override(rk, rv)?));
+    }
     let mut descriptor_to_pid: BTreeMap<(&'y str, &'y str), PackageID> = BTreeMap::new();
     let mut pid_to_entry: HashMap<PackageID, &yarn::Entry> =
         HashMap::with_capacity(yarn_lock.entries.len());
@@ -235,7 +243,13 @@ where
 
     {
         for entry in &yarn_lock.entries {
-            let (pkg, source) = parse_package(entry)?;
+            let (pkg, source) = match parse_package(entry) {
+                Ok(p) => p,
+                Err(e) => {
+                    warnings.skip(e.at([entry_key(entry)]))?;
+                    continue;
+                }
+            };
 
             // For patch: packages, we need to mark the derivation, for which
             // we need the PackageID they're derived from.
@@ -247,13 +261,16 @@ where
                 continue;
             }
 
-            let pid = chastefile_builder.add_package(pkg.build()?)?;
+            let pkg = warnings.build_package(pkg, |e| e.at([entry_key(entry)]))?;
+            let pid = chastefile_builder.add_package(pkg)?;
             pid_to_entry.insert(pid, entry);
-            for (pn, ps) in &entry.descriptors {
-                if descriptor_to_pid.insert((pn, ps), pid).is_some() {
-                    return Err(Error::DuplicateSpecifiers(format!("{pn}@{ps}")));
-                }
-            }
+            insert_specifiers(
+                entry.descriptors.iter().copied(),
+                pid,
+                &mut descriptor_to_pid,
+                warnings,
+                |e| e.at([entry_key(entry)]),
+            )?;
             if let Some(src) = source {
                 package_sources.insert(pid, src);
             }
@@ -281,7 +298,11 @@ where
         let Some((_, patched_pkg_name, patched_pkg_svd_penc, patch_path, _patch_meta)) =
             mjam::patch_descriptor(entry.resolved)
         else {
-            return Err(Error::InvalidResolved(entry.resolved.to_string()));
+            warnings.skip(
+                Error::InvalidResolved(entry.resolved.to_string())
+                    .at([entry_key(entry).as_str(), "resolution"]),
+            )?;
+            continue;
         };
         // "npm%3A0.1.0" -> "npm:0.1.0"
         let patched_pkg_svd =
@@ -299,10 +320,18 @@ where
                     .is_ok()
             });
         let Some((_, &source_pid)) = source_candidates.next() else {
-            return Err(Error::InvalidResolved(entry.resolved.to_string()));
+            warnings.skip(
+                Error::InvalidResolved(entry.resolved.to_string())
+                    .at([entry_key(entry).as_str(), "resolution"]),
+            )?;
+            continue;
         };
         if source_candidates.next().is_some() {
-            return Err(Error::AmbiguousResolved(entry.resolved.to_string()));
+            warnings.skip(
+                Error::AmbiguousResolved(entry.resolved.to_string())
+                    .at([entry_key(entry).as_str(), "resolution"]),
+            )?;
+            continue;
         }
         let patch = PackagePatchBuilder::new(
             patch_path
@@ -314,17 +343,20 @@ where
             PackageDerivationMetaBuilder::new(PackageDerivation::Patch(patch.build()?), source_pid)
                 .build()?,
         );
-        let pid = chastefile_builder.add_package(pkg.build()?)?;
+        let pkg = warnings.build_package(pkg, |e| e.at([entry_key(entry)]))?;
+        let pid = chastefile_builder.add_package(pkg)?;
         pid_to_entry.insert(pid, entry);
-        for (pn, ps) in &entry.descriptors {
-            if descriptor_to_pid.insert((pn, ps), pid).is_some() {
-                return Err(Error::DuplicateSpecifiers(format!("{pn}@{ps}")));
-            }
-        }
+        insert_specifiers(
+            entry.descriptors.iter().copied(),
+            pid,
+            &mut descriptor_to_pid,
+            warnings,
+            |e| e.at([entry_key(entry)]),
+        )?;
     }
 
     let maybe_state_contents =
-        match file_getter(root_dir.join("node_modules").join(".yarn-state.yml")) {
+        match file_source.read_to_string(&root_dir.join("node_modules").join(".yarn-state.yml")) {
             Ok(s) => Some(s),
             Err(e) if e.kind() == io::ErrorKind::NotFound => None,
             Err(e) => return Err(e.into()),
@@ -370,11 +402,19 @@ where
                 Some(true) => DependencyKind::OptionalDependency,
                 Some(false) | None => DependencyKind::Dependency,
             };
-            let Some(dep_pid) =
-                find_dep_pid(dep_descriptor, entry, &resolutions, &descriptor_to_pid)?
-            else {
-                continue;
-            };
+            let dep_pid =
+                match find_dep_pid(dep_descriptor, entry, &resolutions, &descriptor_to_pid) {
+                    Ok(Some(pid)) => pid,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        warnings.skip(e.at([
+                            entry_key(entry).as_str(),
+                            "dependencies",
+                            dep_descriptor.0,
+                        ]))?;
+                        continue;
+                    }
+                };
             let mut dep = DependencyBuilder::new(kind, *from_pid, dep_pid);
             dep_children
                 .get_mut(from_pid)
@@ -387,6 +427,11 @@ where
                 dep.alias_name(PackageName::new(dep_descriptor.0.to_string())?);
             }
             dep.svs(svs);
+            if let Some((index, _)) = resolutions
+                .find_with_index((dep_descriptor.0, dep_descriptor.1), || &entry.descriptors)
+            {
+                dep.overridden_by(override_ids[index]);
+            }
             chastefile_builder.add_dependency(dep.build());
         }
     }
@@ -400,7 +445,7 @@ where
                 Some(true) => DependencyKind::OptionalPeerDependency,
                 Some(false) | None => DependencyKind::PeerDependency,
             };
-            let Some(dep_pid) = find_peer_pid(
+            let dep_pid = match find_peer_pid(
                 dep_descriptor,
                 *from_pid,
                 entry,
@@ -409,9 +454,17 @@ where
                 &pid_to_entry,
                 &dep_children,
                 &package_sources,
-            )?
-            else {
-                continue;
+            ) {
+                Ok(Some(pid)) => pid,
+                Ok(None) => continue,
+                Err(e) => {
+                    warnings.skip(e.at([
+                        entry_key(entry).as_str(),
+                        "peerDependencies",
+                        dep_descriptor.0,
+                    ]))?;
+                    continue;
+                }
             };
             dep_children
                 .get_mut(from_pid)
@@ -425,8 +478,60 @@ where
                 dep.alias_name(PackageName::new(dep_descriptor.0.to_string())?);
             }
             dep.svs(svs);
+            if let Some((index, _)) = resolutions
+                .find_with_index((dep_descriptor.0, dep_descriptor.1), || &entry.descriptors)
+            {
+                dep.overridden_by(override_ids[index]);
+            }
             chastefile_builder.add_dependency(dep.build());
         }
     }
     Ok(chastefile_builder.build()?)
 }
+
+/// Lines of the entry of a workspace member, with only the dependencies named in `names`.
+fn edit_workspace_entry<'c>(lines: &[&'c str], names: &HashSet<&str>) -> Vec<&'c str> {
+    let (body, _) = indented::edit_entries(&lines[1..], 2, |field| {
+        if indented::entry_key(field[0]) != "dependencies" {
+            return Some(field.to_vec());
+        }
+        let (dependencies, kept) = indented::edit_entries(&field[1..], 4, |dependency| {
+            names
+                .contains(indented::entry_key(dependency[0]).as_ref())
+                .then(|| dependency.to_vec())
+        });
+        (kept > 0).then(|| [&field[..1], &dependencies[..]].concat())
+    });
+    [&lines[..1], &body[..]].concat()
+}
+
+/// Write the yarn.lock again with only what is in `pruned`, see [`crate::write_pruned`].
+pub(crate) fn write_pruned<P>(
+    lockfile_contents: &str,
+    yarn_lock: &yarn::Lockfile<'_>,
+    pruned: &Chastefile<P>,
+) -> Result<String>
+where
+    P: ProviderMeta,
+{
+    let package_versions = package_versions(pruned);
+    let workspaces = workspace_dependency_names(pruned);
+    Ok(edit_entries(
+        lockfile_contents,
+        yarn_lock,
+        |entry, lines| {
+            if let Some((_, path)) = entry.resolved.rsplit_once("@workspace:") {
+                let names = workspaces.get(path)?;
+                return Some(edit_workspace_entry(lines, names));
+            }
+            let name = match mjam::parse_source(entry) {
+                Some((name, _)) => name,
+                None => entry.name,
+            };
+            let version = PackageVersion::parse(entry.version).ok()?;
+            package_versions
+                .contains(&(name, &version))
+                .then(|| lines.to_vec())
+        },
+    ))
+}
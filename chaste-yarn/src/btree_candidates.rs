@@ -3,6 +3,10 @@
 
 use std::collections::{btree_map, BTreeMap};
 
+use chaste_types::{PackageID, Warnings};
+
+use crate::error::{Error, Result};
+
 pub struct Candidates<'a, T> {
     first_value: &'a str,
     range: btree_map::Range<'a, (&'a str, &'a str), T>,
@@ -21,12 +25,39 @@ impl<'a, T> Iterator for Candidates<'a, T> {
     type Item = (&'a (&'a str, &'a str), &'a T);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let Some(item) = self.range.next() else {
-            return None;
-        };
+        let item = self.range.next()?;
         if item.0 .0 != self.first_value {
             return None;
         }
         Some(item)
     }
 }
+
+/// Map specifiers of an entry to its package. If a specifier is already taken
+/// by another entry, it's an error located with `at`, or if lenient, the first entry keeps it.
+pub fn insert_specifiers<'a, I, F>(
+    specifiers: I,
+    pid: PackageID,
+    spec_to_pid: &mut BTreeMap<(&'a str, &'a str), PackageID>,
+    warnings: &mut Warnings<Error>,
+    at: F,
+) -> Result<()>
+where
+    I: IntoIterator<Item = (&'a str, &'a str)>,
+    F: Fn(Error) -> Error,
+{
+    for spec in specifiers {
+        match spec_to_pid.entry(spec) {
+            btree_map::Entry::Vacant(v) => {
+                v.insert(pid);
+            }
+            btree_map::Entry::Occupied(_) => {
+                warnings.skip(at(Error::DuplicateSpecifiers(format!(
+                    "{}@{}",
+                    spec.0, spec.1
+                ))))?;
+            }
+        }
+    }
+    Ok(())
+}
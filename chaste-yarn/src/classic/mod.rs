@@ -3,13 +3,14 @@
 
 use std::borrow::Cow;
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
-use std::{io, str};
+use std::path::Path;
+use std::str;
 
 use chaste_types::{
-    ssri, Chastefile, ChastefileBuilder, Checksums, DependencyBuilder, DependencyKind,
-    InstallationBuilder, Integrity, ModulePath, Package, PackageBuilder, PackageID, PackageName,
-    PackageNameBorrowed, PackageSource, PackageVersion, QuirksMode, SourceVersionSpecifier,
+    ssri, Chastefile, ChastefileBuilder, Checksums, DependencyBuilder, DependencyKind, FileSource,
+    InstallationBuilder, Integrity, Locate as _, ModulePath, Override, OverrideBuilder,
+    OverrideSelector, Package, PackageBuilder, PackageID, PackageName, PackageNameBorrowed,
+    PackageSource, PackageVersion, ProviderMeta, QuirksMode, SourceVersionSpecifier, Warnings,
     PACKAGE_JSON_FILENAME, ROOT_MODULE_PATH,
 };
 use globreeks::Globreeks;
@@ -17,12 +18,11 @@ use nom::branch::alt;
 use nom::bytes::complete::{tag, take_while1};
 use nom::combinator::{eof, opt, verify};
 use nom::Parser;
-use walkdir::WalkDir;
 use yarn_lock_parser as yarn;
 
 use crate::classic::types::PackageJson;
 use crate::error::{Error, Result};
-use crate::{Implem, Meta};
+use crate::{edit_entries, entry_key, package_versions, Implem, Meta};
 
 mod types;
 
@@ -119,7 +119,8 @@ fn parse_source<'a>(
     }))
 }
 
-fn parse_package(entry: &yarn::Entry) -> Result<PackageBuilder> {
+/// Name of the package of an entry, which is the aliased one for aliases like "npm:foo@1".
+fn package_name(entry: &yarn::Entry) -> Result<PackageName> {
     let alias_descriptors = entry
         .descriptors
         .iter()
@@ -147,11 +148,15 @@ fn parse_package(entry: &yarn::Entry) -> Result<PackageBuilder> {
             }
         }
     }
-    let name = if let Some(apn) = aliased_package_name {
+    Ok(if let Some(apn) = aliased_package_name {
         apn.to_owned()
     } else {
         PackageName::new(entry.descriptors.first().unwrap().0.to_string())?
-    };
+    })
+}
+
+fn parse_package(entry: &yarn::Entry) -> Result<PackageBuilder> {
+    let name = package_name(entry)?;
     let mut integrity: Integrity = entry.integrity.parse()?;
     let source = if let Some((source, maybe_sha1_hex)) = parse_source(entry, name.as_borrowed())? {
         if let Some(sha1_hex) = maybe_sha1_hex {
@@ -184,6 +189,8 @@ where
     S: AsRef<str>,
 {
     let descriptor = (descriptor.0.as_ref(), descriptor.1.as_ref());
+    // Entries that could not be parsed are missing from `index_to_pid`.
+    let not_found = || Error::DependencyNotFound(format!("{0}@{1}", descriptor.0, descriptor.1));
     if let Some((idx, (_, _))) = member_package_jsons
         .iter()
         .enumerate()
@@ -197,7 +204,10 @@ where
         .enumerate()
         .find(|(_, e)| e.descriptors.contains(&descriptor))
     {
-        return Ok(Some(*index_to_pid.get(&idx).unwrap()));
+        return index_to_pid
+            .get(&idx)
+            .map(|pid| Some(*pid))
+            .ok_or_else(not_found);
     }
     if dep_kind.is_peer() {
         // While peer dependencies have an SVS, it's not necessarily satisfied,
@@ -219,7 +229,10 @@ where
         // This can happen if 2 dependencies have a peer dependency on a package,
         // and one of them is satisfied, but the other is not.
         if candidates.len() == 1 {
-            return Ok(Some(*index_to_pid.get(&candidates[0].0).unwrap()));
+            return index_to_pid
+                .get(&candidates[0].0)
+                .map(|pid| Some(*pid))
+                .ok_or_else(not_found);
         }
 
         // If there are no candidates, assume this is unfulfilled.
@@ -242,16 +255,16 @@ where
                         .iter()
                         .any(|ed| ed.0 == descriptor.0 && ed.1 == alt_svs)
                 }) {
-                    return Ok(Some(*index_to_pid.get(alt_candidate_index).unwrap()));
+                    return index_to_pid
+                        .get(alt_candidate_index)
+                        .map(|pid| Some(*pid))
+                        .ok_or_else(not_found);
                 }
             }
         }
     }
 
-    Err(Error::DependencyNotFound(format!(
-        "{0}@{1}",
-        descriptor.0, descriptor.1
-    )))
+    Err(not_found())
 }
 
 fn pkg_json_to_package<'a>(package_json: &'a PackageJson<'a>) -> Result<Package> {
@@ -267,15 +280,50 @@ fn pkg_json_to_package<'a>(package_json: &'a PackageJson<'a>) -> Result<Package>
     .map_err(Error::ChasteError)
 }
 
-pub(crate) fn resolve<'fp, FG>(
+/// Parse an entry of `"resolutions"`, where the key is a path of package names, like "**/b",
+/// "a/b" for b depended on by a, or "a/**/b" for b anywhere under a.
+fn parse_resolution(key: &str, value: &str) -> Result<Override> {
+    let mut names: Vec<&str> = Vec::new();
+    let mut rest = key;
+    while !rest.is_empty() {
+        // Scoped names have a slash of their own.
+        let end = match rest.starts_with('@') {
+            true => rest.match_indices('/').nth(1).map(|(i, _)| i),
+            false => rest.find('/'),
+        }
+        .unwrap_or(rest.len());
+        names.push(&rest[..end]);
+        rest = rest.get(end + 1..).unwrap_or("");
+    }
+    let invalid = || Error::InvalidResolution(key.to_string());
+    let (name, parents) = names.split_last().ok_or_else(invalid)?;
+    if *name == "**" {
+        return Err(invalid());
+    }
+    let mut override_ = OverrideBuilder::new(OverrideSelector::parse(name)?, value.to_string());
+    match parents {
+        [.., ancestor, "**"] if *ancestor != "**" => {
+            override_.ancestor(OverrideSelector::parse(ancestor)?)
+        }
+        [.., parent] if *parent != "**" => override_.parent(OverrideSelector::parse(parent)?),
+        _ => {}
+    }
+    Ok(override_.build())
+}
+
+pub(crate) fn resolve<S>(
     yarn_lock: yarn::Lockfile<'_>,
     root_dir: &Path,
-    file_getter: &FG,
+    file_source: &S,
+    warnings: &mut Warnings<Error>,
 ) -> Result<Chastefile<Meta>>
 where
-    FG: Fn(PathBuf) -> Result<String, io::Error>,
+    S: FileSource + ?Sized,
 {
-    let root_package_contents = file_getter(root_dir.join(PACKAGE_JSON_FILENAME))?;
+    let root_package_path = root_dir.join(PACKAGE_JSON_FILENAME);
+    let root_package_contents = file_source
+        .read_to_string(&root_package_path)
+        .map_err(|e| Error::IoInWorkspace(e, root_package_path.clone()))?;
     let root_package_json: PackageJson = serde_json::from_str(&root_package_contents)?;
 
     let mut member_package_jsons: Vec<(Cow<'_, str>, PackageJson)> = Vec::new();
@@ -292,12 +340,10 @@ where
     if let Some(workspaces) = &root_package_json.workspaces {
         let dir_globset = Globreeks::new(workspaces.iter())?;
 
-        for de_result in WalkDir::new(root_dir).into_iter() {
-            let de = de_result?;
-            if !de.file_type().is_file() || de.file_name() != PACKAGE_JSON_FILENAME {
+        for absolute_path in file_source.walk_files(root_dir)? {
+            if absolute_path.file_name() != Some(PACKAGE_JSON_FILENAME.as_ref()) {
                 continue;
             }
-            let absolute_path = de.path();
             let relative_workspace_path = absolute_path
                 .parent()
                 .unwrap()
@@ -309,9 +355,9 @@ where
                 continue;
             }
 
-            let absolute_pathbuf = absolute_path.to_path_buf();
-            let member_package_json_contents = file_getter(absolute_pathbuf.clone())
-                .map_err(|e| Error::IoInWorkspace(e, absolute_pathbuf))?;
+            let member_package_json_contents = file_source
+                .read_to_string(&absolute_path)
+                .map_err(|e| Error::IoInWorkspace(e, absolute_path.clone()))?;
             member_package_jsons.push((
                 // must be owned because its lifetime goes out of scope
                 Cow::Owned(relative_workspace_path.to_owned()),
@@ -339,10 +385,17 @@ where
 
     // Now, add everything else.
     for (index, entry) in yarn_lock.entries.iter().enumerate() {
-        let pkg = parse_package(entry)?;
+        let pkg = match parse_package(entry) {
+            Ok(pkg) => pkg,
+            Err(e) => {
+                warnings.skip(e.at([entry_key(entry)]))?;
+                continue;
+            }
+        };
+        let pkg = warnings.build_package(pkg, |e| e.at([entry_key(entry)]))?;
         // When a package is depended on both as a regular npm dependency and via an npm alias,
         // the lockfile duplicates that package. This is specific to v1. Ignore failures and reuse PackageID.
-        let pid = match chastefile_builder.add_package(pkg.build()?) {
+        let pid = match chastefile_builder.add_package(pkg) {
             Ok(pid) => pid,
             Err(chaste_types::Error::DuplicatePackage(pid)) => pid,
             Err(e) => return Err(Error::ChasteError(e)),
@@ -384,7 +437,7 @@ where
                     }
                     k => k,
                 };
-                let Some(dep_pid) = find_dep_pid(
+                let dep_pid = match find_dep_pid(
                     &dep_descriptor,
                     &yarn_lock,
                     &index_to_pid,
@@ -392,9 +445,14 @@ where
                     &member_package_jsons,
                     &mpj_idx_to_pid,
                     kind,
-                )?
-                else {
-                    continue;
+                ) {
+                    Ok(Some(pid)) => pid,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        // Not from the lockfile, but from package.json.
+                        warnings.skip(e)?;
+                        continue;
+                    }
                 };
                 let mut dep = DependencyBuilder::new(kind, member_pid, dep_pid);
                 let svs =
@@ -410,7 +468,10 @@ where
 
     // Finally, dependencies of dependencies.
     for (index, entry) in yarn_lock.entries.iter().enumerate() {
-        let from_pid = index_to_pid.get(&index).unwrap();
+        // Entries that could not be parsed were skipped.
+        let Some(from_pid) = index_to_pid.get(&index) else {
+            continue;
+        };
         // These should be in berry only.
         debug_assert!(
             entry.peer_dependencies.is_empty() && entry.peer_dependencies_meta.is_empty()
@@ -423,7 +484,7 @@ where
             ),
         ] {
             for dep_descriptor in dep_list {
-                let Some(dep_pid) = find_dep_pid(
+                let dep_pid = match find_dep_pid(
                     dep_descriptor,
                     &yarn_lock,
                     &index_to_pid,
@@ -431,9 +492,17 @@ where
                     &member_package_jsons,
                     &mpj_idx_to_pid,
                     dep_kind,
-                )?
-                else {
-                    continue;
+                ) {
+                    Ok(Some(pid)) => pid,
+                    Ok(None) => continue,
+                    Err(e) => {
+                        warnings.skip(e.at([
+                            entry_key(entry).as_str(),
+                            dep_kind.package_json_field(),
+                            dep_descriptor.0,
+                        ]))?;
+                        continue;
+                    }
                 };
                 // devDependencies of non-root packages are not written to the lockfile.
                 // It might be peer and/or optional. But in that case, it got added here
@@ -449,14 +518,46 @@ where
             }
         }
     }
+    for (key, value) in &root_package_json.resolutions {
+        match parse_resolution(key, value) {
+            Ok(override_) => {
+                chastefile_builder.add_override(override_);
+            }
+            Err(e) => warnings.skip(e)?,
+        }
+    }
+    chastefile_builder.match_overrides();
     Ok(chastefile_builder.build()?)
 }
 
+/// Write the yarn.lock again with only what is in `pruned`, see [`crate::write_pruned`].
+pub(crate) fn write_pruned<P>(
+    lockfile_contents: &str,
+    yarn_lock: &yarn::Lockfile<'_>,
+    pruned: &Chastefile<P>,
+) -> Result<String>
+where
+    P: ProviderMeta,
+{
+    let package_versions = package_versions(pruned);
+    Ok(edit_entries(
+        lockfile_contents,
+        yarn_lock,
+        |entry, lines| {
+            let name = package_name(entry).ok()?;
+            let version = PackageVersion::parse(entry.version).ok()?;
+            package_versions
+                .contains(&(name.as_ref(), &version))
+                .then(|| lines.to_vec())
+        },
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use chaste_types::PackageName;
 
-    use super::{is_github_svs, is_registry_url, Result};
+    use super::{is_github_svs, is_registry_url, parse_resolution, Result};
 
     #[test]
     fn github_cvd() -> Result<()> {
@@ -486,4 +587,28 @@ mod tests {
         ));
         Ok(())
     }
+
+    #[test]
+    fn resolution_keys() -> Result<()> {
+        for (key, name, parent) in [
+            ("left-pad", "left-pad", None),
+            ("**/left-pad", "left-pad", None),
+            ("d2/left-pad", "left-pad", Some(("d2", true))),
+            ("d2/**/left-pad", "left-pad", Some(("d2", false))),
+            ("@s/a/@s/b", "@s/b", Some(("@s/a", true))),
+            ("a/**/b/@s/c", "@s/c", Some(("b", true))),
+        ] {
+            let override_ = parse_resolution(key, "1.0.0")?;
+            assert_eq!(override_.selector().name(), name);
+            assert_eq!(
+                override_
+                    .parent()
+                    .map(|p| (p.name().to_string(), override_.is_parent_direct())),
+                parent.map(|(p, direct)| (p.to_string(), direct)),
+            );
+        }
+        assert!(parse_resolution("a/**", "1.0.0").is_err());
+
+        Ok(())
+    }
 }
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
 
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::Deserialize;
 
@@ -22,6 +22,8 @@ pub(crate) struct PackageJson<'a> {
     pub(crate) peer_dependencies_meta: HashMap<Cow<'a, str>, PeerDependencyMeta>,
     #[serde(default)]
     pub(crate) optional_dependencies: HashMap<Cow<'a, str>, Cow<'a, str>>,
+    #[serde(default)]
+    pub(crate) resolutions: BTreeMap<Cow<'a, str>, Cow<'a, str>>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -3,6 +3,7 @@
 
 use std::{io, path, str};
 
+use chaste_types::{ErrorKind, Locate, Located, Position};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -67,6 +68,7 @@ pub enum Error {
     #[cfg(any(feature = "classic", feature = "zpm"))]
     GlobreeksError(#[from] globreeks::Error),
 
+    #[deprecated(note = "workspace members are no longer found by walking directories")]
     #[error("Walkdir error: {0:?}")]
     #[cfg(any(feature = "classic", feature = "zpm"))]
     WalkdirError(#[from] walkdir::Error),
@@ -80,6 +82,77 @@ pub enum Error {
 
     #[error("JSON parsing error: {0:?}")]
     SerdeJsonError(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    At(#[from] Located<Box<Error>>),
+}
+
+impl Error {
+    /// What went wrong, as an [`ErrorKind`] shared with the other implementations.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::DependencyNotFound(_)
+            | Error::StatePackageNotFound(_)
+            | Error::UnrecognizedWorkspaceMember(_) => ErrorKind::DanglingReference,
+            Error::UnknownLockfileVersion(_) => ErrorKind::UnsupportedVersion,
+            Error::AmbiguousResolution(_)
+            | Error::AmbiguousResolved(_)
+            | Error::MissingRoot
+            | Error::ConflictingDescriptors(..)
+            | Error::DuplicateSpecifiers(_) => ErrorKind::Inconsistent,
+            Error::InvalidResolution(_)
+            | Error::InvalidResolved(_)
+            | Error::UnknownFormat
+            | Error::InvalidEntryKey(_)
+            | Error::Utf8Error(_) => ErrorKind::MalformedSyntax,
+            #[cfg(any(feature = "classic", feature = "berry"))]
+            Error::YarnParserError(_) => ErrorKind::MalformedSyntax,
+            #[cfg(any(feature = "classic", feature = "zpm"))]
+            Error::GlobreeksError(_) => ErrorKind::MalformedSyntax,
+            #[cfg(any(feature = "classic", feature = "zpm"))]
+            #[allow(deprecated)]
+            Error::WalkdirError(_) => ErrorKind::Io,
+            #[cfg(feature = "berry")]
+            Error::YarnStateError(_) => ErrorKind::MalformedSyntax,
+            Error::ChasteError(e) => e.kind(),
+            Error::IoError(_) => ErrorKind::Io,
+            Error::IoInWorkspace(e, _) if e.kind() == io::ErrorKind::NotFound => {
+                ErrorKind::MissingWorkspaceFile
+            }
+            Error::IoInWorkspace(..) => ErrorKind::Io,
+            Error::SSRIError(_) => ErrorKind::InvalidChecksum,
+            Error::SerdeJsonError(e) if e.is_io() => ErrorKind::Io,
+            Error::SerdeJsonError(_) => ErrorKind::MalformedSyntax,
+            Error::At(located) => located.source.kind(),
+        }
+    }
+
+    /// Where the syntax error is, if the lockfile could not be deserialized.
+    pub fn position(&self) -> Option<Position> {
+        match self.inner() {
+            Error::SerdeJsonError(e) if e.line() != 0 => Some(Position {
+                line: e.line(),
+                column: e.column(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Locate for Error {
+    fn located(&self) -> Option<&Located<Box<Error>>> {
+        match self {
+            Error::At(located) => Some(located),
+            _ => None,
+        }
+    }
+
+    fn into_located(self) -> Result<Located<Box<Error>>, Error> {
+        match self {
+            Error::At(located) => Ok(located),
+            e => Err(e),
+        }
+    }
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
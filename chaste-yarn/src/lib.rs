@@ -1,10 +1,16 @@
 // SPDX-FileCopyrightText: 2024 The Chaste Authors
 // SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
 
-use std::path::{Path, PathBuf};
-use std::{fs, io, str};
+#[cfg(any(feature = "classic", feature = "berry", feature = "zpm"))]
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::str;
 
-use chaste_types::{Chastefile, LockfileVersion, ProviderMeta};
+#[cfg(any(feature = "classic", feature = "berry"))]
+use chaste_types::indented;
+use chaste_types::{
+    Chastefile, FileSource, LockfileVersion, ProviderMeta, RealFileSource, Warnings,
+};
 use nom::branch::alt;
 use nom::bytes::streaming::tag;
 use nom::character::complete::space0;
@@ -31,6 +37,15 @@ mod zpm;
 
 pub static LOCKFILE_NAME: &str = "yarn.lock";
 
+/// The first descriptor of an entry, which is how its key in yarn.lock starts.
+#[cfg(any(feature = "classic", feature = "berry"))]
+pub(crate) fn entry_key(entry: &yarn::Entry) -> String {
+    match entry.descriptors.first() {
+        Some((name, svs)) => format!("{name}@{svs}"),
+        None => entry.name.to_string(),
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum Implem {
@@ -70,25 +85,67 @@ pub fn parse<P>(root_dir: P) -> Result<Chastefile<Meta>>
 where
     P: AsRef<Path>,
 {
-    let root_dir = root_dir.as_ref();
+    parse_with_source(root_dir, &RealFileSource)
+}
 
-    let lockfile_contents = fs::read_to_string(root_dir.join(LOCKFILE_NAME))?;
-    parse_real(&lockfile_contents, root_dir, &fs::read_to_string)
+/// Like [`parse`], but with all files read from `file_source` instead of the file system.
+pub fn parse_with_source<P, S>(root_dir: P, file_source: &S) -> Result<Chastefile<Meta>>
+where
+    P: AsRef<Path>,
+    S: FileSource + ?Sized,
+{
+    let root_dir = root_dir.as_ref();
+    let lockfile_contents = file_source.read_to_string(&root_dir.join(LOCKFILE_NAME))?;
+    parse_str(&lockfile_contents, root_dir, file_source)
 }
 
-fn parse_real<FG>(
+/// Parse the contents of a yarn.lock. The package.json files of the root package
+/// and workspace members are read from `file_source`, relative to `root_dir`.
+pub fn parse_str<P, S>(
     lockfile_contents: &str,
-    root_dir: &Path,
-    file_getter: &FG,
+    root_dir: P,
+    file_source: &S,
 ) -> Result<Chastefile<Meta>>
 where
-    FG: Fn(PathBuf) -> Result<String, io::Error>,
+    P: AsRef<Path>,
+    S: FileSource + ?Sized,
 {
-    enum Format {
-        Indented,
-        Json,
-    }
-    match preceded(
+    parse_real(
+        lockfile_contents,
+        root_dir.as_ref(),
+        file_source,
+        &mut Warnings::strict(),
+    )
+}
+
+/// Like [`parse_str`], but problems with single packages or dependencies are skipped
+/// and returned alongside, instead of failing the whole parse.
+pub fn parse_str_lenient<P, S>(
+    lockfile_contents: &str,
+    root_dir: P,
+    file_source: &S,
+) -> Result<(Chastefile<Meta>, Vec<Error>)>
+where
+    P: AsRef<Path>,
+    S: FileSource + ?Sized,
+{
+    let mut warnings = Warnings::lenient();
+    let chastefile = parse_real(
+        lockfile_contents,
+        root_dir.as_ref(),
+        file_source,
+        &mut warnings,
+    )?;
+    Ok((chastefile, warnings.into_vec()))
+}
+
+enum Format {
+    Indented,
+    Json,
+}
+
+fn detect_format(lockfile_contents: &str) -> Result<Format> {
+    preceded(
         space0::<&str, ()>,
         alt((
             tag("{").map(|_| Format::Json),
@@ -96,37 +153,153 @@ where
         )),
     )
     .parse(lockfile_contents)
-    {
+    .map(|(_, format)| format)
+    .map_err(|_| Error::UnknownFormat)
+}
+
+fn parse_real<S>(
+    lockfile_contents: &str,
+    root_dir: &Path,
+    file_source: &S,
+    warnings: &mut Warnings<Error>,
+) -> Result<Chastefile<Meta>>
+where
+    S: FileSource + ?Sized,
+{
+    match detect_format(lockfile_contents)? {
         #[cfg(any(feature = "classic", feature = "berry"))]
-        Ok((_, Format::Indented)) => {
-            let yarn_lock: yarn::Lockfile = yarn::parse_str(&lockfile_contents)?;
+        Format::Indented => {
+            let yarn_lock: yarn::Lockfile = yarn::parse_str(lockfile_contents)?;
             match yarn_lock.version {
                 #[cfg(feature = "classic")]
-                1 => classic::resolve(yarn_lock, root_dir, file_getter),
+                1 => classic::resolve(yarn_lock, root_dir, file_source, warnings),
                 #[cfg(feature = "berry")]
-                2..=10 => berry::resolve(yarn_lock, root_dir, file_getter),
+                2..=10 => berry::resolve(yarn_lock, root_dir, file_source, warnings),
                 _ => Err(Error::UnknownLockfileVersion(yarn_lock.version)),
             }
         }
         #[cfg(not(any(feature = "classic", feature = "berry")))]
-        Ok((_, Format::Indented)) => Err(Error::UnknownFormat),
+        Format::Indented => Err(Error::UnknownFormat),
         #[cfg(feature = "zpm")]
-        Ok((_, Format::Json)) => zpm::resolve(lockfile_contents, root_dir, file_getter),
+        Format::Json => zpm::resolve(lockfile_contents, root_dir, file_source, warnings),
         #[cfg(not(feature = "zpm"))]
-        Ok((_, Format::Json)) => Err(Error::UnknownFormat),
+        Format::Json => Err(Error::UnknownFormat),
+    }
+}
 
-        Err(_) => Err(Error::UnknownFormat),
+/// Write the yarn.lock with contents `lockfile_contents` again, with only the entries
+/// of packages in `pruned`, as made with [`Chastefile::prune`] or [`Chastefile::subgraph`]
+/// from the result of parsing the same contents.
+///
+/// Entries of workspace members keep only the dependencies that were not pruned away.
+/// Everything else is kept as it is, with the same formatting.
+pub fn write_pruned<P>(lockfile_contents: &str, pruned: &Chastefile<P>) -> Result<String>
+where
+    P: ProviderMeta,
+{
+    match detect_format(lockfile_contents)? {
+        #[cfg(any(feature = "classic", feature = "berry"))]
+        Format::Indented => {
+            let yarn_lock: yarn::Lockfile = yarn::parse_str(lockfile_contents)?;
+            match yarn_lock.version {
+                #[cfg(feature = "classic")]
+                1 => classic::write_pruned(lockfile_contents, &yarn_lock, pruned),
+                #[cfg(feature = "berry")]
+                2..=10 => berry::write_pruned(lockfile_contents, &yarn_lock, pruned),
+                _ => Err(Error::UnknownLockfileVersion(yarn_lock.version)),
+            }
+        }
+        #[cfg(not(any(feature = "classic", feature = "berry")))]
+        Format::Indented => Err(Error::UnknownFormat),
+        #[cfg(feature = "zpm")]
+        Format::Json => zpm::write_pruned(lockfile_contents, pruned),
+        #[cfg(not(feature = "zpm"))]
+        Format::Json => Err(Error::UnknownFormat),
     }
 }
 
+/// Names and versions of the packages in `chastefile`,
+/// which is what entries of a yarn.lock are kept by.
+#[cfg(any(feature = "classic", feature = "berry", feature = "zpm"))]
+fn package_versions<P>(chastefile: &Chastefile<P>) -> HashSet<(&str, &chaste_types::PackageVersion)>
+where
+    P: ProviderMeta,
+{
+    chastefile
+        .packages()
+        .into_iter()
+        .filter_map(|p| Some((p.name()?.as_ref(), p.version()?)))
+        .collect()
+}
+
+/// Paths of the root package and the workspace members in `chastefile`, with the names
+/// of their dependencies, as in the `workspace:` resolutions of yarn.lock.
+#[cfg(any(feature = "berry", feature = "zpm"))]
+fn workspace_dependency_names<P>(chastefile: &Chastefile<P>) -> HashMap<&str, HashSet<&str>>
+where
+    P: ProviderMeta,
+{
+    let mut workspaces = HashMap::new();
+    for pid in [chastefile.root_package_id()]
+        .iter()
+        .chain(chastefile.workspace_member_ids())
+    {
+        let names: HashSet<&str> = chastefile
+            .declared_dependencies(*pid)
+            .into_iter()
+            .map(|(_, name)| name)
+            .collect();
+        for installation in chastefile.package_installations(*pid) {
+            let path = match installation.path().as_ref() {
+                "" => ".",
+                path => path,
+            };
+            workspaces.insert(path, names.clone());
+        }
+    }
+    workspaces
+}
+
+/// Edit the entries of a yarn.lock in the indented format with `edit`, as in
+/// [`indented::edit_entries`], with the parsed entry if the lines are of one.
+#[cfg(any(feature = "classic", feature = "berry"))]
+fn edit_entries<'c, F>(
+    lockfile_contents: &'c str,
+    yarn_lock: &yarn::Lockfile,
+    mut edit: F,
+) -> String
+where
+    F: FnMut(&yarn::Entry, &[&'c str]) -> Option<Vec<&'c str>>,
+{
+    let entries: HashMap<String, &yarn::Entry> = yarn_lock
+        .entries
+        .iter()
+        .map(|entry| (entry_key(entry), entry))
+        .collect();
+    let (edited, _) = indented::edit_entries(&indented::lines(lockfile_contents), 0, |lines| {
+        let key = indented::entry_key(lines[0]);
+        let first_descriptor = key.split(", ").next().unwrap_or_default();
+        match entries.get(first_descriptor) {
+            Some(entry) => edit(entry, lines),
+            None => Some(lines.to_vec()),
+        }
+    });
+    edited.concat()
+}
+
 #[cfg(feature = "fuzzing")]
-pub fn parse_arbitrary<FG>(
+pub fn parse_arbitrary<S>(
     lockfile_contents: &str,
     root_dir: &Path,
-    file_getter: &FG,
+    file_source: &S,
 ) -> Result<Chastefile<Meta>>
 where
-    FG: Fn(PathBuf) -> Result<String, io::Error>,
+    S: FileSource + ?Sized,
 {
-    parse_real(lockfile_contents, root_dir, file_getter)
+    parse_real(
+        lockfile_contents,
+        root_dir,
+        file_source,
+        &mut Warnings::strict(),
+    )
 }
@@ -9,7 +9,7 @@ use nom::combinator::{map, opt, rest};
 use nom::sequence::{preceded, terminated};
 use nom::{IResult, Parser as _};
 
-use chaste_types::package_name_str;
+use chaste_types::{package_name_str, Override, OverrideBuilder, OverrideSelector, PackageName};
 
 use crate::error::Result;
 use crate::Error;
@@ -20,7 +20,7 @@ pub(crate) struct ResolutionSelector<'a> {
     pub svs: Option<&'a str>,
 }
 
-fn specifier<'a>(input: &'a str) -> IResult<&'a str, &'a str> {
+fn specifier(input: &str) -> IResult<&str, &str> {
     take_till1(|c| c == '/' || c == '@').parse(input)
 }
 
@@ -58,10 +58,28 @@ impl<'a> ResolutionKey<'a> {
     }
 }
 
+fn override_selector(selector: &ResolutionSelector) -> Result<OverrideSelector> {
+    Ok(OverrideSelector::new(
+        PackageName::new(selector.name.to_string())?,
+        selector.svs.map(str::to_string),
+    ))
+}
+
+/// The resolution as an [`Override`], to be exposed on the Chastefile.
+pub(crate) fn resolution_override(key: &str, value: &str) -> Result<Override> {
+    let rk = ResolutionKey::parse(key)?;
+    let mut override_ = OverrideBuilder::new(override_selector(&rk.selector)?, value.to_string());
+    if let Some(parent) = &rk.parent {
+        override_.parent(override_selector(parent)?);
+    }
+    Ok(override_.build())
+}
+
 #[derive(Default)]
 pub(crate) struct Resolutions<'a> {
     /// THESE ARE THE OTHER WAY AROUND. Parent is second.
-    store: BTreeMap<(ResolutionSelector<'a>, Option<ResolutionSelector<'a>>), &'a str>,
+    /// Values are along with the order they were inserted in.
+    store: BTreeMap<(ResolutionSelector<'a>, Option<ResolutionSelector<'a>>), (usize, &'a str)>,
 }
 
 impl<'a> Resolutions<'a> {
@@ -71,12 +89,26 @@ impl<'a> Resolutions<'a> {
 
     pub fn insert(&mut self, key: &'a str, value: &'a str) -> Result<()> {
         let rk = ResolutionKey::parse(key)?;
-        let had_value = self.store.insert((rk.selector, rk.parent), value).is_some();
+        let index = self.store.len();
+        let had_value = self
+            .store
+            .insert((rk.selector, rk.parent), (index, value))
+            .is_some();
         debug_assert!(!had_value, "Duplicate resolution key");
         Ok(())
     }
 
     pub fn find<P>(&self, selector: (&str, &str), parent: P) -> Option<&'a str>
+    where
+        P: Fn() -> &'a [(&'a str, &'a str)],
+    {
+        self.find_with_index(selector, parent)
+            .map(|(_, value)| value)
+    }
+
+    /// Like [`Resolutions::find`], but also with the index of the resolution,
+    /// in the order they were inserted.
+    pub fn find_with_index<P>(&self, selector: (&str, &str), parent: P) -> Option<(usize, &'a str)>
     where
         P: Fn() -> &'a [(&'a str, &'a str)],
     {
@@ -89,7 +121,7 @@ impl<'a> Resolutions<'a> {
                 None,
             )..,
         );
-        for ((sel, key_parent), value) in range {
+        for ((sel, key_parent), &(index, value)) in range {
             if sel.name != selector.0 {
                 break;
             }
@@ -100,9 +132,9 @@ impl<'a> Resolutions<'a> {
                             kp.name == p.0 && kp.svs.is_none_or(|s| is_same_svs(s, p.1))
                         }) =>
                     {
-                        return Some(value)
+                        return Some((index, value))
                     }
-                    None => return Some(value),
+                    None => return Some((index, value)),
                     // Failed the if above
                     Some(_) => {}
                 }
@@ -200,6 +232,7 @@ mod tests {
 
     #[test]
     fn test_parse_resolution_keys() -> Result<()> {
+        #[allow(clippy::type_complexity)]
         fn compare(input: &str, expected: (Option<(&str, Option<&str>)>, (&str, Option<&str>))) {
             assert_eq!(
                 ResolutionKey::parse(input)
@@ -2,17 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
 
 use std::cmp::Ordering;
+use std::fs;
 use std::path::PathBuf;
 use std::sync::LazyLock;
 
 use chaste_types::{
-    Chastefile, Checksums, Dependency, DependencyKind, LockfileVersion, Package, PackageDerivation,
-    PackageID, PackageSourceType, ProviderMeta as _,
+    Chastefile, Checksums, Dependency, DependencyKind, ErrorKind, Locate as _, LockfileVersion,
+    MemoryFileSource, Package,
+    PackageDerivation, PackageID, PackageSourceType, ProviderMeta as _,
 };
 use concat_idents::concat_idents;
 
 use super::Implem::*;
-use super::{parse, Implem, Meta, Result};
+use super::{parse, parse_str, parse_str_lenient, write_pruned, Error, Implem, Meta, Result};
 
 static TEST_WORKSPACES: LazyLock<PathBuf> = LazyLock::new(|| PathBuf::from("test_workspaces"));
 
@@ -264,6 +266,8 @@ test_workspaces!(
             assert_eq!(odd_dep.svs().unwrap(), "^0.1.2");
         }
         assert_eq!(odd_dep.svs().unwrap().aliased_package_name(), None);
+        let odd_override = chastefile.get_override(odd_dep.overridden_by().unwrap());
+        assert_eq!(odd_override.selector().name(), "is-odd");
 
         // Even though the dependency had no alias, real package is nop
         let odd = chastefile.package(odd_dep.on);
@@ -620,6 +624,23 @@ test_workspaces!(
             .collect::<Vec<&str>>();
         assert_eq!(scwm_svss, [if implem == Berry && lv >= 8 { "npm:^1.0.2" } else { "^1.0.2" }]);
 
+        // Which resolution applied is recorded on the dependencies.
+        let [path_dep] = *chastefile.package_dependents(path_pid) else {
+            panic!();
+        };
+        let path_override = chastefile.get_override(path_dep.overridden_by().unwrap());
+        assert_eq!(path_override.selector().name(), "path-to-regexp");
+        assert_eq!(path_override.parent(), None);
+        assert_eq!(path_override.replacement(), "0.1.12");
+        let [scwm_dep] = *chastefile.package_dependents(scwm_pid) else {
+            panic!();
+        };
+        let scwm_override = chastefile.get_override(scwm_dep.overridden_by().unwrap());
+        assert_eq!(scwm_override.selector().name(), "side-channel-weakmap");
+        assert_eq!(scwm_override.parent().unwrap().name(), "side-channel");
+        assert!(scwm_override.is_parent_direct());
+        assert!(chastefile.overrides().len() >= 2);
+
         Ok(())
     }
 );
@@ -817,3 +838,178 @@ test_workspaces!(
         Ok(())
     }
 );
+
+#[test]
+#[cfg(feature = "berry")]
+fn b10_workspace_basic_in_memory() -> Result<()> {
+    let dir = TEST_WORKSPACES.join("b10_workspace_basic");
+    let files: MemoryFileSource = [
+        "package.json",
+        "balls/package.json",
+        "ligma-api/package.json",
+    ]
+    .into_iter()
+    .map(|p| (p, fs::read_to_string(dir.join(p)).unwrap()))
+    .collect();
+    let lockfile_contents = fs::read_to_string(dir.join("yarn.lock"))?;
+    let chastefile = parse_str(&lockfile_contents, "", &files)?;
+    assert_eq!(chastefile.meta().implem, Berry);
+    assert_eq!(chastefile.packages().len(), 4);
+    assert_eq!(chastefile.workspace_member_ids().len(), 2);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "classic")]
+fn c1_basic_lenient() -> Result<()> {
+    let dir = TEST_WORKSPACES.join("c1_basic");
+    let files: MemoryFileSource = [(
+        "package.json",
+        fs::read_to_string(dir.join("package.json"))?,
+    )]
+    .into_iter()
+    .collect();
+    let lockfile_contents = fs::read_to_string(dir.join("yarn.lock"))?;
+    // As if a bad merge lost the kind-of entry, and mangled a version.
+    let (lockfile_contents, _) = lockfile_contents.split_once("\nkind-of@").unwrap();
+    let lockfile_contents = lockfile_contents.replace(r#"version "1.0.0""#, r#"version "one""#);
+
+    let err = parse_str(&lockfile_contents, "", &files).unwrap_err();
+    assert!(matches!(err.inner(), Error::ChasteError(_)));
+
+    let (chastefile, warnings) = parse_str_lenient(&lockfile_contents, "", &files)?;
+    assert_eq!(chastefile.packages().len(), 5);
+    let [semver_warning, dep_warning] = &warnings[..] else {
+        panic!("{warnings:?}");
+    };
+    assert!(matches!(semver_warning.inner(), Error::ChasteError(_)));
+    assert_eq!(semver_warning.key_path().unwrap().keys(), ["is-even@1.0.0"]);
+    assert!(matches!(dep_warning.inner(), Error::DependencyNotFound(d) if d == "kind-of@^3.0.2"));
+    assert_eq!(
+        dep_warning.key_path().unwrap().keys(),
+        ["is-number@^3.0.0", "dependencies", "kind-of"]
+    );
+    let is_even = chastefile
+        .packages()
+        .into_iter()
+        .find(|p| p.name().is_some_and(|n| n == "is-even"))
+        .unwrap();
+    assert!(is_even.version().is_none());
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "classic")]
+fn c1_basic_error_kinds() -> Result<()> {
+    let dir = TEST_WORKSPACES.join("c1_basic");
+    let lockfile_contents = fs::read_to_string(dir.join("yarn.lock"))?;
+
+    let err = parse_str(&lockfile_contents, "", &MemoryFileSource::new()).unwrap_err();
+    assert!(matches!(err, Error::IoInWorkspace(..)));
+    assert_eq!(err.kind(), ErrorKind::MissingWorkspaceFile);
+
+    let files: MemoryFileSource = [(
+        "package.json",
+        fs::read_to_string(dir.join("package.json"))?,
+    )]
+    .into_iter()
+    .collect();
+    let (lockfile_contents, _) = lockfile_contents.split_once("\nkind-of@").unwrap();
+    let err = parse_str(lockfile_contents, "", &files).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::DanglingReference);
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "classic")]
+fn c1_peer_unsatisfied_prune_prod() -> Result<()> {
+    let dir = TEST_WORKSPACES.join("c1_peer_unsatisfied");
+    let lockfile_contents = fs::read_to_string(dir.join("yarn.lock"))?;
+    let chastefile = parse_str(&lockfile_contents, &dir, &chaste_types::RealFileSource)?;
+    let pruned = chastefile.prune(&[], true);
+    assert_eq!(
+        write_pruned(&lockfile_contents, &pruned)?,
+        "# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "berry")]
+fn b10_workspace_basic_prune() -> Result<()> {
+    let dir = TEST_WORKSPACES.join("b10_workspace_basic");
+    let lockfile_contents = fs::read_to_string(dir.join("yarn.lock"))?;
+    let chastefile = parse_str(&lockfile_contents, &dir, &chaste_types::RealFileSource)?;
+    let [ligma_pid] = *chastefile
+        .workspace_member_ids()
+        .iter()
+        .copied()
+        .filter(|pid| chastefile.package(*pid).name().unwrap() == "ligma-api")
+        .collect::<Vec<PackageID>>()
+    else {
+        panic!();
+    };
+    let pruned = chastefile.prune(&[ligma_pid], false);
+    let pruned_contents = write_pruned(&lockfile_contents, &pruned)?;
+    assert_eq!(
+        pruned_contents,
+        r#"# This file is generated by running "yarn install" inside your project.
+# Manual changes might be lost - proceed with caution!
+
+__metadata:
+  version: 10
+  cacheKey: 10
+
+"ligma-api@npm:^1.0.0, ligma-api@workspace:ligma-api":
+  version: 0.0.0-use.local
+  resolution: "ligma-api@workspace:ligma-api"
+  dependencies:
+    long: "npm:^5.0.0"
+  languageName: unknown
+  linkType: soft
+
+"long@npm:^5.0.0":
+  version: 5.2.3
+  resolution: "long@npm:5.2.3"
+  checksum: 10/9167ec6947a825b827c30da169a7384eec6c0c9ec2f0b9c74da2e93d81159bbe39fb09c3f13dae9721d4b807ccfa09797a7dd1012f5d478e3e33ca3c78b608e6
+  languageName: node
+  linkType: hard
+
+"root-workspace-0b6124@workspace:.":
+  version: 0.0.0-use.local
+  resolution: "root-workspace-0b6124@workspace:."
+  languageName: unknown
+  linkType: soft
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "zpm")]
+fn z9_peer_unsatisfied_prune_prod() -> Result<()> {
+    let dir = TEST_WORKSPACES.join("z9_peer_unsatisfied");
+    let lockfile_contents = fs::read_to_string(dir.join("yarn.lock"))?;
+    let chastefile = parse_str(&lockfile_contents, &dir, &chaste_types::RealFileSource)?;
+    let pruned = chastefile.prune(&[], true);
+    assert_eq!(
+        write_pruned(&lockfile_contents, &pruned)?,
+        r#"{
+  "__metadata": {
+    "version": 9
+  },
+  "entries": {}
+}"#
+    );
+
+    Ok(())
+}
@@ -14,11 +14,11 @@ use nom::{IResult, Parser as _};
 
 use crate::error::{Error, Result};
 
-pub fn specifier<'a>(input: &'a str) -> IResult<&'a str, (&'a str, &'a str)> {
+pub fn specifier(input: &str) -> IResult<&str, (&str, &str)> {
     (package_name_str, preceded(tag("@"), is_not(","))).parse(input)
 }
 
-pub fn specifiers<'a>(input: &'a str) -> Result<Vec<(&'a str, &'a str)>> {
+pub fn specifiers(input: &str) -> Result<Vec<(&str, &str)>> {
     terminated(separated_list1(tag(", "), specifier), eof)
         .parse(input)
         .map(|(_, s)| s)
@@ -42,7 +42,7 @@ pub fn resolved_source<'a>(input: &'a str) -> IResult<&'a str, Resolved<'a>> {
                 Resolved::Remote(PackageSource::Git { url: i.to_owned() })
             }),
         ),
-        preceded(tag("workspace:"), map(rest, |i| Resolved::Workspace(i))),
+        preceded(tag("workspace:"), map(rest, Resolved::Workspace)),
         preceded(
             peek(alt((tag("https://"), tag("http://")))),
             map(
@@ -3,50 +3,55 @@
 
 use std::borrow::Cow;
 use std::collections::{BTreeMap, HashMap, HashSet};
-use std::io;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::sync::LazyLock;
 
 use chaste_types::{
     ssri, Chastefile, ChastefileBuilder, Checksums, Dependency, DependencyBuilder, DependencyKind,
-    InstallationBuilder, Integrity, ModulePath, PackageBuilder, PackageDerivation,
-    PackageDerivationMetaBuilder, PackageID, PackageName, PackagePatchBuilder, PackageSource,
-    PackageSourceType, PackageVersion, SourceVersionSpecifier, ROOT_MODULE_PATH,
+    FileSource, InstallationBuilder, Integrity, Locate as _, ModulePath, OverrideID,
+    PackageBuilder, PackageDerivation, PackageDerivationMetaBuilder, PackageID, PackageName,
+    PackagePatchBuilder, PackageSource, PackageSourceType, PackageVersion, ProviderMeta,
+    SourceVersionSpecifier, Warnings, ROOT_MODULE_PATH,
 };
 
 use globreeks::Globreeks;
 use itertools::Itertools as _;
-use walkdir::WalkDir;
+use serde_json::Value;
 use yoke::Yoke;
 
-use crate::btree_candidates::Candidates;
+use crate::btree_candidates::{insert_specifiers, Candidates};
 use crate::error::Result;
-use crate::resolutions::{is_same_svs_zpm, Resolutions};
-use crate::{Error, Implem, Meta};
+use crate::resolutions::{is_same_svs_zpm, resolution_override, Resolutions};
+use crate::{package_versions, workspace_dependency_names, Error, Implem, Meta};
 
 mod mjam;
 mod types;
 
 const PACKAGE_JSON_FILENAME: &str = "package.json";
 
-pub(crate) fn resolve<'y, FG>(
+pub(crate) fn resolve<'y, S>(
     lockfile_contents: &'y str,
     root_dir: &Path,
-    file_getter: &FG,
+    file_source: &S,
+    warnings: &mut Warnings<Error>,
 ) -> Result<Chastefile<Meta>>
 where
-    FG: Fn(PathBuf) -> Result<String, io::Error>,
+    S: FileSource + ?Sized,
 {
     let lockfile: types::Lockfile<'y> = serde_json::from_str(lockfile_contents)?;
     if lockfile.metadata.version != 9 {
         return Err(Error::UnknownLockfileVersion(lockfile.metadata.version));
     }
 
-    let root_package_contents = file_getter(root_dir.join(PACKAGE_JSON_FILENAME))?;
+    let root_package_path = root_dir.join(PACKAGE_JSON_FILENAME);
+    let root_package_contents = file_source
+        .read_to_string(&root_package_path)
+        .map_err(|e| Error::IoInWorkspace(e, root_package_path.clone()))?;
     let root_package_json: types::PackageJson = serde_json::from_str(&root_package_contents)?;
 
     let mut resolutions = Resolutions::new();
     let mut package_builder_copy: HashMap<(&str, &str), PackageBuilder> = HashMap::new();
+    #[allow(clippy::type_complexity)]
     let mut patched_packages: HashMap<(Cow<'_, str>, Cow<'_, str>), (&str, &str)> = HashMap::new();
     for (key, value) in &root_package_json.resolutions {
         resolutions.insert(key, value)?;
@@ -67,18 +72,21 @@ where
         implem: Implem::Zpm,
         lockfile_version: lockfile.metadata.version,
     });
+    // Indexed the same as in `resolutions`, which were inserted in this order.
+    let mut override_ids = Vec::with_capacity(root_package_json.resolutions.len());
+    for (key, value) in &root_package_json.resolutions {
+        override_ids.push(chastefile.add_override(resolution_override(key, value)?));
+    }
 
     let mut member_package_jsons: Vec<(String, Yoke<types::PackageJson, String>)> = Vec::new();
     let mut mpj_idx_to_pid: HashMap<usize, PackageID> = HashMap::new();
     if let Some(workspaces) = &root_package_json.workspaces {
         let dir_globset = Globreeks::new(workspaces.iter())?;
 
-        for de_result in WalkDir::new(root_dir).into_iter() {
-            let de = de_result?;
-            if !de.file_type().is_file() || de.file_name() != PACKAGE_JSON_FILENAME {
+        for absolute_path in file_source.walk_files(root_dir)? {
+            if absolute_path.file_name() != Some(PACKAGE_JSON_FILENAME.as_ref()) {
                 continue;
             }
-            let absolute_path = de.path();
             let relative_workspace_path = absolute_path
                 .parent()
                 .unwrap()
@@ -90,9 +98,9 @@ where
                 continue;
             }
 
-            let absolute_pathbuf = absolute_path.to_path_buf();
-            let member_package_json_contents = file_getter(absolute_pathbuf.clone())
-                .map_err(|e| Error::IoInWorkspace(e, absolute_pathbuf))?;
+            let member_package_json_contents = file_source
+                .read_to_string(&absolute_path)
+                .map_err(|e| Error::IoInWorkspace(e, absolute_path.clone()))?;
             member_package_jsons.push((
                 // must be owned because its lifetime goes out of scope
                 relative_workspace_path.to_owned(),
@@ -156,28 +164,33 @@ where
     let mut package_sources: HashMap<PackageID, PackageSource> =
         HashMap::with_capacity(lockfile.entries.len());
     for (key, entry) in lockfile.entries.iter() {
-        let specifiers = mjam::specifiers(key)?;
-        let (name, resolved) = mjam::resolved(entry.resolution.resolution)?;
-        match resolved {
-            Some(mjam::Resolved::Workspace(path)) => {
-                let Some(pid) = member_package_jsons
-                    .iter()
-                    .enumerate()
-                    .find(|(_, (p, _))| p == path)
-                    .map(|(idx, _)| *mpj_idx_to_pid.get(&idx).unwrap())
-                else {
-                    return Err(Error::UnrecognizedWorkspaceMember(path.to_string()));
-                };
-                for spec in specifiers {
-                    if spec_to_pid.insert(spec, pid).is_some() {
-                        return Err(Error::DuplicateSpecifiers(format!("{}@{}", spec.0, spec.1)));
-                    }
-                }
-                ekey_to_pid.insert(key, pid);
-                pid_to_entry.insert(pid, entry);
+        let parsed = mjam::specifiers(key)
+            .and_then(|specifiers| Ok((specifiers, mjam::resolved(entry.resolution.resolution)?)));
+        let (specifiers, (name, resolved)) = match parsed {
+            Ok(p) => p,
+            Err(e) => {
+                warnings.skip(e.at(["entries", key]))?;
                 continue;
             }
-            _ => {}
+        };
+        if let Some(mjam::Resolved::Workspace(path)) = resolved {
+            let Some(pid) = member_package_jsons
+                .iter()
+                .enumerate()
+                .find(|(_, (p, _))| p == path)
+                .map(|(idx, _)| *mpj_idx_to_pid.get(&idx).unwrap())
+            else {
+                warnings.skip(
+                    Error::UnrecognizedWorkspaceMember(path.to_string()).at(["entries", key]),
+                )?;
+                continue;
+            };
+            insert_specifiers(specifiers, pid, &mut spec_to_pid, warnings, |e| {
+                e.at(["entries", key])
+            })?;
+            ekey_to_pid.insert(key, pid);
+            pid_to_entry.insert(pid, entry);
+            continue;
         }
         let mut pkg = PackageBuilder::new(
             Some(PackageName::new(name.to_owned())?),
@@ -201,12 +214,11 @@ where
                 package_builder_copy.insert((spec.0, spec.1), pkg.clone());
             }
         }
-        let pid = chastefile.add_package(pkg.build()?)?;
-        for spec in specifiers {
-            if spec_to_pid.insert(spec, pid).is_some() {
-                return Err(Error::DuplicateSpecifiers(format!("{}@{}", spec.0, spec.1)));
-            }
-        }
+        let pkg = warnings.build_package(pkg, |e| e.at(["entries", key]))?;
+        let pid = chastefile.add_package(pkg)?;
+        insert_specifiers(specifiers, pid, &mut spec_to_pid, warnings, |e| {
+            e.at(["entries", key])
+        })?;
         ekey_to_pid.insert(key, pid);
         pid_to_entry.insert(pid, entry);
         if let Some(mjam::Resolved::Remote(src)) = resolved {
@@ -217,7 +229,7 @@ where
     for ((patched_name, patched_sv), (patch_path, patch_sv)) in &patched_packages {
         let og_pid = spec_to_pid.get(&(patched_name, patched_sv)).unwrap();
         let mut pkg = package_builder_copy
-            .remove(&(&patched_name, &patched_sv))
+            .remove(&(patched_name, patched_sv))
             .unwrap();
         let patch = PackagePatchBuilder::new(patch_path.to_string()).build()?;
         let deriv_meta =
@@ -234,17 +246,14 @@ where
         .enumerate()
         .map(|(idx, (_, p))| (*mpj_idx_to_pid.get(&idx).unwrap(), p.get()));
     let mut root_done = false;
-    loop {
-        let Some((pid, package_json)) = mpji.next().or_else(|| {
-            if !root_done {
-                root_done = true;
-                Some((root_pid, &root_package_json))
-            } else {
-                None
-            }
-        }) else {
-            break;
-        };
+    while let Some((pid, package_json)) = mpji.next().or_else(|| {
+        if !root_done {
+            root_done = true;
+            Some((root_pid, &root_package_json))
+        } else {
+            None
+        }
+    }) {
         for (kind, dependencies) in [
             (DependencyKind::Dependency, &package_json.dependencies),
             (
@@ -261,17 +270,26 @@ where
             ),
         ] {
             for (dep_name, dep_svs) in dependencies {
-                if let Some(dep) = resolve_dependency(
+                let resolved = resolve_dependency(
                     (dep_name, dep_svs),
                     kind,
-                    &resolutions,
+                    (&resolutions, &override_ids),
                     pid,
                     &[],
                     &spec_to_pid,
                     &dep_children,
                     &package_sources,
                     &pid_to_entry,
-                )? {
+                );
+                // Not from the lockfile, but from package.json.
+                let resolved = match resolved {
+                    Ok(r) => r,
+                    Err(e) => {
+                        warnings.skip(e)?;
+                        None
+                    }
+                };
+                if let Some(dep) = resolved {
                     if !kind.is_peer() {
                         dep_children
                             .get_mut(&pid)
@@ -299,7 +317,10 @@ where
                 ),
                 _ => unreachable!(),
             };
-            let &from_pid = ekey_to_pid.get(key).unwrap();
+            // Entries that could not be parsed were skipped.
+            let Some(&from_pid) = ekey_to_pid.get(key) else {
+                continue;
+            };
             let parent_specifiers = LazyLock::new(|| mjam::specifiers(key).unwrap());
             for (dep_name, dep_svs) in dependencies {
                 let kind = match (kind_, optional_deps.contains(dep_name)) {
@@ -311,17 +332,30 @@ where
                     }
                     _ => unreachable!(),
                 };
-                if let Some(dep) = resolve_dependency(
+                let resolved = match resolve_dependency(
                     (dep_name, dep_svs),
                     kind,
-                    &resolutions,
+                    (&resolutions, &override_ids),
                     from_pid,
                     &parent_specifiers,
                     &spec_to_pid,
                     &dep_children,
                     &package_sources,
                     &pid_to_entry,
-                )? {
+                ) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        warnings.skip(e.at([
+                            "entries",
+                            key,
+                            "resolution",
+                            kind_.package_json_field(),
+                            dep_name,
+                        ]))?;
+                        None
+                    }
+                };
+                if let Some(dep) = resolved {
                     if !kind.is_peer() {
                         dep_children
                             .get_mut(&from_pid)
@@ -339,10 +373,11 @@ where
     chastefile.build().map_err(Error::ChasteError)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn resolve_dependency<'y>(
     (dep_name, dep_svs): (&str, &str),
     kind: DependencyKind,
-    resolutions: &Resolutions,
+    (resolutions, override_ids): (&Resolutions, &[OverrideID]),
     from_pid: PackageID,
     parent_specifiers: &[(&str, &str)],
     spec_to_pid: &BTreeMap<(&str, &str), PackageID>,
@@ -350,7 +385,8 @@ fn resolve_dependency<'y>(
     package_sources: &HashMap<PackageID, PackageSource>,
     pid_to_entry: &HashMap<PackageID, &'y types::Entry<'y>>,
 ) -> Result<Option<Dependency>> {
-    let override_spec = resolutions.find((dep_name, dep_svs), || parent_specifiers);
+    let found_override = resolutions.find_with_index((dep_name, dep_svs), || parent_specifiers);
+    let override_spec = found_override.map(|(_, spec)| spec);
     let evaluated_spec = override_spec.unwrap_or(dep_svs);
     let original_svs = SourceVersionSpecifier::new(dep_svs.to_string())?;
     let override_svs = if let Some(ospec) = override_spec {
@@ -365,7 +401,7 @@ fn resolve_dependency<'y>(
         .unwrap_or(evaluated_spec);
     let candidates = Candidates::new(
         alias.as_ref().map(|n| n.as_ref()).unwrap_or(dep_name),
-        &spec_to_pid,
+        spec_to_pid,
     );
     let Some(pid) = (if kind.is_peer() {
         resolve_peer_dependency(
@@ -401,9 +437,13 @@ fn resolve_dependency<'y>(
         dep.alias_name(PackageName::new(dep_name.to_string())?);
     }
     dep.svs(original_svs);
+    if let Some((index, _)) = found_override {
+        dep.overridden_by(override_ids[index]);
+    }
     Ok(Some(dep.build()))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn resolve_peer_dependency<'y>(
     (_dep_name, dep_svs): (&'y str, &'y str),
     override_spec: Option<&'y str>,
@@ -425,7 +465,7 @@ fn resolve_peer_dependency<'y>(
         return Ok(Some(pid));
     }
     // Peer dependencies can be optional or unfulfilled.
-    if candidate_entries.len() == 0 {
+    if candidate_entries.is_empty() {
         return Ok(None);
     }
     // If an SVS is overridden through package.json "resolutions" field,
@@ -513,3 +553,48 @@ fn resolve_peer_dependency<'y>(
     // "@chastelock/testcase@workspace:. doesn't provide acorn (pf1eb59), requested by @sveltejs/acorn-typescript."
     Ok(None)
 }
+
+/// Write the yarn.lock again with only what is in `pruned`, see [`crate::write_pruned`].
+pub(crate) fn write_pruned<P>(lockfile_contents: &str, pruned: &Chastefile<P>) -> Result<String>
+where
+    P: ProviderMeta,
+{
+    let package_versions = package_versions(pruned);
+    let workspaces = workspace_dependency_names(pruned);
+    let mut lockfile: Value = serde_json::from_str(lockfile_contents)?;
+    if let Some(Value::Object(entries)) = lockfile.get_mut("entries") {
+        entries.retain(|_, entry| {
+            let Some(Value::Object(resolution)) = entry.get_mut("resolution") else {
+                return false;
+            };
+            let Some(resolved) = resolution.get("resolution").and_then(Value::as_str) else {
+                return false;
+            };
+            match mjam::resolved(resolved) {
+                Ok((_, Some(mjam::Resolved::Workspace(path)))) => {
+                    let Some(names) = workspaces.get(path) else {
+                        return false;
+                    };
+                    if let Some(Value::Object(dependencies)) = resolution.get_mut("dependencies") {
+                        dependencies.retain(|name, _| names.contains(name.as_str()));
+                        if dependencies.is_empty() {
+                            resolution.shift_remove("dependencies");
+                        }
+                    }
+                    true
+                }
+                Ok((name, _)) => resolution
+                    .get("version")
+                    .and_then(Value::as_str)
+                    .and_then(|v| PackageVersion::parse(v).ok())
+                    .is_some_and(|version| package_versions.contains(&(name, &version))),
+                Err(_) => false,
+            }
+        });
+    }
+    let mut contents = serde_json::to_string_pretty(&lockfile)?;
+    if lockfile_contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    Ok(contents)
+}
@@ -0,0 +1,84 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+//! Parsing every lockfile in a directory, and finding where they disagree.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use crate::error::Result;
+use crate::{
+    from_root_path_with_implementation, implementations_from_root_path, Chastefile, Implementation,
+    Meta,
+};
+
+/// A package that is not locked to the same versions in all lockfiles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disagreement {
+    pub package_name: String,
+    /// Versions locked by each implementation. Empty if the package is missing from a lockfile.
+    pub versions: Vec<(Implementation, BTreeSet<String>)>,
+}
+
+/// Parse the lockfiles of all implementations found in the directory.
+pub fn all_from_root_path<P>(root_path: P) -> Vec<(Implementation, Result<Chastefile<Meta>>)>
+where
+    P: AsRef<Path>,
+{
+    let root_path = root_path.as_ref();
+    implementations_from_root_path(root_path)
+        .into_iter()
+        .map(|implem| {
+            (
+                implem,
+                from_root_path_with_implementation(root_path, implem),
+            )
+        })
+        .collect()
+}
+
+fn locked_versions(chastefile: &Chastefile<Meta>) -> BTreeMap<String, BTreeSet<String>> {
+    let root_pid = chastefile.root_package_id();
+    let member_pids = chastefile.workspace_member_ids();
+    let mut versions: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for (pid, package) in chastefile.packages_with_ids() {
+        if pid == root_pid || member_pids.contains(&pid) {
+            continue;
+        }
+        let Some(name) = package.name() else {
+            continue;
+        };
+        versions
+            .entry(name.to_string())
+            .or_default()
+            .extend(package.version().map(|v| v.to_string()));
+    }
+    versions
+}
+
+/// Packages whose locked versions differ between the lockfiles,
+/// not counting the root package and workspace members.
+pub fn disagreements(chastefiles: &[(Implementation, Chastefile<Meta>)]) -> Vec<Disagreement> {
+    let locked: Vec<(Implementation, BTreeMap<String, BTreeSet<String>>)> = chastefiles
+        .iter()
+        .map(|(implem, c)| (*implem, locked_versions(c)))
+        .collect();
+    let names: BTreeSet<&String> = locked.iter().flat_map(|(_, l)| l.keys()).collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let versions: Vec<(Implementation, BTreeSet<String>)> = locked
+                .iter()
+                .map(|(implem, l)| (*implem, l.get(name).cloned().unwrap_or_default()))
+                .collect();
+            let first = &versions.first()?.1;
+            if versions.iter().all(|(_, v)| v == first) {
+                return None;
+            }
+            Some(Disagreement {
+                package_name: name.clone(),
+                versions,
+            })
+        })
+        .collect()
+}
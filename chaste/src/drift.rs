@@ -0,0 +1,339 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+//! Finding where the lockfile is out of date with the package.json files it was made from.
+
+use std::fmt;
+use std::path::Path;
+
+use chaste_types::{
+    Chastefile, Dependency, FileSource, PackageID, ProviderMeta, SourceVersionSpecifier,
+};
+
+use crate::error::{Error, Result};
+
+/// Fields of package.json that dependencies are declared in.
+static FIELDS: &[&str] = &[
+    "dependencies",
+    "devDependencies",
+    "optionalDependencies",
+    "peerDependencies",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DriftChange {
+    /// Declared in package.json, but not in the lockfile.
+    Added { declared: String },
+    /// In the lockfile, but no longer declared in package.json.
+    Removed { locked: Option<String> },
+    /// Declared with another specifier than the one in the lockfile.
+    Changed { locked: String, declared: String },
+    /// The locked version does not satisfy the range declared in package.json.
+    Unsatisfied { declared: String, version: String },
+}
+
+/// A dependency of the root package or a workspace member that is not
+/// the same in its package.json and in the lockfile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drift {
+    /// The root package or workspace member whose package.json this is about.
+    pub importer: PackageID,
+    /// Directory of the importer, relative to the root. Empty for the root package.
+    pub importer_path: String,
+    /// Field of package.json that the dependency is, or was, declared in.
+    pub field: &'static str,
+    /// Name of the dependency, as in package.json.
+    pub name: String,
+    pub change: DriftChange,
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.importer_path.is_empty() {
+            write!(f, "{}/", self.importer_path)?;
+        }
+        write!(f, "package.json: {} {:?}", self.field, self.name)?;
+        match &self.change {
+            DriftChange::Added { declared } => {
+                write!(f, " {declared:?} is not in the lockfile")
+            }
+            DriftChange::Removed {
+                locked: Some(locked),
+            } => {
+                write!(f, " {locked:?} is in the lockfile, but no longer declared")
+            }
+            DriftChange::Removed { locked: None } => {
+                write!(f, " is in the lockfile, but no longer declared")
+            }
+            DriftChange::Changed { locked, declared } => {
+                write!(f, " is {declared:?}, but {locked:?} in the lockfile")
+            }
+            DriftChange::Unsatisfied { declared, version } => {
+                write!(f, " {declared:?} is not satisfied by locked {version}")
+            }
+        }
+    }
+}
+
+/// `declared` is as in package.json, `locked` as the implementation put it into the lockfile,
+/// e.g. yarn berry writes "^4.20" from package.json as "npm:^4.20".
+fn is_same_specifier(declared: &str, locked: &str) -> bool {
+    declared == locked
+        || Some(declared) == locked.strip_prefix("npm:")
+        // Patches have parameters added, like "::locator=%40chastelock%2Ftestcase%40workspace%3A."
+        || Some(declared) == locked.rsplit_once("::").map(|(s, _)| s)
+}
+
+fn dependency_name<M>(chastefile: &Chastefile<M>, dependency: &Dependency) -> Option<String>
+where
+    M: ProviderMeta,
+{
+    match dependency.alias_name() {
+        Some(alias) => Some(alias.to_string()),
+        None => chastefile
+            .package(dependency.on)
+            .name()
+            .map(|n| n.to_string()),
+    }
+}
+
+/// Compare the dependencies declared in package.json files of the root package
+/// and every workspace member, read from `file_source` under `root_path`,
+/// with the dependencies of them in the lockfile.
+///
+/// Dependencies in `"peerDependencies"` are only compared if they are in the lockfile,
+/// as not every implementation installs them.
+pub fn check<M, P, S>(
+    chastefile: &Chastefile<M>,
+    root_path: P,
+    file_source: &S,
+) -> Result<Vec<Drift>>
+where
+    M: ProviderMeta,
+    P: AsRef<Path>,
+    S: FileSource + ?Sized,
+{
+    let root_path = root_path.as_ref();
+    let mut importers = vec![(chastefile.root_package_id(), String::new())];
+    for &member_pid in chastefile.workspace_member_ids() {
+        if let Some(installation) = chastefile.package_installations(member_pid).first() {
+            let path = installation.path().as_ref().trim_end_matches('/');
+            importers.push((member_pid, path.to_string()));
+        }
+    }
+
+    let mut drifts = Vec::new();
+    for (importer, importer_path) in importers {
+        let package_json_path = root_path.join(&importer_path).join("package.json");
+        let contents = file_source
+            .read_to_string(&package_json_path)
+            .map_err(|e| Error::IoInWorkspace(e, package_json_path.clone()))?;
+        let package_json: serde_json::Value = serde_json::from_str(&contents)
+            .map_err(|e| Error::PackageJsonError(e, package_json_path.clone()))?;
+        let declared: Vec<(&'static str, &str, &str)> = FIELDS
+            .iter()
+            .filter_map(|&field| Some((field, package_json.get(field)?.as_object()?)))
+            .flat_map(|(field, deps)| {
+                deps.iter()
+                    .filter_map(move |(name, svs)| Some((field, name.as_str(), svs.as_str()?)))
+            })
+            .collect();
+        let locked: Vec<(String, &Dependency)> = chastefile
+            .package_dependencies(importer)
+            .into_iter()
+            .filter_map(|d| Some((dependency_name(chastefile, d)?, d)))
+            .collect();
+
+        // Which locked dependency each declared one is.
+        let mut matched: Vec<Option<usize>> = declared
+            .iter()
+            .map(|&(field, name, _)| {
+                let is_peer = field == "peerDependencies";
+                let candidates = || {
+                    locked
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, (n, d))| n == name && d.kind.is_peer() == is_peer)
+                };
+                candidates()
+                    .find(|(_, (_, d))| d.kind.package_json_field() == field)
+                    .or_else(|| candidates().next())
+                    .map(|(i, _)| i)
+            })
+            .collect();
+        // Dependencies not from npm can be under another name in the lockfile,
+        // that of their package.json, so they are matched by the specifier.
+        for (i, &(_, _, declared_svs)) in declared.iter().enumerate() {
+            if matched[i].is_some() {
+                continue;
+            }
+            matched[i] = locked.iter().enumerate().position(|(l, (n, d))| {
+                !matched.contains(&Some(l))
+                    && !declared.iter().any(|(_, dn, _)| dn == n)
+                    && d.svs()
+                        .is_some_and(|s| is_same_specifier(declared_svs, s.as_ref()))
+            });
+        }
+
+        let mut drift = |field: &'static str, name: &str, change: DriftChange| {
+            drifts.push(Drift {
+                importer,
+                importer_path: importer_path.clone(),
+                field,
+                name: name.to_string(),
+                change,
+            })
+        };
+
+        for (&(field, name, declared_svs), matched) in declared.iter().zip(&matched) {
+            let Some(&(_, dependency)) = matched.map(|i| &locked[i]) else {
+                if field != "peerDependencies" {
+                    let declared = declared_svs.to_string();
+                    drift(field, name, DriftChange::Added { declared });
+                }
+                continue;
+            };
+            // Other importers are depended on with workspace specifiers,
+            // which implementations lock in their own ways.
+            if dependency.on == chastefile.root_package_id()
+                || chastefile.workspace_member_ids().contains(&dependency.on)
+            {
+                continue;
+            }
+            let version = chastefile.package(dependency.on).version();
+            let range = SourceVersionSpecifier::new(declared_svs.to_string())
+                .ok()
+                .filter(|svs| svs.is_npm())
+                .and_then(|svs| svs.npm_range());
+            // Peer dependencies are provided by whatever depends on the importer.
+            if let (Some(range), Some(version), false) = (range, version, dependency.kind.is_peer())
+            {
+                if !range.satisfies(version) {
+                    let change = DriftChange::Unsatisfied {
+                        declared: declared_svs.to_string(),
+                        version: version.to_string(),
+                    };
+                    drift(field, name, change);
+                    continue;
+                }
+            }
+            if let Some(locked_svs) = dependency.svs() {
+                if !is_same_specifier(declared_svs, locked_svs.as_ref()) {
+                    let change = DriftChange::Changed {
+                        locked: locked_svs.as_ref().to_string(),
+                        declared: declared_svs.to_string(),
+                    };
+                    drift(field, name, change);
+                }
+            }
+        }
+
+        for (i, (name, dependency)) in locked.iter().enumerate() {
+            // Some implementations have the same dependency more than once.
+            if !matched.contains(&Some(i)) && !declared.iter().any(|(_, n, _)| n == name) {
+                let locked = dependency.svs().map(|s| s.as_ref().to_string());
+                drift(
+                    dependency.kind.package_json_field(),
+                    name,
+                    DriftChange::Removed { locked },
+                );
+            }
+        }
+    }
+    Ok(drifts)
+}
+
+#[cfg(test)]
+#[cfg(feature = "npm")]
+mod tests {
+    use chaste_types::MemoryFileSource;
+
+    use super::{check, DriftChange};
+    use crate::error::Result;
+    use crate::{from_str_with_implementation, Implementation};
+
+    static LOCKFILE: &str = r#"{
+        "name": "@chastelock/testcase",
+        "lockfileVersion": 3,
+        "packages": {
+            "": {
+                "name": "@chastelock/testcase",
+                "dependencies": { "ms": "^2.1.0", "tiny": "npm:tinyexec@^0.3.0" },
+                "devDependencies": { "debug": "^4.3.0" }
+            },
+            "node_modules/ms": { "version": "2.1.3" },
+            "node_modules/tiny": { "name": "tinyexec", "version": "0.3.2" },
+            "node_modules/debug": { "version": "4.3.4", "dev": true }
+        }
+    }"#;
+
+    fn changes(package_json: &str) -> Result<Vec<(&'static str, String, DriftChange)>> {
+        let files: MemoryFileSource = [("package.json", package_json.to_string())]
+            .into_iter()
+            .collect();
+        let chastefile = from_str_with_implementation(LOCKFILE, Implementation::Npm, "", &files)?;
+        Ok(check(&chastefile, "", &files)?
+            .into_iter()
+            .map(|d| (d.field, d.name, d.change))
+            .collect())
+    }
+
+    #[test]
+    fn in_sync() -> Result<()> {
+        let drifts = changes(
+            r#"{
+                "dependencies": { "ms": "^2.1.0", "tiny": "npm:tinyexec@^0.3.0" },
+                "devDependencies": { "debug": "^4.3.0" }
+            }"#,
+        )?;
+        assert_eq!(drifts, []);
+
+        Ok(())
+    }
+
+    #[test]
+    fn drifted() -> Result<()> {
+        let drifts = changes(
+            r#"{
+                "dependencies": { "ms": "^2.0.0", "tiny": "npm:tinyexec@^1.0.0", "lodash": "^4.0.0" }
+            }"#,
+        )?;
+        assert_eq!(
+            drifts,
+            [
+                (
+                    "dependencies",
+                    "ms".to_string(),
+                    DriftChange::Changed {
+                        locked: "^2.1.0".to_string(),
+                        declared: "^2.0.0".to_string(),
+                    }
+                ),
+                (
+                    "dependencies",
+                    "tiny".to_string(),
+                    DriftChange::Unsatisfied {
+                        declared: "npm:tinyexec@^1.0.0".to_string(),
+                        version: "0.3.2".to_string(),
+                    }
+                ),
+                (
+                    "dependencies",
+                    "lodash".to_string(),
+                    DriftChange::Added {
+                        declared: "^4.0.0".to_string(),
+                    }
+                ),
+                (
+                    "devDependencies",
+                    "debug".to_string(),
+                    DriftChange::Removed {
+                        locked: Some("^4.3.0".to_string()),
+                    }
+                ),
+            ]
+        );
+
+        Ok(())
+    }
+}
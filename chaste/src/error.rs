@@ -1,8 +1,14 @@
 // SPDX-FileCopyrightText: 2024 The Chaste Authors
 // SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
 
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use chaste_types::{Diagnostic, KeyPath, Locate as _, Position, Severity};
 use thiserror::Error;
 
+pub use chaste_types::ErrorKind;
+
 #[derive(Debug, Error)]
 #[non_exhaustive]
 pub enum Error {
@@ -12,9 +18,30 @@ pub enum Error {
     #[error("Multiple lockfiles found in directory: {0:?}")]
     MultipleLockfiles(Vec<crate::Implementation>),
 
+    #[error("The lockfile format was not recognized")]
+    UnrecognizedLockfile,
+
+    #[error("Multiple lockfiles found in directory, of providers: {0:?}")]
+    MultipleProviders(Vec<&'static str>),
+
+    #[error("No lockfile provider named {0:?}")]
+    UnknownProvider(String),
+
+    #[error("Writing pruned lockfiles of {0} is not supported")]
+    PruneUnsupported(&'static str),
+
+    #[error("Lockfile provider error: {1}")]
+    ProviderError(ErrorKind, Box<dyn std::error::Error + Send + Sync>),
+
     #[error("I/O error: {0:?}")]
     IoError(#[from] std::io::Error),
 
+    #[error("I/O error trying to read {1:?}: {0:?}")]
+    IoInWorkspace(std::io::Error, PathBuf),
+
+    #[error("Could not parse {1:?}: {0}")]
+    PackageJsonError(serde_json::Error, PathBuf),
+
     #[error("Chaste core error: {0:?}")]
     CoreError(#[from] chaste_types::Error),
 
@@ -35,4 +62,90 @@ pub enum Error {
     YarnError(#[from] chaste_yarn::Error),
 }
 
+impl Error {
+    /// Classify the error the same way regardless of which implementation it comes from.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::NoLockfile | Error::UnrecognizedLockfile => ErrorKind::NoLockfile,
+            Error::UnknownProvider(_) => ErrorKind::UnknownProvider,
+            Error::MultipleLockfiles(_) | Error::MultipleProviders(_) => {
+                ErrorKind::AmbiguousLockfile
+            }
+            Error::PruneUnsupported(_) => ErrorKind::Unsupported,
+            Error::ProviderError(kind, _) => *kind,
+            Error::IoError(_) => ErrorKind::Io,
+            Error::IoInWorkspace(e, _) if e.kind() == std::io::ErrorKind::NotFound => {
+                ErrorKind::MissingWorkspaceFile
+            }
+            Error::IoInWorkspace(..) => ErrorKind::Io,
+            Error::PackageJsonError(e, _) if e.is_io() => ErrorKind::Io,
+            Error::PackageJsonError(..) => ErrorKind::MalformedSyntax,
+            Error::CoreError(e) => e.kind(),
+            #[cfg(feature = "bun")]
+            Error::BunError(e) => e.kind(),
+            #[cfg(feature = "npm")]
+            Error::NpmError(e) => e.kind(),
+            #[cfg(feature = "pnpm")]
+            Error::PnpmError(e) => e.kind(),
+            #[cfg(any(feature = "yarn-berry", feature = "yarn-classic", feature = "yarn-zpm"))]
+            Error::YarnError(e) => e.kind(),
+        }
+    }
+
+    /// The error of the implementation, if it is one: what went wrong without where,
+    /// the keys in the lockfile leading to where it was found, and where the syntax error is.
+    fn provider_error(&self) -> Option<(&dyn fmt::Display, Option<&KeyPath>, Option<Position>)> {
+        match self {
+            #[cfg(feature = "bun")]
+            Error::BunError(e) => Some((e.inner(), e.key_path(), e.position())),
+            #[cfg(feature = "npm")]
+            Error::NpmError(e) => Some((e.inner(), e.key_path(), e.position())),
+            #[cfg(feature = "pnpm")]
+            Error::PnpmError(e) => Some((e.inner(), e.key_path(), e.position())),
+            #[cfg(any(feature = "yarn-berry", feature = "yarn-classic", feature = "yarn-zpm"))]
+            Error::YarnError(e) => Some((e.inner(), e.key_path(), e.position())),
+            _ => None,
+        }
+    }
+
+    /// Keys in the lockfile leading to where the error was found, if known.
+    pub fn key_path(&self) -> Option<&KeyPath> {
+        self.provider_error().and_then(|(_, key_path, _)| key_path)
+    }
+
+    /// Where the syntax error is, if the lockfile could not be deserialized.
+    pub fn position(&self) -> Option<Position> {
+        self.provider_error().and_then(|(_, _, position)| position)
+    }
+
+    fn message(&self) -> String {
+        match self.provider_error() {
+            Some((inner, _, _)) => inner.to_string(),
+            None => self.to_string(),
+        }
+    }
+
+    /// Describe the error with where it is in the lockfile `file`, whose contents
+    /// are `lockfile_contents`. Returns `None` if the error is not about the lockfile.
+    pub fn diagnostic<P>(&self, file: P, lockfile_contents: &str) -> Option<Diagnostic>
+    where
+        P: AsRef<Path>,
+    {
+        let key_path = self.key_path();
+        let position = self
+            .position()
+            .or_else(|| key_path.and_then(|kp| Position::of_key_path(lockfile_contents, kp)));
+        if position.is_none() && key_path.is_none() {
+            return None;
+        }
+        Some(Diagnostic {
+            severity: Severity::Error,
+            message: self.message(),
+            file: Some(file.as_ref().to_path_buf()),
+            position,
+            key_path: key_path.cloned(),
+        })
+    }
+}
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,282 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+//! Reading files from a git revision, through the local git CLI.
+
+use std::ffi::OsStr;
+use std::io::{self, BufRead as _, BufReader, Read as _, Write as _};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Output, Stdio};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use chaste_types::FileSource;
+
+/// Files of a commit in a git repository.
+///
+/// Paths are relative to the top level of the repository.
+///
+/// Files are read through one `git cat-file --batch` process, started on the first read
+/// and shared by clones.
+#[derive(Debug, Clone)]
+pub struct GitFileSource {
+    repo_path: PathBuf,
+    commit: String,
+    cat_file: Arc<Mutex<Option<CatFile>>>,
+}
+
+/// A commit as listed by [`GitFileSource::log`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    pub hash: String,
+    /// Committer date, in strict ISO 8601 format.
+    pub date: String,
+    pub subject: String,
+}
+
+fn git<I, S>(repo_path: &Path, args: I) -> io::Result<Output>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(args)
+        .output()
+}
+
+fn git_stdout<I, S>(repo_path: &Path, args: I) -> io::Result<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = git(repo_path, args)?;
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// A `git cat-file --batch` process, that objects are asked for one after another.
+#[derive(Debug)]
+struct CatFile {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl CatFile {
+    fn spawn(repo_path: &Path) -> io::Result<Self> {
+        let mut child = Command::new("git")
+            .arg("-C")
+            .arg(repo_path)
+            .args(["cat-file", "--batch"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdin = child.stdin.take();
+        let stdout = child.stdout.take().map(BufReader::new);
+        let Some(stdout) = stdout else {
+            return Err(io::Error::other("git cat-file has no stdout"));
+        };
+        Ok(CatFile {
+            child,
+            stdin,
+            stdout,
+        })
+    }
+
+    /// Type and contents of the object, or None if there is no object named `name`.
+    fn object(&mut self, name: &str) -> io::Result<Option<(String, Vec<u8>)>> {
+        let Some(stdin) = &mut self.stdin else {
+            return Err(io::Error::from(io::ErrorKind::BrokenPipe));
+        };
+        writeln!(stdin, "{name}")?;
+        stdin.flush()?;
+
+        // "<oid> <type> <size>", or "<name> missing", or "<name> ambiguous".
+        let mut header = String::new();
+        if self.stdout.read_line(&mut header)? == 0 {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+        }
+        let mut fields = header.trim_end_matches('\n').rsplitn(3, ' ');
+        let (Some(last), Some(middle)) = (fields.next(), fields.next()) else {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, header));
+        };
+        match last {
+            "missing" => return Ok(None),
+            "ambiguous" => return Err(io::Error::other(header)),
+            _ => {}
+        }
+        let size: usize = last
+            .parse()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, header.clone()))?;
+        // The contents are followed by a line break.
+        let mut contents = vec![0; size + 1];
+        self.stdout.read_exact(&mut contents)?;
+        contents.pop();
+        Ok(Some((middle.to_string(), contents)))
+    }
+}
+
+impl Drop for CatFile {
+    fn drop(&mut self) {
+        // Closing stdin lets git exit, and then it can be waited for.
+        self.stdin = None;
+        let _ = self.child.wait();
+    }
+}
+
+/// Git wants "a/b" where the file system could be given "./a/../a/b".
+fn tree_path(path: &Path) -> String {
+    let mut parts: Vec<&str> = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(p) => parts.push(p.to_str().unwrap_or_default()),
+            Component::ParentDir => {
+                parts.pop();
+            }
+            _ => {}
+        }
+    }
+    parts.join("/")
+}
+
+/// Top level directory of the work tree containing `path`.
+pub fn repository_root<P>(path: P) -> io::Result<PathBuf>
+where
+    P: AsRef<Path>,
+{
+    git_stdout(path.as_ref(), ["rev-parse", "--show-toplevel"])
+        .map(|s| PathBuf::from(s.trim_end_matches('\n')))
+}
+
+impl GitFileSource {
+    /// `revision` can be anything git understands as a commit, e.g. "HEAD~2" or a tag.
+    pub fn new<P>(repo_path: P, revision: &str) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let repo_path = repo_path.as_ref();
+        let commit = git_stdout(
+            repo_path,
+            [
+                "rev-parse",
+                "--verify",
+                "--end-of-options",
+                &format!("{revision}^{{commit}}"),
+            ],
+        )?;
+        Ok(GitFileSource {
+            repo_path: repo_path.to_path_buf(),
+            commit: commit.trim_end().to_string(),
+            cat_file: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Full hash of the commit files are read from.
+    pub fn commit(&self) -> &str {
+        &self.commit
+    }
+
+    /// Commits reachable from `revision` that changed any of `paths`, oldest first.
+    pub fn log<P, I>(repo_path: P, revision: &str, paths: I) -> io::Result<Vec<Commit>>
+    where
+        P: AsRef<Path>,
+        I: IntoIterator,
+        I::Item: AsRef<Path>,
+    {
+        let mut args = vec![
+            "log".to_string(),
+            "--reverse".to_string(),
+            "-z".to_string(),
+            "--format=%H%x1f%cI%x1f%s".to_string(),
+            "--end-of-options".to_string(),
+            revision.to_string(),
+            "--".to_string(),
+        ];
+        args.extend(paths.into_iter().map(|p| tree_path(p.as_ref())));
+        let stdout = git_stdout(repo_path.as_ref(), args)?;
+        Ok(stdout
+            .split('\0')
+            .filter(|e| !e.is_empty())
+            .filter_map(|e| {
+                let mut fields = e.trim_start_matches('\n').splitn(3, '\x1f');
+                Some(Commit {
+                    hash: fields.next()?.to_string(),
+                    date: fields.next()?.to_string(),
+                    subject: fields.next()?.to_string(),
+                })
+            })
+            .collect())
+    }
+}
+
+impl FileSource for GitFileSource {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        let tree_path = tree_path(path);
+        // Object names are given to git cat-file one per line.
+        if tree_path.contains('\n') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{tree_path:?} cannot be read from git with a line break in it"),
+            ));
+        }
+        let mut slot = self.cat_file.lock().unwrap_or_else(PoisonError::into_inner);
+        let cat_file = match &mut *slot {
+            Some(cat_file) => cat_file,
+            none => none.insert(CatFile::spawn(&self.repo_path)?),
+        };
+        let object = match cat_file.object(&format!("{}:{tree_path}", self.commit)) {
+            Ok(object) => object,
+            Err(e) => {
+                // What git writes next might not match what is asked, so start it again next time.
+                *slot = None;
+                return Err(e);
+            }
+        };
+        match object {
+            // The commit was already verified, so the path is what's missing.
+            None => Err(io::Error::from(io::ErrorKind::NotFound)),
+            Some((kind, contents)) if kind == "blob" => String::from_utf8(contents)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Some((kind, _)) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{tree_path:?} is a {kind}, not a file"),
+            )),
+        }
+    }
+
+    fn walk_files(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let tree_dir = tree_path(dir);
+        let mut args = vec![
+            "ls-tree".to_string(),
+            "-r".to_string(),
+            "-z".to_string(),
+            "--name-only".to_string(),
+            self.commit.clone(),
+        ];
+        if !tree_dir.is_empty() {
+            args.push("--".to_string());
+            args.push(tree_dir.clone());
+        }
+        let stdout = git_stdout(&self.repo_path, args)?;
+        // Returned paths are prefixed with the directory as it was passed,
+        // so that callers can strip it back.
+        Ok(stdout
+            .split('\0')
+            .filter(|p| !p.is_empty())
+            .filter_map(|p| {
+                let rel = if tree_dir.is_empty() {
+                    p
+                } else {
+                    p.strip_prefix(&tree_dir)?.strip_prefix('/')?
+                };
+                Some(dir.join(rel))
+            })
+            .collect())
+    }
+}
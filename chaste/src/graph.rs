@@ -0,0 +1,520 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+//! Drawing the dependencies of a lockfile as Graphviz DOT, Mermaid or GraphML.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Write as _;
+
+use chaste_types::{
+    Chastefile, Dependency, DependencyKind, PackageID, PackageSourceType, ProviderMeta,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// [Graphviz DOT](https://graphviz.org/doc/info/lang.html)
+    Dot,
+    /// [Mermaid flowchart](https://mermaid.js.org/syntax/flowchart.html)
+    Mermaid,
+    /// [GraphML](http://graphml.graphdrawing.org/), e.g. for yEd or Gephi
+    GraphML,
+}
+
+impl GraphFormat {
+    pub fn from_name(name: &str) -> Option<GraphFormat> {
+        match name {
+            "dot" => Some(GraphFormat::Dot),
+            "mermaid" => Some(GraphFormat::Mermaid),
+            "graphml" => Some(GraphFormat::GraphML),
+            _ => None,
+        }
+    }
+}
+
+/// Packages and the dependencies between them, to be drawn.
+///
+/// Packages are grouped by the workspace member they are only installed for,
+/// and those used by multiple members or the root package are left out of groups.
+pub struct Graph<'a, P: ProviderMeta> {
+    chastefile: &'a Chastefile<P>,
+    /// In order of names and versions.
+    packages: Vec<PackageID>,
+    /// Where each package is in `packages`.
+    index: HashMap<PackageID, usize>,
+    dependencies: Vec<&'a Dependency>,
+    members: HashMap<PackageID, PackageID>,
+}
+
+fn kind_name(kind: DependencyKind) -> &'static str {
+    match kind {
+        DependencyKind::DevDependency => "dev",
+        DependencyKind::OptionalDependency => "optional",
+        DependencyKind::PeerDependency => "peer",
+        DependencyKind::OptionalPeerDependency => "optional peer",
+        _ => "prod",
+    }
+}
+
+fn source_name(source_type: PackageSourceType) -> &'static str {
+    match source_type {
+        PackageSourceType::Npm => "npm",
+        PackageSourceType::TarballURL => "tarball",
+        PackageSourceType::Git => "git",
+        _ => "other",
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+/// Dependencies of each package.
+type Dependencies<'a> = HashMap<PackageID, Vec<&'a Dependency>>;
+
+fn dependencies_by_package<P: ProviderMeta>(chastefile: &Chastefile<P>) -> Dependencies<'_> {
+    chastefile
+        .packages_with_ids()
+        .into_iter()
+        .map(|(pid, _)| (pid, chastefile.package_dependencies(pid)))
+        .collect()
+}
+
+/// Packages at most `depth` dependencies away from `from`, going through `next`
+/// to the package `to` says each dependency leads to.
+fn within_depth<F>(
+    from: &[PackageID],
+    next: &Dependencies,
+    to: F,
+    depth: Option<usize>,
+) -> HashSet<PackageID>
+where
+    F: Fn(&Dependency) -> PackageID,
+{
+    let mut distances: HashMap<PackageID, usize> = from.iter().map(|&pid| (pid, 0)).collect();
+    let mut q: VecDeque<PackageID> = from.iter().copied().collect();
+    while let Some(pid) = q.pop_front() {
+        let distance = distances[&pid] + 1;
+        if depth.is_some_and(|depth| distance > depth) {
+            continue;
+        }
+        for dependency in next.get(&pid).into_iter().flatten() {
+            distances.entry(to(dependency)).or_insert_with(|| {
+                q.push_back(to(dependency));
+                distance
+            });
+        }
+    }
+    distances.into_keys().collect()
+}
+
+fn members<P: ProviderMeta>(
+    chastefile: &Chastefile<P>,
+    dependencies: &Dependencies,
+) -> HashMap<PackageID, PackageID> {
+    let root = chastefile.root_package_id();
+    let members = chastefile.workspace_member_ids();
+    let mut owners: HashMap<PackageID, HashSet<PackageID>> = HashMap::new();
+    for &top in [root].iter().chain(members) {
+        let mut seen: HashSet<PackageID> = HashSet::from([top]);
+        let mut q: VecDeque<PackageID> = VecDeque::from([top]);
+        while let Some(pid) = q.pop_front() {
+            for dependency in dependencies.get(&pid).into_iter().flatten() {
+                let on = dependency.on;
+                if on != root && !members.contains(&on) && seen.insert(on) {
+                    owners.entry(on).or_default().insert(top);
+                    q.push_back(on);
+                }
+            }
+        }
+    }
+    owners
+        .into_iter()
+        .filter_map(
+            |(pid, owners)| match owners.into_iter().collect::<Vec<_>>()[..] {
+                [owner] if owner != root => Some((pid, owner)),
+                _ => None,
+            },
+        )
+        .chain(members.iter().map(|&m| (m, m)))
+        .collect()
+}
+
+impl<'a, P: ProviderMeta> Graph<'a, P> {
+    fn with_packages(
+        chastefile: &'a Chastefile<P>,
+        dependencies: &Dependencies<'a>,
+        packages: HashSet<PackageID>,
+    ) -> Self {
+        let mut packages: Vec<PackageID> = packages.into_iter().collect();
+        packages.sort_by_cached_key(|&pid| {
+            let package = chastefile.package(pid);
+            (
+                package.name().map(|n| n.to_string()),
+                package.version().cloned(),
+                chastefile
+                    .package_installations(pid)
+                    .first()
+                    .map(|i| i.path().as_ref().to_string()),
+            )
+        });
+        let index: HashMap<PackageID, usize> = packages
+            .iter()
+            .enumerate()
+            .map(|(i, &pid)| (pid, i))
+            .collect();
+        let mut shown: Vec<&Dependency> = packages
+            .iter()
+            .flat_map(|pid| dependencies.get(pid).into_iter().flatten().copied())
+            .filter(|d| index.contains_key(&d.on))
+            .collect();
+        shown.sort_by_key(|d| (index[&d.from], index[&d.on], kind_name(d.kind)));
+        Self {
+            chastefile,
+            members: members(chastefile, dependencies),
+            packages,
+            index,
+            dependencies: shown,
+        }
+    }
+
+    /// All packages, or only those at most `depth` dependencies
+    /// from the root package or a workspace member.
+    pub fn new(chastefile: &'a Chastefile<P>, depth: Option<usize>) -> Self {
+        let dependencies = dependencies_by_package(chastefile);
+        let packages: HashSet<PackageID> = match depth {
+            Some(_) => {
+                let mut tops = vec![chastefile.root_package_id()];
+                tops.extend(chastefile.workspace_member_ids());
+                within_depth(&tops, &dependencies, |d| d.on, depth)
+            }
+            None => chastefile
+                .packages_with_ids()
+                .into_iter()
+                .map(|(pid, _)| pid)
+                .collect(),
+        };
+        Self::with_packages(chastefile, &dependencies, packages)
+    }
+
+    /// Only `focus`, the packages it depends on, and the packages that depend on it,
+    /// directly or transitively, at most `depth` dependencies away.
+    pub fn around(
+        chastefile: &'a Chastefile<P>,
+        focus: &[PackageID],
+        depth: Option<usize>,
+    ) -> Self {
+        let dependencies = dependencies_by_package(chastefile);
+        let mut dependents: Dependencies = HashMap::new();
+        for dependency in dependencies.values().flatten() {
+            dependents
+                .entry(dependency.on)
+                .or_default()
+                .push(dependency);
+        }
+        let mut packages = within_depth(focus, &dependencies, |d| d.on, depth);
+        packages.extend(within_depth(focus, &dependents, |d| d.from, depth));
+        Self::with_packages(chastefile, &dependencies, packages)
+    }
+
+    fn label(&self, pid: PackageID) -> (String, Option<String>) {
+        let package = self.chastefile.package(pid);
+        let name = match package.name() {
+            Some(name) => name.to_string(),
+            None if pid == self.chastefile.root_package_id() => "[root]".to_string(),
+            None => "[unnamed]".to_string(),
+        };
+        (name, package.version().map(|v| v.to_string()))
+    }
+
+    /// Groups of packages for each workspace member, and packages that are in none.
+    fn groups(&self) -> (Vec<(PackageID, Vec<usize>)>, Vec<usize>) {
+        let mut groups: Vec<(PackageID, Vec<usize>)> = Vec::new();
+        let mut ungrouped = Vec::new();
+        for (i, pid) in self.packages.iter().enumerate() {
+            match self.members.get(pid) {
+                Some(&member) => match groups.iter_mut().find(|(m, _)| *m == member) {
+                    Some((_, group)) => group.push(i),
+                    None => groups.push((member, vec![i])),
+                },
+                None => ungrouped.push(i),
+            }
+        }
+        (groups, ungrouped)
+    }
+
+    fn index(&self, pid: PackageID) -> usize {
+        self.index[&pid]
+    }
+
+    fn dot_node(&self, out: &mut String, i: usize, indent: &str) {
+        let pid = self.packages[i];
+        let (name, version) = self.label(pid);
+        let mut attributes = vec![
+            match &version {
+                Some(version) => {
+                    format!("label=\"{}\\n{}\"", escape_dot(&name), escape_dot(version))
+                }
+                None => format!("label=\"{}\"", escape_dot(&name)),
+            },
+            format!("name=\"{}\"", escape_dot(&name)),
+        ];
+        if let Some(version) = &version {
+            attributes.push(format!("version=\"{}\"", escape_dot(version)));
+        }
+        if let Some(source_type) = self.chastefile.package(pid).source_type() {
+            attributes.push(format!("source=\"{}\"", source_name(source_type)));
+            match source_type {
+                PackageSourceType::TarballURL => attributes.push("shape=folder".to_string()),
+                PackageSourceType::Git => attributes.push("shape=hexagon".to_string()),
+                _ => {}
+            }
+        }
+        let _ = writeln!(out, "{indent}p{i} [{}];", attributes.join(", "));
+    }
+
+    fn to_dot(&self) -> String {
+        let mut out = String::from("digraph dependencies {\n  rankdir=LR;\n  node [shape=box];\n");
+        let (groups, ungrouped) = self.groups();
+        for (g, (member, group)) in groups.iter().enumerate() {
+            let _ = writeln!(out, "  subgraph cluster_{g} {{");
+            let _ = writeln!(out, "    label=\"{}\";", escape_dot(&self.label(*member).0));
+            for &i in group {
+                self.dot_node(&mut out, i, "    ");
+            }
+            out.push_str("  }\n");
+        }
+        for i in ungrouped {
+            self.dot_node(&mut out, i, "  ");
+        }
+        for dependency in &self.dependencies {
+            let mut attributes = vec![format!("kind=\"{}\"", kind_name(dependency.kind))];
+            match dependency.kind {
+                DependencyKind::DevDependency => attributes.push("style=dashed".to_string()),
+                DependencyKind::OptionalDependency => attributes.push("style=dotted".to_string()),
+                DependencyKind::PeerDependency => attributes.push("arrowhead=empty".to_string()),
+                DependencyKind::OptionalPeerDependency => {
+                    attributes.push("style=dotted".to_string());
+                    attributes.push("arrowhead=empty".to_string());
+                }
+                _ => {}
+            }
+            let _ = writeln!(
+                out,
+                "  p{} -> p{} [{}];",
+                self.index(dependency.from),
+                self.index(dependency.on),
+                attributes.join(", ")
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn mermaid_node(&self, out: &mut String, i: usize, indent: &str) {
+        let pid = self.packages[i];
+        let label = match self.label(pid) {
+            (name, Some(version)) => format!("{name}@{version}"),
+            (name, None) => name,
+        };
+        let class = match self.chastefile.package(pid).source_type() {
+            Some(PackageSourceType::TarballURL) => ":::tarball",
+            Some(PackageSourceType::Git) => ":::git",
+            _ => "",
+        };
+        let _ = writeln!(out, "{indent}p{i}[\"{}\"]{class}", escape_mermaid(&label));
+    }
+
+    fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart LR\n");
+        out.push_str("  classDef tarball stroke-dasharray: 6 3\n");
+        out.push_str("  classDef git stroke-dasharray: 2 2\n");
+        let (groups, ungrouped) = self.groups();
+        for (g, (member, group)) in groups.iter().enumerate() {
+            let name = self.label(*member).0;
+            let _ = writeln!(out, "  subgraph m{g} [\"{}\"]", escape_mermaid(&name));
+            for &i in group {
+                self.mermaid_node(&mut out, i, "    ");
+            }
+            out.push_str("  end\n");
+        }
+        for i in ungrouped {
+            self.mermaid_node(&mut out, i, "  ");
+        }
+        for dependency in &self.dependencies {
+            let arrow = match dependency.kind {
+                DependencyKind::Dependency => "-->".to_string(),
+                DependencyKind::PeerDependency => "-->|peer|".to_string(),
+                kind => format!("-.->|{}|", kind_name(kind)),
+            };
+            let _ = writeln!(
+                out,
+                "  p{} {arrow} p{}",
+                self.index(dependency.from),
+                self.index(dependency.on)
+            );
+        }
+        out
+    }
+
+    fn to_graphml(&self) -> String {
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"name\" for=\"node\" attr.name=\"name\" attr.type=\"string\"/>\n",
+            "  <key id=\"version\" for=\"node\" attr.name=\"version\" attr.type=\"string\"/>\n",
+            "  <key id=\"source\" for=\"node\" attr.name=\"source\" attr.type=\"string\"/>\n",
+            "  <key id=\"member\" for=\"node\" attr.name=\"member\" attr.type=\"string\"/>\n",
+            "  <key id=\"kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n",
+            "  <key id=\"specifier\" for=\"edge\" attr.name=\"specifier\" attr.type=\"string\"/>\n",
+            "  <graph id=\"dependencies\" edgedefault=\"directed\">\n",
+        ));
+        for (i, &pid) in self.packages.iter().enumerate() {
+            let (name, version) = self.label(pid);
+            let mut data = vec![("name", name)];
+            data.extend(version.map(|v| ("version", v)));
+            if let Some(source_type) = self.chastefile.package(pid).source_type() {
+                data.push(("source", source_name(source_type).to_string()));
+            }
+            if let Some(&member) = self.members.get(&pid) {
+                data.push(("member", self.label(member).0));
+            }
+            let _ = writeln!(out, "    <node id=\"p{i}\">");
+            for (key, value) in data {
+                let _ = writeln!(
+                    out,
+                    "      <data key=\"{key}\">{}</data>",
+                    escape_xml(&value)
+                );
+            }
+            out.push_str("    </node>\n");
+        }
+        for dependency in &self.dependencies {
+            let _ = writeln!(
+                out,
+                "    <edge source=\"p{}\" target=\"p{}\">",
+                self.index(dependency.from),
+                self.index(dependency.on)
+            );
+            let _ = writeln!(
+                out,
+                "      <data key=\"kind\">{}</data>",
+                kind_name(dependency.kind)
+            );
+            if let Some(svs) = dependency.svs() {
+                let _ = writeln!(
+                    out,
+                    "      <data key=\"specifier\">{}</data>",
+                    escape_xml(svs.as_ref())
+                );
+            }
+            out.push_str("    </edge>\n");
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Mermaid => self.to_mermaid(),
+            GraphFormat::GraphML => self.to_graphml(),
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "npm")]
+mod tests {
+    use chaste_types::Chastefile;
+
+    use super::{Graph, GraphFormat};
+    use crate::error::Result;
+    use crate::npm::{parse, Meta};
+
+    fn test_workspace(name: &str) -> Result<Chastefile<Meta>> {
+        Ok(parse(format!("../chaste-npm/test_workspaces/{name}"))?)
+    }
+
+    #[test]
+    fn dot() -> Result<()> {
+        let chastefile = test_workspace("v3_workspace_basic")?;
+        assert_eq!(
+            Graph::new(&chastefile, None).render(GraphFormat::Dot),
+            r#"digraph dependencies {
+  rankdir=LR;
+  node [shape=box];
+  subgraph cluster_0 {
+    label="@chastelock/balls";
+    p0 [label="@chastelock/balls\n1.0.0", name="@chastelock/balls", version="1.0.0"];
+  }
+  subgraph cluster_1 {
+    label="ligma-api";
+    p1 [label="ligma-api\n1.0.0", name="ligma-api", version="1.0.0"];
+    p2 [label="long\n5.2.3", name="long", version="5.2.3", source="npm"];
+  }
+  p3 [label="v3_workspace_basic", name="v3_workspace_basic"];
+  p0 -> p1 [kind="prod"];
+  p1 -> p2 [kind="prod"];
+}
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn mermaid_around() -> Result<()> {
+        let chastefile = test_workspace("v3_workspace_basic")?;
+        let ligma_pid = chastefile
+            .workspace_member_ids()
+            .iter()
+            .copied()
+            .find(|pid| chastefile.package(*pid).name().unwrap() == "ligma-api")
+            .unwrap();
+        assert_eq!(
+            Graph::around(&chastefile, &[ligma_pid], Some(1)).render(GraphFormat::Mermaid),
+            r#"flowchart LR
+  classDef tarball stroke-dasharray: 6 3
+  classDef git stroke-dasharray: 2 2
+  subgraph m0 ["@chastelock/balls"]
+    p0["@chastelock/balls@1.0.0"]
+  end
+  subgraph m1 ["ligma-api"]
+    p1["ligma-api@1.0.0"]
+    p2["long@5.2.3"]
+  end
+  p0 --> p1
+  p1 --> p2
+"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn graphml_depth() -> Result<()> {
+        let chastefile = test_workspace("v3_git_url")?;
+        let graphml = Graph::new(&chastefile, None).render(GraphFormat::GraphML);
+        assert_eq!(graphml.matches("<node ").count(), 9);
+        let graphml = Graph::new(&chastefile, Some(1)).render(GraphFormat::GraphML);
+        assert!(graphml.contains("<data key=\"source\">git</data>"));
+        assert!(graphml.contains("<data key=\"kind\">dev</data>"));
+        assert!(graphml.contains(
+            "<data key=\"specifier\">https://github.com/isaacs/minimatch.git#v10.0.1</data>"
+        ));
+        assert_eq!(graphml.matches("<node ").count(), 3);
+        let graphml = Graph::new(&chastefile, Some(0)).render(GraphFormat::GraphML);
+        assert_eq!(graphml.matches("<node ").count(), 1);
+        assert_eq!(graphml.matches("<edge ").count(), 0);
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 // SPDX-FileCopyrightText: 2024 The Chaste Authors
 // SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
 
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 #[cfg(feature = "bun")]
 pub use chaste_bun as bun;
@@ -13,12 +14,23 @@ pub use chaste_types as types;
 #[cfg(any(feature = "yarn-berry", feature = "yarn-classic"))]
 pub use chaste_yarn as yarn;
 
-pub use chaste_types::{Chastefile, Dependency, DependencyKind, Package, PackageID};
+pub use chaste_types::{
+    Chastefile, Dependency, DependencyKind, FileSource, MemoryFileSource, Package, PackageID,
+    RealFileSource,
+};
 
+pub mod compare;
+pub mod drift;
 pub mod error;
+pub mod git;
+pub mod graph;
+pub mod package_manager;
+pub mod provider;
+pub mod sqlite;
 use crate::error::{Error, Result};
+use crate::provider::{builtin_registry, CustomMeta};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Implementation {
     #[cfg(feature = "bun")]
     Bun,
@@ -70,8 +82,103 @@ impl Implementation {
             _ => None,
         }
     }
+
+    /// File names of lockfiles of this implementation, in order of precedence.
+    pub fn lockfile_names(&self) -> &'static [&'static str] {
+        use Implementation::*;
+        match self {
+            #[cfg(feature = "bun")]
+            Bun => &["bun.lock"],
+            #[cfg(feature = "npm")]
+            Npm => &["npm-shrinkwrap.json", "package-lock.json"],
+            #[cfg(feature = "pnpm")]
+            Pnpm => &["pnpm-lock.yaml"],
+            #[cfg(any(feature = "yarn-classic", feature = "yarn-berry", feature = "yarn-zpm"))]
+            Yarn => &["yarn.lock"],
+            #[cfg(not(any(
+                feature = "bun",
+                feature = "npm",
+                feature = "pnpm",
+                feature = "yarn-classic",
+                feature = "yarn-berry",
+                feature = "yarn-zpm",
+            )))]
+            _ => unreachable!(),
+        }
+    }
+
+    /// Implementation that writes lockfiles with this file name, e.g. "pnpm-lock.yaml".
+    pub fn from_lockfile_name(file_name: &str) -> Option<Implementation> {
+        Implementation::from_name(match file_name {
+            "bun.lock" => "bun",
+            "package-lock.json" | "npm-shrinkwrap.json" => "npm",
+            "pnpm-lock.yaml" => "pnpm",
+            "yarn.lock" => "yarn",
+            _ => return None,
+        })
+    }
+
+    /// Guess the implementation from the contents of a lockfile.
+    /// Returns None if it doesn't look like any lockfile, or if the implementation is not enabled.
+    pub fn sniff(lockfile_contents: &str) -> Option<Implementation> {
+        Implementation::from_name(sniff_name(lockfile_contents)?)
+    }
 }
 
+/// Is any `"key":` in the JSON text immediately followed by an object?
+fn json_key_has_object(contents: &str, key: &str) -> bool {
+    contents.match_indices(key).any(|(i, _)| {
+        contents[i + key.len()..]
+            .trim_start()
+            .strip_prefix(':')
+            .is_some_and(|r| r.trim_start().starts_with('{'))
+    })
+}
+
+fn sniff_name(contents: &str) -> Option<&'static str> {
+    let contents = contents.trim_start_matches('\u{feff}').trim_start();
+    if contents.starts_with('{') {
+        // Yarn 6+ writes JSON with the same "__metadata" as the YAML-like format of berry.
+        if contents.contains("\"__metadata\"") {
+            return Some("yarn");
+        }
+        // bun.lock has a top level object of workspaces,
+        // while in package-lock.json "workspaces" are arrays copied from package.json.
+        if json_key_has_object(contents, "\"workspaces\"") {
+            return Some("bun");
+        }
+        if contents.contains("\"lockfileVersion\"") {
+            return Some("npm");
+        }
+        return None;
+    }
+    let first_line = contents
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with('#'));
+    if first_line.is_some_and(|l| l.starts_with("lockfileVersion:")) {
+        return Some("pnpm");
+    }
+    if contents.contains("# yarn lockfile v1")
+        || contents.lines().any(|l| l.starts_with("__metadata:"))
+    {
+        return Some("yarn");
+    }
+    None
+}
+
+/// Every implementation enabled at build time.
+pub static ALL_IMPLEMENTATIONS: &[Implementation] = &[
+    #[cfg(feature = "bun")]
+    Implementation::Bun,
+    #[cfg(feature = "npm")]
+    Implementation::Npm,
+    #[cfg(feature = "pnpm")]
+    Implementation::Pnpm,
+    #[cfg(any(feature = "yarn-classic", feature = "yarn-berry", feature = "yarn-zpm"))]
+    Implementation::Yarn,
+];
+
 #[derive(Debug, Clone)]
 pub enum Meta {
     #[cfg(feature = "bun")]
@@ -85,6 +192,9 @@ pub enum Meta {
 
     #[cfg(any(feature = "yarn-classic", feature = "yarn-berry", feature = "yarn-zpm"))]
     Yarn(yarn::Meta),
+
+    /// Meta of a provider that is not built in, see [`provider::LockfileProvider`].
+    Custom(CustomMeta),
 }
 
 impl Meta {
@@ -101,17 +211,32 @@ impl Meta {
             Meta::Pnpm(meta) => func(meta),
             #[cfg(any(feature = "yarn-classic", feature = "yarn-berry"))]
             Meta::Yarn(meta) => func(meta),
-            #[cfg(not(any(
-                feature = "bun",
-                feature = "npm",
-                feature = "pnpm",
-                feature = "yarn-classic",
-                feature = "yarn-berry",
-                feature = "yarn-zpm",
-            )))]
-            _ => unreachable!(),
+            Meta::Custom(meta) => func(meta),
+        }
+    }
+
+    /// Name of the provider the lockfile was parsed with, as it is registered in
+    /// a [`provider::Registry`], e.g. "yarn" where [`types::ProviderMeta::provider_name`]
+    /// tells "yarn-berry". Providers that are not built in are expected to be registered
+    /// under the name their meta tells.
+    pub fn provider(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "bun")]
+            Meta::Bun(_) => "bun",
+            #[cfg(feature = "npm")]
+            Meta::Npm(_) => "npm",
+            #[cfg(feature = "pnpm")]
+            Meta::Pnpm(_) => "pnpm",
+            #[cfg(any(feature = "yarn-classic", feature = "yarn-berry", feature = "yarn-zpm"))]
+            Meta::Yarn(_) => "yarn",
+            Meta::Custom(meta) => types::ProviderMeta::provider_name(meta),
         }
     }
+
+    /// The built-in implementation the lockfile was parsed with.
+    pub fn implementation(&self) -> Option<Implementation> {
+        Implementation::from_name(self.provider())
+    }
 }
 
 impl types::ProviderMeta for Meta {
@@ -131,86 +256,280 @@ pub fn from_root_path_with_implementation<P>(
 where
     P: AsRef<Path>,
 {
-    use Implementation::*;
-    match implementation {
-        #[cfg(feature = "bun")]
-        Bun => bun::parse(root_path)
-            .map(|c| c.map_meta(Meta::Bun))
-            .map_err(Error::BunError),
-        #[cfg(feature = "npm")]
-        Npm => npm::parse(root_path)
-            .map(|c| c.map_meta(Meta::Npm))
-            .map_err(Error::NpmError),
-        #[cfg(feature = "pnpm")]
-        Pnpm => pnpm::parse(root_path)
-            .map(|c| c.map_meta(Meta::Pnpm))
-            .map_err(Error::PnpmError),
-        #[cfg(any(feature = "yarn-classic", feature = "yarn-berry", feature = "yarn-zpm"))]
-        Yarn => yarn::parse(root_path)
-            .map(|c| c.map_meta(Meta::Yarn))
-            .map_err(Error::YarnError),
-        #[cfg(not(any(
-            feature = "bun",
-            feature = "npm",
-            feature = "pnpm",
-            feature = "yarn-classic",
-            feature = "yarn-berry",
-            feature = "yarn-zpm",
-        )))]
-        _ => unreachable!(),
-    }
+    from_source_with_implementation(root_path, implementation, &RealFileSource)
 }
 
-pub fn implementations_from_root_path<P>(root_path: P) -> Vec<Implementation>
+/// Parse lockfile contents that were already read, e.g. from stdin or an artifact store.
+///
+/// Some implementations also need the package.json files of workspace members,
+/// which are read from `file_source`, relative to `root_path`.
+pub fn from_str_with_implementation<P, S>(
+    lockfile_contents: &str,
+    implementation: Implementation,
+    root_path: P,
+    file_source: &S,
+) -> Result<Chastefile<Meta>>
 where
     P: AsRef<Path>,
+    S: FileSource + ?Sized,
 {
-    let root_path = root_path.as_ref();
-    let mut impls_found = Vec::new();
+    builtin_registry().parse_str(
+        implementation.name(),
+        lockfile_contents,
+        root_path,
+        &file_source,
+    )
+}
 
-    use Implementation::*;
+/// Like [`from_str_with_implementation`], but problems with single packages or dependencies
+/// are skipped and returned as warnings alongside, instead of failing the whole parse.
+///
+/// Errors that leave nothing to work with, like invalid syntax or an unsupported
+/// lockfile version, are still returned as errors.
+pub fn from_str_with_implementation_lenient<P, S>(
+    lockfile_contents: &str,
+    implementation: Implementation,
+    root_path: P,
+    file_source: &S,
+) -> Result<(Chastefile<Meta>, Vec<Error>)>
+where
+    P: AsRef<Path>,
+    S: FileSource + ?Sized,
+{
+    builtin_registry().parse_str_lenient(
+        implementation.name(),
+        lockfile_contents,
+        root_path,
+        &file_source,
+    )
+}
 
-    #[cfg(feature = "bun")]
-    {
-        if root_path.join(bun::LOCKFILE_NAME).exists() {
-            impls_found.push(Bun);
-        }
-    }
+/// Like [`from_str_with_implementation`], with the implementation guessed from the contents.
+pub fn from_str<P, S>(
+    lockfile_contents: &str,
+    root_path: P,
+    file_source: &S,
+) -> Result<Chastefile<Meta>>
+where
+    P: AsRef<Path>,
+    S: FileSource + ?Sized,
+{
+    builtin_registry().from_str(lockfile_contents, root_path, &file_source)
+}
 
-    #[cfg(feature = "npm")]
-    {
-        if root_path.join(npm::SHRINKWRAP_NAME).exists()
-            || root_path.join(npm::LOCKFILE_NAME).exists()
-        {
-            impls_found.push(Npm);
-        }
-    }
+/// Like [`from_str`], but lenient as [`from_str_with_implementation_lenient`].
+pub fn from_str_lenient<P, S>(
+    lockfile_contents: &str,
+    root_path: P,
+    file_source: &S,
+) -> Result<(Chastefile<Meta>, Vec<Error>)>
+where
+    P: AsRef<Path>,
+    S: FileSource + ?Sized,
+{
+    let implementation =
+        Implementation::sniff(lockfile_contents).ok_or(Error::UnrecognizedLockfile)?;
+    from_str_with_implementation_lenient(lockfile_contents, implementation, root_path, file_source)
+}
 
-    #[cfg(feature = "pnpm")]
-    {
-        if root_path.join(pnpm::LOCKFILE_NAME).exists() {
-            impls_found.push(Pnpm);
-        }
+/// Parse a lockfile at any path, not necessarily named the way the implementation names it.
+/// The directory it is in is treated as the root of the project.
+pub fn from_lockfile_path_with_implementation<P>(
+    lockfile_path: P,
+    implementation: Implementation,
+) -> Result<Chastefile<Meta>>
+where
+    P: AsRef<Path>,
+{
+    let lockfile_path = lockfile_path.as_ref();
+    let contents = fs::read_to_string(lockfile_path)?;
+    let root_path = lockfile_path.parent().unwrap_or(Path::new(""));
+    from_str_with_implementation(&contents, implementation, root_path, &RealFileSource)
+}
+
+/// Like [`from_lockfile_path_with_implementation`], with the implementation
+/// recognized from the file name, or otherwise guessed from the contents.
+pub fn from_lockfile_path<P>(lockfile_path: P) -> Result<Chastefile<Meta>>
+where
+    P: AsRef<Path>,
+{
+    let (chastefile, _) = parse_lockfile_path(lockfile_path.as_ref(), false)?;
+    Ok(chastefile)
+}
+
+/// Like [`from_lockfile_path`], but lenient as [`from_str_with_implementation_lenient`].
+pub fn from_lockfile_path_lenient<P>(lockfile_path: P) -> Result<(Chastefile<Meta>, Vec<Error>)>
+where
+    P: AsRef<Path>,
+{
+    parse_lockfile_path(lockfile_path.as_ref(), true)
+}
+
+/// [`from_lockfile_path`], or with `lenient`, [`from_lockfile_path_lenient`].
+/// Strict parsing returns no warnings.
+fn parse_lockfile_path(
+    lockfile_path: &Path,
+    lenient: bool,
+) -> Result<(Chastefile<Meta>, Vec<Error>)> {
+    let contents = fs::read_to_string(lockfile_path)?;
+    let root_path = lockfile_path.parent().unwrap_or(Path::new(""));
+    let implementation = lockfile_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .and_then(Implementation::from_lockfile_name)
+        .or_else(|| Implementation::sniff(&contents))
+        .ok_or(Error::UnrecognizedLockfile)?;
+    if lenient {
+        from_str_with_implementation_lenient(&contents, implementation, root_path, &RealFileSource)
+    } else {
+        from_str_with_implementation(&contents, implementation, root_path, &RealFileSource)
+            .map(|chastefile| (chastefile, Vec::new()))
     }
+}
 
-    #[cfg(any(feature = "yarn-berry", feature = "yarn-classic", feature = "yarn-zpm"))]
-    {
-        if root_path.join(yarn::LOCKFILE_NAME).exists() {
-            impls_found.push(Yarn);
-        }
+/// Like [`from_root_path_with_implementation`], with all files read from `file_source`,
+/// e.g. a [`git::GitFileSource`].
+pub fn from_source_with_implementation<P, S>(
+    root_path: P,
+    implementation: Implementation,
+    file_source: &S,
+) -> Result<Chastefile<Meta>>
+where
+    P: AsRef<Path>,
+    S: FileSource + ?Sized,
+{
+    builtin_registry().parse_source(implementation.name(), root_path, &file_source)
+}
+
+/// Like [`implementations_from_root_path`], with the lockfiles looked up in `file_source`.
+pub fn implementations_from_source<P, S>(root_path: P, file_source: &S) -> Vec<Implementation>
+where
+    P: AsRef<Path>,
+    S: FileSource + ?Sized,
+{
+    builtin_registry()
+        .found_in_source(root_path, file_source)
+        .into_iter()
+        .filter_map(Implementation::from_name)
+        .collect()
+}
+
+/// Like [`from_root_path`], with all files read from `file_source`.
+pub fn from_source<P, S>(root_path: P, file_source: &S) -> Result<Chastefile<Meta>>
+where
+    P: AsRef<Path>,
+    S: FileSource + ?Sized,
+{
+    builtin_registry().from_source(root_path, &file_source)
+}
+
+pub fn implementations_from_root_path<P>(root_path: P) -> Vec<Implementation>
+where
+    P: AsRef<Path>,
+{
+    builtin_registry()
+        .found_in_root_path(root_path)
+        .into_iter()
+        .filter_map(Implementation::from_name)
+        .collect()
+}
+
+/// Does the directory declare a workspace, by a pnpm-workspace.yaml
+/// or the "workspaces" field in package.json?
+pub fn is_workspace_root<P>(path: P) -> bool
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    if path.join("pnpm-workspace.yaml").is_file() {
+        return true;
     }
+    fs::read_to_string(path.join("package.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+        .is_some_and(|v| v.get("workspaces").is_some())
+}
 
-    impls_found
+/// Find the directory that [`from_root_path`] should be called with, when standing in `path`
+/// or any directory under it, e.g. inside a workspace member of a monorepo.
+///
+/// Parent directories are walked until one has a lockfile or is a workspace root.
+pub fn find_root_path<P>(path: P) -> Option<PathBuf>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref().canonicalize().ok()?;
+    path.ancestors()
+        .find(|dir| !implementations_from_root_path(dir).is_empty() || is_workspace_root(dir))
+        .map(Path::to_path_buf)
 }
 
+/// Parse the lockfile in the directory.
+///
+/// If lockfiles of multiple implementations are found, the one in use is chosen
+/// with [`package_manager::preferred_implementation`].
 pub fn from_root_path<P>(root_path: P) -> Result<Chastefile<Meta>>
 where
     P: AsRef<Path>,
 {
-    let implems = implementations_from_root_path(root_path.as_ref());
-    match *implems {
-        [] => Err(Error::NoLockfile),
-        [implementation] => from_root_path_with_implementation(root_path, implementation),
-        _ => Err(Error::MultipleLockfiles(implems)),
+    builtin_registry().from_root_path(root_path)
+}
+
+/// Like [`from_root_path`], but lenient as [`from_str_with_implementation_lenient`].
+pub fn from_root_path_lenient<P>(root_path: P) -> Result<(Chastefile<Meta>, Vec<Error>)>
+where
+    P: AsRef<Path>,
+{
+    let root_path = root_path.as_ref();
+    let registry = builtin_registry();
+    let name = registry.in_use(root_path)?;
+    let contents = registry
+        .lockfile_names(name)
+        .unwrap_or_default()
+        .iter()
+        .find_map(|n| fs::read_to_string(root_path.join(n)).ok())
+        .ok_or(Error::NoLockfile)?;
+    registry.parse_str_lenient(name, &contents, root_path, &RealFileSource)
+}
+
+/// Write the lockfile with contents `lockfile_contents` again, in its format,
+/// with only what is in `pruned`, as made with [`Chastefile::prune`] from parsing the contents.
+pub fn write_pruned(lockfile_contents: &str, pruned: &Chastefile<Meta>) -> Result<String> {
+    builtin_registry().write_pruned(lockfile_contents, pruned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sniff_name;
+
+    #[test]
+    fn sniff_lockfile_contents() {
+        assert_eq!(
+            sniff_name("{\n  \"lockfileVersion\": 1,\n  \"workspaces\": {\n    \"\": {},\n  },\n}"),
+            Some("bun")
+        );
+        assert_eq!(
+            sniff_name(
+                "{\n  \"name\": \"a\",\n  \"lockfileVersion\": 3,\n  \"packages\": {\n    \"\": { \"workspaces\": [\"b\"] }\n  }\n}"
+            ),
+            Some("npm")
+        );
+        assert_eq!(
+            sniff_name("{\n  \"__metadata\": {\n    \"version\": 9\n  },\n  \"entries\": {}\n}"),
+            Some("yarn")
+        );
+        assert_eq!(
+            sniff_name("lockfileVersion: '9.0'\n\nsettings:\n  autoInstallPeers: true\n"),
+            Some("pnpm")
+        );
+        assert_eq!(
+            sniff_name("# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.\n# yarn lockfile v1\n"),
+            Some("yarn")
+        );
+        assert_eq!(
+            sniff_name("# This file is generated by running \"yarn install\" inside your project.\n\n__metadata:\n  version: 8\n"),
+            Some("yarn")
+        );
+        assert_eq!(sniff_name("hello"), None);
     }
 }
@@ -0,0 +1,161 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+//! Choosing between multiple lockfiles found in one directory.
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::Implementation;
+
+/// Why an implementation was chosen as authoritative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Preference {
+    /// The corepack "packageManager" field of package.json.
+    PackageManagerField,
+    /// The "devEngines.packageManager" field of package.json.
+    DevEngines,
+    /// A configuration file only read by one of the implementations, e.g. ".yarnrc.yml".
+    Marker(&'static str),
+}
+
+impl std::fmt::Display for Preference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Preference::PackageManagerField => f.write_str("packageManager in package.json"),
+            Preference::DevEngines => f.write_str("devEngines.packageManager in package.json"),
+            Preference::Marker(file_name) => f.write_str(file_name),
+        }
+    }
+}
+
+/// Configuration files, and the package managers that read them.
+/// Not .npmrc, which npm, pnpm, yarn v1 and bun all read.
+static MARKERS: &[(&str, &[&str])] = &[
+    (".yarnrc.yml", &["yarn"]),
+    (".yarnrc", &["yarn"]),
+    ("bunfig.toml", &["bun"]),
+    ("pnpm-workspace.yaml", &["pnpm"]),
+];
+
+/// "yarn@4.1.0+sha224.abc" -> "yarn"
+fn manager_name(spec: &str) -> &str {
+    spec.split_once('@').map(|(n, _)| n).unwrap_or(spec)
+}
+
+fn dev_engines_names(dev_engines: &Value) -> Vec<&str> {
+    match dev_engines.get("packageManager") {
+        Some(Value::Object(pm)) => pm.get("name").and_then(Value::as_str).into_iter().collect(),
+        Some(Value::Array(pms)) => pms
+            .iter()
+            .filter_map(|pm| pm.get("name").and_then(Value::as_str))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Choose which of the `candidates`, whose lockfiles were all found in `root_path`,
+/// is the one actually in use.
+pub fn preferred_implementation<P>(
+    root_path: P,
+    candidates: &[Implementation],
+) -> Option<(Implementation, Preference)>
+where
+    P: AsRef<Path>,
+{
+    let names: Vec<&str> = candidates.iter().map(|implem| implem.name()).collect();
+    let (name, preference) = preferred_name(root_path, &names)?;
+    Some((Implementation::from_name(name)?, preference))
+}
+
+/// Like [`preferred_implementation`], with the candidates named as package managers are,
+/// e.g. by [`crate::provider::LockfileProvider::name`].
+pub fn preferred_name<'n, P>(root_path: P, candidates: &[&'n str]) -> Option<(&'n str, Preference)>
+where
+    P: AsRef<Path>,
+{
+    let root_path = root_path.as_ref();
+    let find = |name: &str| candidates.iter().copied().find(|c| *c == name);
+
+    let package_json: Option<Value> = fs::read_to_string(root_path.join("package.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok());
+    if let Some(package_json) = &package_json {
+        if let Some(name) = package_json
+            .get("packageManager")
+            .and_then(Value::as_str)
+            .and_then(|s| find(manager_name(s)))
+        {
+            return Some((name, Preference::PackageManagerField));
+        }
+        if let Some(name) = package_json
+            .get("devEngines")
+            .map(dev_engines_names)
+            .and_then(|names| match *names {
+                // With more than one allowed, it's no help.
+                [name] => find(name),
+                _ => None,
+            })
+        {
+            return Some((name, Preference::DevEngines));
+        }
+    }
+
+    let mut found: Option<(&str, Preference)> = None;
+    for (file_name, names) in MARKERS {
+        if !root_path.join(file_name).exists() {
+            continue;
+        }
+        let matching: Vec<&str> = names.iter().filter_map(|n| find(n)).collect();
+        if let [name] = *matching {
+            match found {
+                None => found = Some((name, Preference::Marker(file_name))),
+                // Markers of different managers contradict each other.
+                Some((f, _)) if f != name => return None,
+                Some(_) => {}
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{dev_engines_names, manager_name, preferred_name, Preference};
+
+    #[test]
+    fn package_manager_names() {
+        assert_eq!(manager_name("yarn@4.1.0+sha224.abcdef"), "yarn");
+        assert_eq!(manager_name("pnpm"), "pnpm");
+        assert_eq!(
+            dev_engines_names(&json!({"packageManager": {"name": "bun", "onFail": "warn"}})),
+            ["bun"]
+        );
+        assert_eq!(
+            dev_engines_names(&json!({"packageManager": [{"name": "npm"}, {"name": "pnpm"}]})),
+            ["npm", "pnpm"]
+        );
+        assert!(dev_engines_names(&json!({"runtime": {"name": "node"}})).is_empty());
+    }
+
+    #[test]
+    fn preferred_names() {
+        let root_path = "../chaste-cli/test_workspaces/multiple_lockfiles";
+        // Names of providers that are not built in do not get in the way.
+        assert_eq!(
+            preferred_name(root_path, &["toy", "yarn"]),
+            Some(("yarn", Preference::PackageManagerField))
+        );
+        assert_eq!(preferred_name(root_path, &["npm", "toy"]), None);
+
+        // .npmrc is read by all of them, so it does not tell any apart.
+        let root_path = "../chaste-bun/test_workspaces/text_v1_scope_registry";
+        assert_eq!(preferred_name(root_path, &["bun"]), None);
+        assert_eq!(preferred_name(root_path, &["npm", "bun"]), None);
+    }
+}
@@ -0,0 +1,782 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+use std::fmt::Debug;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+
+use chaste_types::{Chastefile, FileSource, LockfileVersion, ProviderMeta, RealFileSource};
+
+use crate::error::{Error, Result};
+use crate::{package_manager, Implementation, Meta};
+
+/// A lockfile format that can be detected and parsed.
+///
+/// The implementations built into this crate are providers, and so can be others,
+/// e.g. for the lockfile of an in-house tool, once added to a [`Registry`].
+pub trait LockfileProvider: Send + Sync {
+    type Meta: ProviderMeta + Debug + Send + Sync + 'static;
+
+    /// Name of the provider, e.g. "npm". It is unique within a [`Registry`].
+    fn name(&self) -> &'static str;
+
+    /// File names of lockfiles of this provider, in order of precedence.
+    fn lockfile_names(&self) -> &'static [&'static str];
+
+    /// Do the lockfile contents look like they were written by this provider?
+    fn detect(&self, lockfile_contents: &str) -> bool;
+
+    /// Parse lockfile contents. Other files, like package.json of workspace members,
+    /// are read from `file_source`, relative to `root_path`.
+    fn parse_str(
+        &self,
+        lockfile_contents: &str,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<Chastefile<Self::Meta>>;
+
+    /// Like [`LockfileProvider::parse_str`], but problems with single packages or dependencies
+    /// are returned as warnings. By default, nothing is skipped.
+    fn parse_str_lenient(
+        &self,
+        lockfile_contents: &str,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<(Chastefile<Self::Meta>, Vec<Error>)> {
+        self.parse_str(lockfile_contents, root_path, file_source)
+            .map(|c| (c, Vec::new()))
+    }
+
+    /// Read the lockfile in `root_path` and parse it.
+    fn parse_source(
+        &self,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<Chastefile<Self::Meta>> {
+        let contents = read_lockfile(self.lockfile_names(), root_path, file_source)?;
+        self.parse_str(&contents, root_path, file_source)
+    }
+
+    /// Write the lockfile with contents `lockfile_contents` again, in the same format,
+    /// with only what is in `pruned`, as made with [`Chastefile::prune`] or [`Chastefile::subgraph`]
+    /// from the result of parsing the same contents. All built-in providers support this;
+    /// for others it is [`Error::PruneUnsupported`] unless they implement it.
+    fn write_pruned(&self, lockfile_contents: &str, pruned: &Chastefile<Meta>) -> Result<String> {
+        let _ = (lockfile_contents, pruned);
+        Err(Error::PruneUnsupported(self.name()))
+    }
+
+    /// Wrap the meta to be used in [`Meta`].
+    fn into_meta(meta: Self::Meta) -> Meta {
+        Meta::Custom(CustomMeta(Arc::new(meta)))
+    }
+}
+
+/// Contents of the first of the lockfiles that exists.
+fn read_lockfile(
+    lockfile_names: &[&str],
+    root_path: &Path,
+    file_source: &dyn FileSource,
+) -> Result<String> {
+    for name in lockfile_names {
+        match file_source.read_to_string(&root_path.join(name)) {
+            Ok(contents) => return Ok(contents),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(Error::IoError(e)),
+        }
+    }
+    Err(Error::NoLockfile)
+}
+
+pub trait AnyProviderMeta: ProviderMeta + Debug + Send + Sync {}
+
+impl<M: ProviderMeta + Debug + Send + Sync> AnyProviderMeta for M {}
+
+/// Meta of a provider that is not built in.
+#[derive(Debug, Clone)]
+pub struct CustomMeta(pub Arc<dyn AnyProviderMeta>);
+
+impl ProviderMeta for CustomMeta {
+    fn provider_name(&self) -> &'static str {
+        self.0.provider_name()
+    }
+
+    fn lockfile_version<'m>(&'m self) -> Option<LockfileVersion<'m>> {
+        self.0.lockfile_version()
+    }
+}
+
+/// [`LockfileProvider`] with the meta wrapped, so that providers can be kept together.
+trait DynProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn lockfile_names(&self) -> &'static [&'static str];
+    fn detect(&self, lockfile_contents: &str) -> bool;
+    fn parse_str(
+        &self,
+        lockfile_contents: &str,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<Chastefile<Meta>>;
+    fn parse_str_lenient(
+        &self,
+        lockfile_contents: &str,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<(Chastefile<Meta>, Vec<Error>)>;
+    fn parse_source(
+        &self,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<Chastefile<Meta>>;
+    fn write_pruned(&self, lockfile_contents: &str, pruned: &Chastefile<Meta>) -> Result<String>;
+}
+
+impl<P: LockfileProvider> DynProvider for P {
+    fn name(&self) -> &'static str {
+        LockfileProvider::name(self)
+    }
+
+    fn lockfile_names(&self) -> &'static [&'static str] {
+        LockfileProvider::lockfile_names(self)
+    }
+
+    fn detect(&self, lockfile_contents: &str) -> bool {
+        LockfileProvider::detect(self, lockfile_contents)
+    }
+
+    fn parse_str(
+        &self,
+        lockfile_contents: &str,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<Chastefile<Meta>> {
+        LockfileProvider::parse_str(self, lockfile_contents, root_path, file_source)
+            .map(|c| c.map_meta(P::into_meta))
+    }
+
+    fn parse_str_lenient(
+        &self,
+        lockfile_contents: &str,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<(Chastefile<Meta>, Vec<Error>)> {
+        LockfileProvider::parse_str_lenient(self, lockfile_contents, root_path, file_source)
+            .map(|(c, warnings)| (c.map_meta(P::into_meta), warnings))
+    }
+
+    fn parse_source(
+        &self,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<Chastefile<Meta>> {
+        LockfileProvider::parse_source(self, root_path, file_source)
+            .map(|c| c.map_meta(P::into_meta))
+    }
+
+    fn write_pruned(&self, lockfile_contents: &str, pruned: &Chastefile<Meta>) -> Result<String> {
+        LockfileProvider::write_pruned(self, lockfile_contents, pruned)
+    }
+}
+
+/// Providers that lockfiles are detected and parsed with.
+pub struct Registry {
+    providers: Vec<Box<dyn DynProvider>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Registry::builtin()
+    }
+}
+
+impl Registry {
+    /// A registry with no providers.
+    pub fn new() -> Self {
+        Registry {
+            providers: Vec::new(),
+        }
+    }
+
+    /// A registry with the providers of all implementations enabled at build time.
+    pub fn builtin() -> Self {
+        #[allow(unused_mut)]
+        let mut registry = Registry::new();
+        #[cfg(feature = "bun")]
+        registry.register(BunProvider);
+        #[cfg(feature = "npm")]
+        registry.register(NpmProvider);
+        #[cfg(feature = "pnpm")]
+        registry.register(PnpmProvider);
+        #[cfg(any(feature = "yarn-classic", feature = "yarn-berry", feature = "yarn-zpm"))]
+        registry.register(YarnProvider);
+        registry
+    }
+
+    /// Add the provider, replacing one already registered under the same name.
+    pub fn register<P>(&mut self, provider: P) -> &mut Self
+    where
+        P: LockfileProvider + 'static,
+    {
+        let provider: Box<dyn DynProvider> = Box::new(provider);
+        match self
+            .providers
+            .iter_mut()
+            .find(|p| p.name() == provider.name())
+        {
+            Some(existing) => *existing = provider,
+            None => self.providers.push(provider),
+        }
+        self
+    }
+
+    /// Names of the registered providers, in order of registration.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.providers.iter().map(|p| p.name()).collect()
+    }
+
+    fn get(&self, name: &str) -> Result<&dyn DynProvider> {
+        self.providers
+            .iter()
+            .find(|p| p.name() == name)
+            .map(|p| &**p)
+            .ok_or_else(|| Error::UnknownProvider(name.to_string()))
+    }
+
+    /// File names of lockfiles of the provider, if it is registered.
+    pub fn lockfile_names(&self, name: &str) -> Option<&'static [&'static str]> {
+        self.get(name).ok().map(|p| p.lockfile_names())
+    }
+
+    /// Provider that writes lockfiles with this file name, e.g. "pnpm-lock.yaml".
+    pub fn from_lockfile_name(&self, file_name: &str) -> Option<&'static str> {
+        self.providers
+            .iter()
+            .find(|p| p.lockfile_names().contains(&file_name))
+            .map(|p| p.name())
+    }
+
+    /// Guess the provider from the contents of a lockfile.
+    ///
+    /// Providers registered later are asked first,
+    /// so they can claim contents that a built-in provider would detect too.
+    pub fn sniff(&self, lockfile_contents: &str) -> Option<&'static str> {
+        self.providers
+            .iter()
+            .rev()
+            .find(|p| p.detect(lockfile_contents))
+            .map(|p| p.name())
+    }
+
+    /// Providers with a lockfile in `root_path` of `file_source`.
+    pub fn found_in_source<P, S>(&self, root_path: P, file_source: &S) -> Vec<&'static str>
+    where
+        P: AsRef<Path>,
+        S: FileSource + ?Sized,
+    {
+        let root_path = root_path.as_ref();
+        self.providers
+            .iter()
+            .filter(|p| {
+                p.lockfile_names()
+                    .iter()
+                    .any(|n| file_source.read_to_string(&root_path.join(n)).is_ok())
+            })
+            .map(|p| p.name())
+            .collect()
+    }
+
+    /// Providers with a lockfile in the directory.
+    pub fn found_in_root_path<P>(&self, root_path: P) -> Vec<&'static str>
+    where
+        P: AsRef<Path>,
+    {
+        let root_path = root_path.as_ref();
+        self.providers
+            .iter()
+            .filter(|p| {
+                p.lockfile_names()
+                    .iter()
+                    .any(|n| root_path.join(n).exists())
+            })
+            .map(|p| p.name())
+            .collect()
+    }
+
+    /// Parse lockfile contents with the named provider.
+    pub fn parse_str<P, S>(
+        &self,
+        name: &str,
+        lockfile_contents: &str,
+        root_path: P,
+        file_source: &S,
+    ) -> Result<Chastefile<Meta>>
+    where
+        P: AsRef<Path>,
+        S: FileSource,
+    {
+        self.get(name)?
+            .parse_str(lockfile_contents, root_path.as_ref(), file_source)
+    }
+
+    /// Like [`Registry::parse_str`], but lenient as [`crate::from_str_with_implementation_lenient`].
+    pub fn parse_str_lenient<P, S>(
+        &self,
+        name: &str,
+        lockfile_contents: &str,
+        root_path: P,
+        file_source: &S,
+    ) -> Result<(Chastefile<Meta>, Vec<Error>)>
+    where
+        P: AsRef<Path>,
+        S: FileSource,
+    {
+        self.get(name)?
+            .parse_str_lenient(lockfile_contents, root_path.as_ref(), file_source)
+    }
+
+    /// Read the lockfile of the named provider in `root_path` of `file_source`, and parse it.
+    pub fn parse_source<P, S>(
+        &self,
+        name: &str,
+        root_path: P,
+        file_source: &S,
+    ) -> Result<Chastefile<Meta>>
+    where
+        P: AsRef<Path>,
+        S: FileSource,
+    {
+        self.get(name)?
+            .parse_source(root_path.as_ref(), file_source)
+    }
+
+    /// Write the lockfile again with only what is in `pruned`, with the provider it was parsed
+    /// with, see [`LockfileProvider::write_pruned`].
+    pub fn write_pruned(
+        &self,
+        lockfile_contents: &str,
+        pruned: &Chastefile<Meta>,
+    ) -> Result<String> {
+        self.get(pruned.meta().provider())?
+            .write_pruned(lockfile_contents, pruned)
+    }
+
+    /// Parse lockfile contents with the provider guessed by [`Registry::sniff`].
+    pub fn from_str<P, S>(
+        &self,
+        lockfile_contents: &str,
+        root_path: P,
+        file_source: &S,
+    ) -> Result<Chastefile<Meta>>
+    where
+        P: AsRef<Path>,
+        S: FileSource,
+    {
+        let name = self
+            .sniff(lockfile_contents)
+            .ok_or(Error::UnrecognizedLockfile)?;
+        self.parse_str(name, lockfile_contents, root_path, file_source)
+    }
+
+    /// Parse the only lockfile in `root_path` of `file_source`.
+    pub fn from_source<P, S>(&self, root_path: P, file_source: &S) -> Result<Chastefile<Meta>>
+    where
+        P: AsRef<Path>,
+        S: FileSource,
+    {
+        let root_path = root_path.as_ref();
+        match *self.found_in_source(root_path, file_source) {
+            [] => Err(Error::NoLockfile),
+            [name] => self.parse_source(name, root_path, file_source),
+            ref names => Err(ambiguous(names)),
+        }
+    }
+
+    /// Name of the provider whose lockfile is in the directory. If there are lockfiles
+    /// of multiple providers, the one in use is chosen by their names with
+    /// [`package_manager::preferred_name`].
+    pub fn in_use<P>(&self, root_path: P) -> Result<&'static str>
+    where
+        P: AsRef<Path>,
+    {
+        let root_path = root_path.as_ref();
+        match *self.found_in_root_path(root_path) {
+            [] => Err(Error::NoLockfile),
+            [name] => Ok(name),
+            ref names => package_manager::preferred_name(root_path, names)
+                .map(|(name, _)| name)
+                .ok_or_else(|| ambiguous(names)),
+        }
+    }
+
+    /// Parse the lockfile in the directory, of the provider chosen by [`Registry::in_use`].
+    pub fn from_root_path<P>(&self, root_path: P) -> Result<Chastefile<Meta>>
+    where
+        P: AsRef<Path>,
+    {
+        let root_path = root_path.as_ref();
+        self.parse_source(self.in_use(root_path)?, root_path, &RealFileSource)
+    }
+}
+
+fn ambiguous(names: &[&'static str]) -> Error {
+    match names.iter().map(|n| Implementation::from_name(n)).collect() {
+        Some(implems) => Error::MultipleLockfiles(implems),
+        None => Error::MultipleProviders(names.to_vec()),
+    }
+}
+
+/// The registry of built-in providers, that the functions at the root of this crate use.
+pub fn builtin_registry() -> &'static Registry {
+    static BUILTIN: LazyLock<Registry> = LazyLock::new(Registry::builtin);
+    &BUILTIN
+}
+
+#[cfg(feature = "bun")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BunProvider;
+
+#[cfg(feature = "bun")]
+impl LockfileProvider for BunProvider {
+    type Meta = crate::bun::Meta;
+
+    fn name(&self) -> &'static str {
+        Implementation::Bun.name()
+    }
+
+    fn lockfile_names(&self) -> &'static [&'static str] {
+        Implementation::Bun.lockfile_names()
+    }
+
+    fn detect(&self, lockfile_contents: &str) -> bool {
+        crate::sniff_name(lockfile_contents) == Some(LockfileProvider::name(self))
+    }
+
+    fn parse_str(
+        &self,
+        lockfile_contents: &str,
+        _root_path: &Path,
+        _file_source: &dyn FileSource,
+    ) -> Result<Chastefile<Self::Meta>> {
+        Ok(crate::bun::parse_str(lockfile_contents)?)
+    }
+
+    fn parse_str_lenient(
+        &self,
+        lockfile_contents: &str,
+        _root_path: &Path,
+        _file_source: &dyn FileSource,
+    ) -> Result<(Chastefile<Self::Meta>, Vec<Error>)> {
+        let (chastefile, warnings) = crate::bun::parse_str_lenient(lockfile_contents)?;
+        Ok((chastefile, warnings.into_iter().map(Error::from).collect()))
+    }
+
+    fn parse_source(
+        &self,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<Chastefile<Self::Meta>> {
+        Ok(crate::bun::parse_with_source(root_path, file_source)?)
+    }
+
+    fn write_pruned(&self, lockfile_contents: &str, pruned: &Chastefile<Meta>) -> Result<String> {
+        Ok(crate::bun::write_pruned(lockfile_contents, pruned)?)
+    }
+
+    fn into_meta(meta: Self::Meta) -> Meta {
+        Meta::Bun(meta)
+    }
+}
+
+#[cfg(feature = "npm")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NpmProvider;
+
+#[cfg(feature = "npm")]
+impl LockfileProvider for NpmProvider {
+    type Meta = crate::npm::Meta;
+
+    fn name(&self) -> &'static str {
+        Implementation::Npm.name()
+    }
+
+    fn lockfile_names(&self) -> &'static [&'static str] {
+        Implementation::Npm.lockfile_names()
+    }
+
+    fn detect(&self, lockfile_contents: &str) -> bool {
+        crate::sniff_name(lockfile_contents) == Some(LockfileProvider::name(self))
+    }
+
+    fn parse_str(
+        &self,
+        lockfile_contents: &str,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<Chastefile<Self::Meta>> {
+        let (chastefile, _) = crate::npm::parse_str(
+            lockfile_contents,
+            root_path,
+            file_source,
+            chaste_types::Warnings::strict(),
+        )?;
+        Ok(chastefile)
+    }
+
+    fn parse_str_lenient(
+        &self,
+        lockfile_contents: &str,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<(Chastefile<Self::Meta>, Vec<Error>)> {
+        let (chastefile, warnings) = crate::npm::parse_str(
+            lockfile_contents,
+            root_path,
+            file_source,
+            chaste_types::Warnings::lenient(),
+        )?;
+        Ok((
+            chastefile,
+            warnings.into_vec().into_iter().map(Error::from).collect(),
+        ))
+    }
+
+    fn write_pruned(&self, lockfile_contents: &str, pruned: &Chastefile<Meta>) -> Result<String> {
+        Ok(crate::npm::write_pruned(lockfile_contents, pruned)?)
+    }
+
+    fn into_meta(meta: Self::Meta) -> Meta {
+        Meta::Npm(meta)
+    }
+}
+
+#[cfg(feature = "pnpm")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PnpmProvider;
+
+#[cfg(feature = "pnpm")]
+impl LockfileProvider for PnpmProvider {
+    type Meta = crate::pnpm::Meta;
+
+    fn name(&self) -> &'static str {
+        Implementation::Pnpm.name()
+    }
+
+    fn lockfile_names(&self) -> &'static [&'static str] {
+        Implementation::Pnpm.lockfile_names()
+    }
+
+    fn detect(&self, lockfile_contents: &str) -> bool {
+        crate::sniff_name(lockfile_contents) == Some(LockfileProvider::name(self))
+    }
+
+    fn parse_str(
+        &self,
+        lockfile_contents: &str,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<Chastefile<Self::Meta>> {
+        Ok(crate::pnpm::parse_str(
+            lockfile_contents,
+            root_path,
+            file_source,
+        )?)
+    }
+
+    fn parse_str_lenient(
+        &self,
+        lockfile_contents: &str,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<(Chastefile<Self::Meta>, Vec<Error>)> {
+        let (chastefile, warnings) =
+            crate::pnpm::parse_str_lenient(lockfile_contents, root_path, file_source)?;
+        Ok((chastefile, warnings.into_iter().map(Error::from).collect()))
+    }
+
+    fn parse_source(
+        &self,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<Chastefile<Self::Meta>> {
+        Ok(crate::pnpm::parse_with_source(root_path, file_source)?)
+    }
+
+    fn write_pruned(&self, lockfile_contents: &str, pruned: &Chastefile<Meta>) -> Result<String> {
+        Ok(crate::pnpm::write_pruned(lockfile_contents, pruned)?)
+    }
+
+    fn into_meta(meta: Self::Meta) -> Meta {
+        Meta::Pnpm(meta)
+    }
+}
+
+#[cfg(any(feature = "yarn-classic", feature = "yarn-berry", feature = "yarn-zpm"))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct YarnProvider;
+
+#[cfg(any(feature = "yarn-classic", feature = "yarn-berry", feature = "yarn-zpm"))]
+impl LockfileProvider for YarnProvider {
+    type Meta = crate::yarn::Meta;
+
+    fn name(&self) -> &'static str {
+        Implementation::Yarn.name()
+    }
+
+    fn lockfile_names(&self) -> &'static [&'static str] {
+        Implementation::Yarn.lockfile_names()
+    }
+
+    fn detect(&self, lockfile_contents: &str) -> bool {
+        crate::sniff_name(lockfile_contents) == Some(LockfileProvider::name(self))
+    }
+
+    fn parse_str(
+        &self,
+        lockfile_contents: &str,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<Chastefile<Self::Meta>> {
+        Ok(crate::yarn::parse_str(
+            lockfile_contents,
+            root_path,
+            file_source,
+        )?)
+    }
+
+    fn parse_str_lenient(
+        &self,
+        lockfile_contents: &str,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<(Chastefile<Self::Meta>, Vec<Error>)> {
+        let (chastefile, warnings) =
+            crate::yarn::parse_str_lenient(lockfile_contents, root_path, file_source)?;
+        Ok((chastefile, warnings.into_iter().map(Error::from).collect()))
+    }
+
+    fn parse_source(
+        &self,
+        root_path: &Path,
+        file_source: &dyn FileSource,
+    ) -> Result<Chastefile<Self::Meta>> {
+        Ok(crate::yarn::parse_with_source(root_path, file_source)?)
+    }
+
+    fn write_pruned(&self, lockfile_contents: &str, pruned: &Chastefile<Meta>) -> Result<String> {
+        Ok(crate::yarn::write_pruned(lockfile_contents, pruned)?)
+    }
+
+    fn into_meta(meta: Self::Meta) -> Meta {
+        Meta::Yarn(meta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use chaste_types::{
+        Chastefile, ChastefileBuilder, ErrorKind, FileSource, LockfileVersion, MemoryFileSource,
+        PackageBuilder, PackageName, ProviderMeta,
+    };
+
+    use super::{LockfileProvider, Registry};
+    use crate::error::{Error, Result};
+
+    #[derive(Debug)]
+    struct ToyMeta;
+
+    impl ProviderMeta for ToyMeta {
+        fn provider_name(&self) -> &'static str {
+            "toy"
+        }
+
+        fn lockfile_version<'m>(&'m self) -> Option<LockfileVersion<'m>> {
+            None
+        }
+    }
+
+    /// Lockfile with the name of the root package on the first line, after "toy ".
+    struct ToyProvider;
+
+    impl LockfileProvider for ToyProvider {
+        type Meta = ToyMeta;
+
+        fn name(&self) -> &'static str {
+            "toy"
+        }
+
+        fn lockfile_names(&self) -> &'static [&'static str] {
+            &["toy.lock"]
+        }
+
+        fn detect(&self, lockfile_contents: &str) -> bool {
+            lockfile_contents.starts_with("toy ")
+        }
+
+        fn parse_str(
+            &self,
+            lockfile_contents: &str,
+            _root_path: &Path,
+            _file_source: &dyn FileSource,
+        ) -> Result<Chastefile<ToyMeta>> {
+            let name = lockfile_contents
+                .lines()
+                .next()
+                .and_then(|l| l.strip_prefix("toy "))
+                .ok_or_else(|| {
+                    Error::ProviderError(ErrorKind::MalformedSyntax, "no name".into())
+                })?;
+            let mut chastefile = ChastefileBuilder::new(ToyMeta);
+            let root = PackageBuilder::new(Some(PackageName::new(name.to_string())?), None);
+            let root_pid = chastefile.add_package(root.build()?)?;
+            chastefile.set_root_package_id(root_pid)?;
+            Ok(chastefile.build()?)
+        }
+    }
+
+    #[test]
+    fn custom_provider() -> Result<()> {
+        let mut registry = Registry::builtin();
+        registry.register(ToyProvider);
+        let files: MemoryFileSource = [("toy.lock", "toy @chastelock/toy\n".to_string())]
+            .into_iter()
+            .collect();
+
+        assert_eq!(registry.found_in_source("", &files), ["toy"]);
+        assert_eq!(registry.from_lockfile_name("toy.lock"), Some("toy"));
+        let chastefile = registry.from_source("", &files)?;
+        assert_eq!(chastefile.root_package().name().unwrap(), "@chastelock/toy");
+        assert_eq!(chastefile.meta().provider_name(), "toy");
+        assert_eq!(chastefile.meta().provider(), "toy");
+
+        let err = registry.parse_str("toy", "yarn", "", &files).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MalformedSyntax);
+        let err = registry.parse_str("cargo", "", "", &files).unwrap_err();
+        assert!(matches!(err, Error::UnknownProvider(_)));
+        assert_eq!(err.kind(), ErrorKind::UnknownProvider);
+        let err = registry
+            .write_pruned("toy @chastelock/toy\n", &chastefile)
+            .unwrap_err();
+        assert!(matches!(err, Error::PruneUnsupported("toy")));
+        assert_eq!(err.kind(), ErrorKind::Unsupported);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "yarn-berry")]
+    fn yarn_write_pruned() -> Result<()> {
+        let root_path = "../chaste-yarn/test_workspaces/b10_workspace_basic";
+        let registry = Registry::builtin();
+        let chastefile = registry.from_source(root_path, &crate::RealFileSource)?;
+        // Yarn tells its implementation, but is registered as one provider.
+        assert_eq!(chastefile.meta().provider_name(), "yarn-berry");
+        assert_eq!(chastefile.meta().provider(), "yarn");
+        let contents = std::fs::read_to_string(Path::new(root_path).join("yarn.lock"))?;
+        assert_eq!(registry.write_pruned(&contents, &chastefile)?, contents);
+
+        Ok(())
+    }
+}
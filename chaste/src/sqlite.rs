@@ -0,0 +1,341 @@
+// SPDX-FileCopyrightText: 2026 The Chaste Authors
+// SPDX-License-Identifier: Apache-2.0 OR BSD-2-Clause
+
+//! Exporting lockfiles into SQLite databases, to be queried across many projects.
+//!
+//! [`to_sql`] makes the statements, to be run with any SQLite client, e.g. the `sqlite3` command.
+
+use std::collections::HashMap;
+
+use chaste_types::{
+    Chastefile, Checksums, DependencyKind, PackageDerivation, PackageID, PackageSource,
+    ProviderMeta,
+};
+
+/// Tables of the database. Every row is of a project, which is a lockfile at a path.
+/// Packages are numbered in each project, in order of names and versions.
+pub static SCHEMA: &str = "\
+CREATE TABLE IF NOT EXISTS projects (
+    id INTEGER PRIMARY KEY,
+    path TEXT NOT NULL,
+    lockfile_hash TEXT NOT NULL,
+    implementation TEXT NOT NULL,
+    UNIQUE (path, lockfile_hash)
+);
+CREATE TABLE IF NOT EXISTS packages (
+    project_id INTEGER NOT NULL REFERENCES projects (id),
+    id INTEGER NOT NULL,
+    name TEXT,
+    version TEXT,
+    is_root INTEGER NOT NULL,
+    is_workspace_member INTEGER NOT NULL,
+    PRIMARY KEY (project_id, id)
+);
+CREATE TABLE IF NOT EXISTS sources (
+    project_id INTEGER NOT NULL REFERENCES projects (id),
+    package_id INTEGER NOT NULL,
+    type TEXT NOT NULL,
+    url TEXT,
+    PRIMARY KEY (project_id, package_id)
+);
+CREATE TABLE IF NOT EXISTS checksums (
+    project_id INTEGER NOT NULL REFERENCES projects (id),
+    package_id INTEGER NOT NULL,
+    type TEXT NOT NULL,
+    integrity TEXT NOT NULL,
+    PRIMARY KEY (project_id, package_id)
+);
+CREATE TABLE IF NOT EXISTS derivations (
+    project_id INTEGER NOT NULL REFERENCES projects (id),
+    package_id INTEGER NOT NULL,
+    derived_from INTEGER NOT NULL,
+    type TEXT NOT NULL,
+    patch_path TEXT,
+    patch_integrity TEXT,
+    PRIMARY KEY (project_id, package_id)
+);
+CREATE TABLE IF NOT EXISTS dependencies (
+    project_id INTEGER NOT NULL REFERENCES projects (id),
+    from_package_id INTEGER NOT NULL,
+    on_package_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    specifier TEXT,
+    alias TEXT
+);
+CREATE TABLE IF NOT EXISTS installations (
+    project_id INTEGER NOT NULL REFERENCES projects (id),
+    package_id INTEGER NOT NULL,
+    path TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS packages_name ON packages (name, version);
+CREATE INDEX IF NOT EXISTS dependencies_from ON dependencies (project_id, from_package_id);
+CREATE INDEX IF NOT EXISTS dependencies_on ON dependencies (project_id, on_package_id);
+CREATE INDEX IF NOT EXISTS installations_package ON installations (project_id, package_id);
+CREATE INDEX IF NOT EXISTS installations_path ON installations (path);
+";
+
+/// Tables with rows of projects, other than `projects`.
+static PROJECT_TABLES: &[&str] = &[
+    "packages",
+    "sources",
+    "checksums",
+    "derivations",
+    "dependencies",
+    "installations",
+];
+
+/// Which project a lockfile is exported as. Exporting the same project again replaces it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Project<'a> {
+    /// Where the project is, e.g. its root directory.
+    pub path: &'a str,
+    /// A hash of the lockfile, from [`lockfile_hash`], so that versions of it can be kept apart.
+    pub lockfile_hash: &'a str,
+    /// Keep the other lockfiles already exported for the same path, to track its history.
+    /// Otherwise they are replaced too. Rows of other paths are always kept.
+    pub keep_other_lockfiles: bool,
+}
+
+/// An SRI hash of the lockfile contents, like "sha256-…".
+pub fn lockfile_hash(contents: &str) -> String {
+    chaste_types::Integrity::from(contents).to_string()
+}
+
+fn quote(value: Option<&str>) -> String {
+    match value {
+        Some(value) => format!("'{}'", value.replace('\'', "''")),
+        None => "NULL".to_string(),
+    }
+}
+
+fn kind_name(kind: DependencyKind) -> &'static str {
+    match kind {
+        DependencyKind::Dependency => "prod",
+        DependencyKind::DevDependency => "dev",
+        DependencyKind::OptionalDependency => "optional",
+        DependencyKind::PeerDependency => "peer",
+        DependencyKind::OptionalPeerDependency => "optional peer",
+        // Kinds added to chaste-types later are not mistaken for any of these.
+        _ => "other",
+    }
+}
+
+/// Insert the rows of one project, with the id of the project prepended to the values.
+fn insert(sql: &mut String, table: &str, columns: &str, values: &[String]) {
+    sql.push_str(&format!(
+        "INSERT INTO {table} (project_id, {columns}) SELECT id, {} FROM current_project;\n",
+        values.join(", ")
+    ));
+}
+
+/// Statements that create the tables if needed, and put the lockfile in them
+/// in a transaction, replacing the project if it was already exported.
+pub fn to_sql<P: ProviderMeta>(chastefile: &Chastefile<P>, project: &Project) -> String {
+    let mut sql = String::from(SCHEMA);
+    sql.push_str("BEGIN;\n");
+    let (path, hash) = (
+        quote(Some(project.path)),
+        quote(Some(project.lockfile_hash)),
+    );
+    let selected =
+        format!("SELECT id FROM projects WHERE path = {path} AND lockfile_hash = {hash}");
+    let replaced = match project.keep_other_lockfiles {
+        true => selected.clone(),
+        false => format!("SELECT id FROM projects WHERE path = {path}"),
+    };
+    for table in PROJECT_TABLES {
+        sql.push_str(&format!(
+            "DELETE FROM {table} WHERE project_id IN ({replaced});\n"
+        ));
+    }
+    sql.push_str(&format!(
+        "DELETE FROM projects WHERE id IN ({replaced});\n\
+         INSERT INTO projects (path, lockfile_hash, implementation) VALUES ({path}, {hash}, {});\n\
+         CREATE TEMP TABLE current_project AS {selected};\n",
+        quote(Some(chastefile.meta().provider_name())),
+    ));
+
+    let mut packages: Vec<PackageID> = chastefile
+        .packages_with_ids()
+        .into_iter()
+        .map(|(pid, _)| pid)
+        .collect();
+    packages.sort_by_cached_key(|&pid| {
+        let package = chastefile.package(pid);
+        (
+            package.name().map(|n| n.to_string()),
+            package.version().cloned(),
+            chastefile
+                .package_installations(pid)
+                .first()
+                .map(|i| i.path().as_ref().to_string()),
+        )
+    });
+    let ids: HashMap<PackageID, String> = packages
+        .iter()
+        .enumerate()
+        .map(|(i, &pid)| (pid, i.to_string()))
+        .collect();
+
+    for &pid in &packages {
+        let package = chastefile.package(pid);
+        let id = ids[&pid].clone();
+        insert(
+            &mut sql,
+            "packages",
+            "id, name, version, is_root, is_workspace_member",
+            &[
+                id.clone(),
+                quote(package.name().map(|n| n.as_ref())),
+                quote(package.version().map(|v| v.to_string()).as_deref()),
+                u8::from(pid == chastefile.root_package_id()).to_string(),
+                u8::from(chastefile.workspace_member_ids().contains(&pid)).to_string(),
+            ],
+        );
+        if let Some(source) = package.source() {
+            let (source_type, url) = match source {
+                PackageSource::Npm => ("npm", None),
+                PackageSource::TarballURL { url } => ("tarball", Some(url.as_str())),
+                PackageSource::Git { url } => ("git", Some(url.as_str())),
+                _ => ("other", None),
+            };
+            let values = [id.clone(), quote(Some(source_type)), quote(url)];
+            insert(&mut sql, "sources", "package_id, type, url", &values);
+        }
+        if let Some(checksums) = package.checksums() {
+            let checksums_type = match checksums {
+                Checksums::Tarball(_) => "tarball",
+                Checksums::RepackZip(_) => "repack_zip",
+            };
+            let integrity = checksums.integrity().to_string();
+            let values = [
+                id.clone(),
+                quote(Some(checksums_type)),
+                quote(Some(&integrity)),
+            ];
+            insert(
+                &mut sql,
+                "checksums",
+                "package_id, type, integrity",
+                &values,
+            );
+        }
+        if let Some(derivation) = package.derivation_meta() {
+            let (derivation_type, patch) = match derivation.derivation() {
+                PackageDerivation::Patch(patch) => ("patch", Some(patch)),
+                _ => ("other", None),
+            };
+            let values = [
+                id.clone(),
+                ids[&derivation.derived_from()].clone(),
+                quote(Some(derivation_type)),
+                quote(patch.map(|p| p.path())),
+                quote(
+                    patch
+                        .and_then(|p| p.integrity())
+                        .map(|i| i.to_string())
+                        .as_deref(),
+                ),
+            ];
+            insert(
+                &mut sql,
+                "derivations",
+                "package_id, derived_from, type, patch_path, patch_integrity",
+                &values,
+            );
+        }
+        for installation in chastefile.package_installations(pid) {
+            let values = [id.clone(), quote(Some(installation.path().as_ref()))];
+            insert(&mut sql, "installations", "package_id, path", &values);
+        }
+        for dependency in chastefile.package_dependencies(pid) {
+            let values = [
+                id.clone(),
+                ids[&dependency.on].clone(),
+                quote(Some(kind_name(dependency.kind))),
+                quote(dependency.svs().map(|s| s.as_ref())),
+                quote(dependency.alias_name().map(|a| a.to_string()).as_deref()),
+            ];
+            insert(
+                &mut sql,
+                "dependencies",
+                "from_package_id, on_package_id, kind, specifier, alias",
+                &values,
+            );
+        }
+    }
+
+    sql.push_str("DROP TABLE current_project;\nCOMMIT;\n");
+    sql
+}
+
+#[cfg(test)]
+mod tests {
+    use chaste_types::{
+        ChastefileBuilder, Checksums, DependencyBuilder, DependencyKind, InstallationBuilder,
+        ModulePath, PackageBuilder, PackageName, PackageSource,
+    };
+
+    use super::{lockfile_hash, to_sql, Project};
+    use crate::error::Result;
+
+    #[test]
+    fn sql_rows() -> Result<()> {
+        let mut chastefile = ChastefileBuilder::new(());
+        let root_pid = chastefile.add_package(PackageBuilder::new(None, None).build()?)?;
+        chastefile.set_root_package_id(root_pid)?;
+        let mut package = PackageBuilder::new(
+            Some(PackageName::new("o'neil".to_string())?),
+            Some("1.0.0".to_string()),
+        );
+        package.source(PackageSource::Git {
+            url: "https://example.com/o'neil.git".to_string(),
+        });
+        package.checksums(Checksums::Tarball("sha512-AAAA".parse().unwrap()));
+        let pid = chastefile.add_package(package.build()?)?;
+        let path = ModulePath::new("node_modules/o'neil".to_string())?;
+        chastefile.add_package_installation(InstallationBuilder::new(pid, path).build()?);
+        chastefile.add_dependency(
+            DependencyBuilder::new(DependencyKind::DevDependency, root_pid, pid).build(),
+        );
+        let chastefile = chastefile.build()?;
+        let hash = lockfile_hash("{}");
+        let project = Project {
+            path: "/src/app",
+            lockfile_hash: &hash,
+            keep_other_lockfiles: false,
+        };
+        let sql = to_sql(&chastefile, &project);
+
+        assert!(sql.starts_with("CREATE TABLE IF NOT EXISTS projects ("));
+        assert!(sql.ends_with("DROP TABLE current_project;\nCOMMIT;\n"));
+        assert!(sql.contains(&format!(
+            "INSERT INTO projects (path, lockfile_hash, implementation) VALUES ('/src/app', '{hash}', '()');\n"
+        )));
+        for row in [
+            "INSERT INTO packages (project_id, id, name, version, is_root, is_workspace_member) SELECT id, 0, NULL, NULL, 1, 0 FROM current_project;\n",
+            "INSERT INTO packages (project_id, id, name, version, is_root, is_workspace_member) SELECT id, 1, 'o''neil', '1.0.0', 0, 0 FROM current_project;\n",
+            "INSERT INTO sources (project_id, package_id, type, url) SELECT id, 1, 'git', 'https://example.com/o''neil.git' FROM current_project;\n",
+            "INSERT INTO checksums (project_id, package_id, type, integrity) SELECT id, 1, 'tarball', 'sha512-AAAA' FROM current_project;\n",
+            "INSERT INTO installations (project_id, package_id, path) SELECT id, 1, 'node_modules/o''neil' FROM current_project;\n",
+            "INSERT INTO dependencies (project_id, from_package_id, on_package_id, kind, specifier, alias) SELECT id, 0, 1, 'dev', NULL, NULL FROM current_project;\n",
+        ] {
+            assert!(sql.contains(row), "{row}");
+        }
+        assert!(sql.contains(
+            "DELETE FROM projects WHERE id IN (SELECT id FROM projects WHERE path = '/src/app');\n"
+        ));
+
+        let appended = to_sql(
+            &chastefile,
+            &Project {
+                keep_other_lockfiles: true,
+                ..project
+            },
+        );
+        assert!(appended.contains(&format!(
+            "DELETE FROM projects WHERE id IN (SELECT id FROM projects WHERE path = '/src/app' AND lockfile_hash = '{hash}');\n"
+        )));
+        Ok(())
+    }
+}
@@ -8,6 +8,7 @@ use std::io;
 use std::path::{Path, PathBuf};
 
 use arbitrary::Arbitrary;
+use chaste_types::FileSource;
 use libfuzzer_sys::fuzz_target;
 
 #[derive(Debug)]
@@ -41,21 +42,36 @@ impl Arbitrary<'_> for ArbitraryIoErrorKind {
     }
 }
 
+#[derive(Debug, Arbitrary)]
+struct Files(HashMap<PathBuf, Result<String, ArbitraryIoErrorKind>>);
+
+impl FileSource for Files {
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        self.0
+            .get(path)
+            .map(|o| match o {
+                Ok(s) => Ok(s.clone()),
+                Err(e) => Err(io::Error::new(e.0, NotAnError)),
+            })
+            .unwrap_or_else(|| Err(io::Error::new(io::ErrorKind::NotFound, NotAnError)))
+    }
+
+    fn walk_files(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(self
+            .0
+            .keys()
+            .filter(|p| p.starts_with(dir))
+            .cloned()
+            .collect())
+    }
+}
+
 #[derive(Debug, Arbitrary)]
 struct Data<'d> {
     lockfile: chaste_pnpm::types::Lockfile<'d>,
-    files: HashMap<PathBuf, Result<String, ArbitraryIoErrorKind>>,
+    files: Files,
 }
 
 fuzz_target!(|data: Data| {
-    let _ = chaste_pnpm::parse_arbitrary(data.lockfile, Path::new(""), &|p| {
-        data.files
-            .get(&p)
-            .map(|o| {
-                o.as_ref()
-                    .map(|s| s.clone())
-                    .map_err(|e| io::Error::new(e.0, NotAnError))
-            })
-            .unwrap_or_else(|| Err(io::Error::new(io::ErrorKind::NotFound, NotAnError)))
-    });
+    let _ = chaste_pnpm::parse_arbitrary(data.lockfile, Path::new(""), &data.files);
 });